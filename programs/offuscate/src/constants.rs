@@ -2,15 +2,280 @@
 //!
 //! Contains all constant values used across the program.
 
+use anchor_lang::prelude::*;
+
 /// Minimum delay before withdrawal can be claimed (in seconds)
 pub const MIN_DELAY_SECONDS: i64 = 30; // 30 seconds minimum
 
 /// Maximum delay for withdrawal (in seconds)
 pub const MAX_DELAY_SECONDS: i64 = 300; // 5 minutes maximum
 
+/// Window after `available_at` in which a withdrawal must be claimed (in seconds)
+/// Expired withdrawals must be reopened, which re-randomizes their delay
+pub const CLAIM_WINDOW_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
+
+/// Largest tip a pending withdrawal may offer to whoever executes its claim,
+/// in basis points of the amount
+pub const MAX_CLAIM_TIP_BPS: u64 = 100; // 1%
+
+/// Time after `available_at` after which anyone may void an unclaimed withdrawal
+/// and return its reserved amount to the pool (in seconds)
+pub const PENDING_VOID_TIMEOUT_SECONDS: i64 = 90 * 24 * 60 * 60; // 90 days
+
+/// Length of a shuffled withdrawal queue time bucket (in seconds)
+/// Queued withdrawals requested in the same bucket are released together
+pub const QUEUE_BUCKET_SECONDS: i64 = 300; // 5 minutes
+
+/// Slots between arming a queue shuffle and the slot whose hash seeds it
+pub const SHUFFLE_SEED_DELAY_SLOTS: u64 = 32;
+
+/// Maximum withdrawals in one queue bucket
+pub const MAX_QUEUE_ENTRIES: usize = 32;
+
+/// Number of pool vault shards (`["pool_vault", shard_index]`)
+/// Each shard books on its own `PoolShard` account, so transactions on
+/// different shards don't write-lock the same vault or pool account
+pub const VAULT_SHARD_COUNT: usize = 4;
+
+/// Paused epochs before users may self-rescue, until the authority sets its own delay
+pub const DEFAULT_RESCUE_DELAY_EPOCHS: u64 = 15; // ~1 month
+
+/// Bounds on the self-rescue delay the pool authority may set, once
+pub const MIN_RESCUE_DELAY_EPOCHS: u64 = 2;
+pub const MAX_RESCUE_DELAY_EPOCHS: u64 = 30;
+
+/// Basis-point denominator for fee settings
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Maximum length of an encrypted deposit memo (in bytes)
+pub const MAX_MEMO_LEN: usize = 128;
+
+/// Maximum size of an encrypted note announcement
+pub const MAX_NOTE_CIPHERTEXT_LEN: usize = 128;
+
+/// Maximum size of an auditor disclosure blob
+pub const MAX_DISCLOSURE_LEN: usize = 256;
+
+/// Maximum swap programs the pool authority can whitelist for swap claims
+pub const MAX_SWAP_PROGRAMS: usize = 4;
+
+/// Maximum depth of a commitment Merkle tree (2^20 leaves)
+/// Each tree picks its own depth up to this when it is created
+pub const COMMITMENT_TREE_DEPTH: usize = 20;
+
+/// Number of recent commitment tree roots accepted by withdrawals
+pub const ROOT_HISTORY_SIZE: usize = 30;
+
+/// Bytes per spent-leaf bitmap chunk (8192 leaves per chunk)
+pub const SPENT_BITMAP_BYTES: usize = 1024;
+
+/// Relayers the pool authority can allowlist for allowlist mode
+pub const MAX_ALLOWED_RELAYERS: usize = 16;
+
+/// Longest validity of a signed relay authorization
+/// Every authorization message carries a `valid_until` time at most this far
+/// ahead, so a captured signature cannot be held and used much later
+pub const MAX_AUTHORIZATION_SECONDS: i64 = 10 * 60; // 10 minutes
+
+/// Longest validity of a claim authorization bound to a durable nonce account
+/// Such a claim only lands in a transaction that advances that nonce, so it
+/// can be pre-signed offline well ahead of the claim
+pub const MAX_DURABLE_AUTHORIZATION_SECONDS: i64 = 14 * 24 * 60 * 60; // 14 days
+
+/// Time without use after which an escrow's owner may close it
+/// Longer than any authorization window, so no signed release is still
+/// in flight when the balance goes back to the owner
+pub const ESCROW_REFUND_DELAY_SECONDS: i64 = 30 * 24 * 60 * 60; // 30 days
+
+/// Longest lifetime of a relay session key
+pub const MAX_SESSION_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
+
+/// Session key permission bit: authorize relayed claims
+pub const SESSION_ALLOW_CLAIM: u8 = 1 << 0;
+
+/// Every session key permission bit currently defined
+pub const SESSION_ALLOWED_MASK: u8 = SESSION_ALLOW_CLAIM;
+
+/// Time after use before a nullifier PDA may be closed to reclaim its rent
+/// The spent-leaf bitmap keeps double-spend protection after closing
+pub const NULLIFIER_CLOSE_DELAY_SECONDS: i64 = 30 * 24 * 60 * 60; // 30 days
+
+/// Timelock before a refundable deposit can be reclaimed by its refund key
+pub const REFUND_TIMELOCK_SECONDS: i64 = 365 * 24 * 60 * 60; // 1 year
+
+/// Deposits of the same note class that must follow a note before it can be spent
+/// Stops a withdrawal straight into an anonymity set of one
+pub const MIN_ANONYMITY_DEPOSITS: usize = 8;
+
+/// Note classes, each with its own commitment tree: one per denomination
+/// plus one for hidden-amount notes
+pub const NOTE_CLASS_COUNT: usize = DENOMINATION_COUNT + 1;
+
+/// Class stored on the receipt membership tree, outside the note classes
+pub const RECEIPT_TREE_CLASS: u8 = NOTE_CLASS_COUNT as u8;
+
+/// Class stored on every per-mint SPL token note tree
+pub const TOKEN_TREE_CLASS: u8 = NOTE_CLASS_COUNT as u8 + 1;
+
+/// Default rounding for timestamps stored on nullifiers and commitment events
+/// Exact times would let observers line deposits up with withdrawals
+pub const DEFAULT_TIMESTAMP_GRANULARITY: i64 = 24 * 60 * 60; // 1 day
+
+/// Note format version written by deposits
+/// Version 0 leaves are hash(commitment || amount) and must be migrated.
+/// Version 1 hashes untagged preimages; it stays spendable until the
+/// tree's legacy window closes, after which it too must be migrated.
+pub const NOTE_VERSION: u8 = 2;
+
+/// Domain tag for sha256 note commitments from note version 2
+pub const COMMITMENT_DOMAIN: &[u8] = b"offuscate:commitment:v2";
+
+/// Domain tag for sha256 commitments of notes owned by a signing key
+/// (ed25519 or secp256k1 owner, delegated), which only signed spends accept
+pub const OWNED_COMMITMENT_DOMAIN: &[u8] = b"offuscate:owned-commitment:v2";
+
+/// Domain tag for the statement a spend intent commits to
+pub const SPEND_INTENT_DOMAIN: &[u8] = b"offuscate:spend-intent:v2";
+
+/// Slots a spend intent must wait before the spend it names can land
+/// Longer than one leader's run, so whoever first sees the revealed note
+/// cannot post and use an intent of their own before the original spend lands
+pub const SPEND_INTENT_DELAY_SLOTS: u64 = 32;
+
+/// Domain tag for tree leaves from note version 2
+pub const LEAF_DOMAIN: &[u8] = b"offuscate:leaf:v2";
+
+/// Domain tag for the inner commitment of hidden-amount notes from note version 2
+pub const HIDDEN_NOTE_DOMAIN: &[u8] = b"offuscate:hidden-note:v2";
+
+/// Domain tag binding a hidden-amount note to its value commitment from note version 2
+pub const HIDDEN_COMMITMENT_DOMAIN: &[u8] = b"offuscate:hidden-commitment:v2";
+
+/// Domain tag for the inner commitment of SPL token notes
+pub const TOKEN_NOTE_DOMAIN: &[u8] = b"offuscate:token-note:v2";
+
+/// Domain tag binding an SPL token note to its mint and amount
+pub const TOKEN_COMMITMENT_DOMAIN: &[u8] = b"offuscate:token-commitment:v2";
+
+/// Receipt format version written by new receipts
+/// Version 0 receipts hash an untagged preimage
+pub const RECEIPT_VERSION: u8 = 2;
+
+/// Domain tag for receipt commitments from receipt version 2
+pub const RECEIPT_DOMAIN: &[u8] = b"offuscate:receipt:v2";
+
+/// Domain tag for leaves of the receipt membership tree
+pub const RECEIPT_LEAF_DOMAIN: &[u8] = b"offuscate:receipt-leaf:v2";
+
+/// Receipt tree leaves only carry the payment day, so a membership proof
+/// narrows a receipt down to a day rather than an exact time
+pub const RECEIPT_TIME_BUCKET_SECONDS: i64 = 24 * 60 * 60; // 1 day
+
+/// Commitment scheme id: commitment = sha256(secret_hash || nullifier || amount)
+pub const NOTE_SCHEME_SHA256: u8 = 0;
+
+/// Commitment scheme id: hidden-amount note, the amount lives in a Pedersen commitment
+pub const NOTE_SCHEME_PEDERSEN: u8 = 1;
+
+/// Commitment scheme id: SPL token note, commitment = sha256(tag || mint || amount || inner)
+pub const NOTE_SCHEME_TOKEN: u8 = 4;
+
+/// Pedersen value base G (compressed Ristretto basepoint)
+pub const PEDERSEN_G: [u8; 32] = [
+    226, 242, 174, 10, 106, 188, 78, 113, 168, 132, 169, 97, 197, 0, 81, 95,
+    88, 227, 11, 106, 165, 130, 221, 141, 182, 166, 89, 69, 224, 141, 45, 118,
+];
+
+/// Pedersen blinding base H, as used by the ZK ElGamal proof program
+pub const PEDERSEN_H: [u8; 32] = [
+    140, 146, 64, 180, 86, 169, 230, 220, 101, 195, 119, 161, 4, 141, 116, 95,
+    148, 160, 140, 219, 127, 68, 203, 205, 123, 70, 243, 64, 72, 135, 17, 52,
+];
+
+/// Number of churn vaults used for internal mixing
+pub const CHURN_VAULT_COUNT: u8 = 3;
+
+/// Number of standardized denominations
+pub const DENOMINATION_COUNT: usize = 6;
+
 /// Allowed withdrawal amounts in lamports (standardized to break amount correlation)
-pub const ALLOWED_AMOUNTS: [u64; 3] = [
-    100_000_000,   // 0.1 SOL
-    500_000_000,   // 0.5 SOL
-    1_000_000_000, // 1.0 SOL
+/// Each denomination keeps its own anonymity counters in the pool
+pub const ALLOWED_AMOUNTS: [u64; DENOMINATION_COUNT] = [
+    10_000_000,     // 0.01 SOL
+    100_000_000,    // 0.1 SOL
+    500_000_000,    // 0.5 SOL
+    1_000_000_000,  // 1.0 SOL
+    5_000_000_000,  // 5.0 SOL
+    10_000_000_000, // 10.0 SOL
 ];
+
+/// Maximum (recipient, pending) pairs in one batch claim
+/// Sized for v0 transactions that load the accounts from a lookup table
+pub const MAX_BATCH_CLAIMS: usize = 20;
+
+/// Maximum notes spent by one batched private withdrawal
+pub const MAX_BATCH_SPENDS: usize = 8;
+
+/// Maximum number of outputs a single claim can be split into
+pub const MAX_CLAIM_SPLITS: usize = 4;
+
+/// Minimum lamports per split output (the smallest denomination)
+pub const MIN_SPLIT_OUTPUT: u64 = ALLOWED_AMOUNTS[0];
+
+/// Maximum (employee, payout) pairs paid by one `run_payday`
+pub const MAX_PAYDAY_EMPLOYEES: usize = 16;
+
+/// Monthly paydays fall at most this far into a month, so every month,
+/// February included, has exactly one (in seconds)
+pub const MAX_MONTHLY_PAYDAY_OFFSET: i64 = 28 * 24 * 60 * 60; // 28 days
+
+/// Pyth pull-oracle receiver program, owner of `PriceUpdateV2` accounts
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Oldest price a USD salary claim accepts (in seconds)
+pub const MAX_PRICE_AGE_SECONDS: i64 = 60;
+
+/// Widest price confidence interval a USD salary claim accepts, in basis
+/// points of the price
+pub const MAX_PRICE_CONF_BPS: u64 = 100; // 1%
+
+/// Employees per batch roster page
+/// Keeps a page well under the 10KB an account can be created with
+pub const ROSTER_PAGE_SIZE: usize = 128;
+
+/// Pending invites expire this long after creation (in seconds)
+pub const INVITE_EXPIRY_SECONDS: i64 = 30 * 24 * 60 * 60; // 30 days
+
+/// Largest per-job tip a batch may pay keeper bots that crank it
+pub const MAX_CRANK_TIP: u64 = 1_000_000; // 0.001 SOL
+
+/// Largest share of the salary a crank pays out that its tip may take,
+/// in basis points, so frequent tiny payouts cannot drain the vault in tips
+pub const MAX_SALARY_CRANK_TIP_BPS: u64 = 100; // 1%
+
+/// Maximum invites expired by one `crank_expire_invites`
+pub const MAX_CRANK_INVITES: usize = 16;
+
+/// Maximum scheduled rate changes pending on one employee
+pub const MAX_RATE_CHANGES: usize = 4;
+
+/// Maximum recipients an employee's claims can be split between
+pub const MAX_PAYOUT_SPLITS: usize = 3;
+
+/// Longest severance `terminate_employee` pays, in days of salary
+pub const MAX_SEVERANCE_DAYS: u16 = 730; // 2 years
+
+/// Claims kept in an employee's on-account claim history
+pub const CLAIM_HISTORY_LEN: usize = 12;
+
+/// Longest minimum interval a batch may enforce between payouts to an employee
+pub const MAX_MIN_CLAIM_INTERVAL: i64 = 30 * 24 * 60 * 60; // 30 days
+
+/// Most a campaign's deadline may be pushed back, over all extensions
+pub const MAX_DEADLINE_EXTENSION: i64 = 90 * 24 * 60 * 60; // 90 days
+
+/// Campaigns listed per campaign registry page
+pub const CAMPAIGN_REGISTRY_PAGE_SIZE: usize = 128;
+
+/// Wait between a destination owner's confidential withdraw request and the payout
+pub const CONFIDENTIAL_WITHDRAW_DELAY_SECONDS: i64 = 24 * 60 * 60; // 1 day
@@ -37,11 +37,19 @@ pub enum ErrorCode {
     MetaAddressTooLong,
     #[msg("Ephemeral public key too long (max 64 chars)")]
     EphemeralKeyTooLong,
+    #[msg("Goal cannot be lowered once the campaign has donations")]
+    GoalDecreaseAfterDonations,
+    #[msg("New deadline must be later, and at most 90 days past the original")]
+    InvalidDeadlineExtension,
+    #[msg("Campaign is already in the registry")]
+    CampaignAlreadyRegistered,
+    #[msg("Registry page does not hold this campaign index")]
+    InvalidRegistryPage,
 
     // ============================================
     // Privacy Pool errors
     // ============================================
-    #[msg("Invalid withdrawal amount. Must be 0.1, 0.5, or 1 SOL")]
+    #[msg("Invalid withdrawal amount. Must be 0.01, 0.1, 0.5, 1, 5, or 10 SOL")]
     InvalidWithdrawAmount,
     #[msg("Insufficient funds in privacy pool")]
     InsufficientPoolFunds,
@@ -49,6 +57,110 @@ pub enum ErrorCode {
     WithdrawNotReady,
     #[msg("Withdrawal already claimed")]
     AlreadyClaimed,
+    #[msg("Withdrawal claim window has expired - reopen it first")]
+    WithdrawExpired,
+    #[msg("Withdrawal claim window has not expired")]
+    WithdrawNotExpired,
+    #[msg("Withdrawal was voided after timeout")]
+    WithdrawVoided,
+    #[msg("Withdrawal has not been abandoned long enough to void")]
+    WithdrawNotAbandoned,
+    #[msg("Withdrawal is queued and can only be released by the queue crank")]
+    WithdrawQueued,
+    #[msg("Claim tip must be non-zero and at most MAX_CLAIM_TIP_BPS of the amount")]
+    InvalidClaimTip,
+    #[msg("Withdrawal does not offer a tip for permissionless claims")]
+    ClaimTipNotOffered,
+    #[msg("Pool outflow limit reached for this epoch - claims resume next epoch")]
+    EpochOutflowLimitReached,
+    #[msg("No dust to sweep from the pool vault")]
+    NoDustToSweep,
+    #[msg("Account is not one of the pool vault shards")]
+    InvalidVaultShard,
+    #[msg("Reconciliation needs every vault shard, pool shard and churn vault")]
+    IncompleteVaultSet,
+    #[msg("Pending withdrawal is reserved on a different pool shard")]
+    WrongPoolShard,
+    #[msg("The pre-shard pool vault holds no lamports")]
+    LegacyVaultEmpty,
+    #[msg("No pool authority transfer is pending for this signer")]
+    NotPendingAuthority,
+    #[msg("Encrypted memo must be 1 to 128 bytes")]
+    InvalidMemoLength,
+    #[msg("Pool only accepts deposits of 0.01, 0.1, 0.5, 1, 5, or 10 SOL")]
+    NonStandardDeposit,
+    #[msg("Pool is paused")]
+    PoolPaused,
+    #[msg("Self-rescue is only available after the pool has been paused for the rescue delay")]
+    RescueNotAvailable,
+    #[msg("Rescue delay can only be set once, and not while the pool is paused")]
+    RescueDelayLocked,
+    #[msg("Rescue delay must be between 2 and 30 epochs")]
+    InvalidRescueDelay,
+    #[msg("Pending withdrawal already uses the current layout")]
+    PendingWithdrawCurrent,
+    #[msg("Privacy pool already uses the current layout")]
+    PoolAccountCurrent,
+    #[msg("Account is not the privacy pool")]
+    InvalidPoolAccount,
+    #[msg("Pending withdrawal must be claimed or voided before it can be closed")]
+    WithdrawNotSettled,
+
+    // ============================================
+    // Epoch snapshot errors
+    // ============================================
+    #[msg("Epoch snapshot cannot be created for a future epoch")]
+    InvalidSnapshotEpoch,
+    #[msg("Epoch has not ended yet")]
+    EpochNotEnded,
+    #[msg("Epoch snapshot already finalized")]
+    SnapshotAlreadyFinalized,
+    #[msg("Epoch statistics are no longer retained by the pool")]
+    SnapshotStatsUnavailable,
+
+    // ============================================
+    // Token claim errors
+    // ============================================
+    #[msg("Swap program is not whitelisted by the pool")]
+    SwapProgramNotAllowed,
+    #[msg("Invalid swap program slot")]
+    InvalidSwapProgramIndex,
+    #[msg("Swap returned less than the minimum output amount")]
+    SwapOutputTooLow,
+
+    // ============================================
+    // Withdrawal queue errors
+    // ============================================
+    #[msg("Queue bucket must be the current or a future bucket")]
+    InvalidQueueBucket,
+    #[msg("Queue bucket is closed for new withdrawals")]
+    QueueBucketClosed,
+    #[msg("Queue bucket is still open")]
+    QueueBucketOpen,
+    #[msg("Withdrawal queue is full")]
+    QueueFull,
+    #[msg("Withdrawal queue has already been shuffled")]
+    QueueAlreadyShuffled,
+    #[msg("Withdrawal queue shuffle is already armed")]
+    QueueShuffleArmed,
+    #[msg("Withdrawal queue shuffle is not armed, or its seed slot has expired")]
+    QueueShuffleNotArmed,
+    #[msg("Withdrawal queue shuffle seed slot has not passed yet")]
+    QueueSeedNotReady,
+    #[msg("Withdrawal queue has not been shuffled yet")]
+    QueueNotShuffled,
+    #[msg("All queued withdrawals have been released")]
+    QueueExhausted,
+    #[msg("Pending withdrawal is not next in the queue")]
+    QueueEntryMismatch,
+    #[msg("Claim split too large - max 4 outputs")]
+    ClaimSplitTooLarge,
+    #[msg("Claim split amounts must match writable remaining accounts")]
+    ClaimSplitAccountMismatch,
+    #[msg("Claim split output below minimum (0.01 SOL)")]
+    ClaimSplitOutputTooSmall,
+    #[msg("Claim split amounts must sum to the pending amount")]
+    ClaimSplitSumMismatch,
 
     // ============================================
     // Batch withdrawal errors
@@ -57,8 +169,18 @@ pub enum ErrorCode {
     BatchTooSmall,
     #[msg("Batch accounts must be pairs (recipient + pending)")]
     BatchInvalidPairs,
-    #[msg("Batch too large - max 5 withdrawals (10 accounts)")]
+    #[msg("Batch too large - max 20 withdrawals (40 accounts)")]
     BatchTooLarge,
+    #[msg("Batch pending account is not owned by this program")]
+    BatchInvalidPendingOwner,
+    #[msg("Batch pending account is not a PendingWithdraw")]
+    BatchInvalidPendingDiscriminator,
+    #[msg("Batch pending account is not the recipient's pending PDA")]
+    BatchInvalidPendingAddress,
+    #[msg("Relayed batch accounts must be (recipient, pending, relay nonce) triples")]
+    BatchInvalidTriples,
+    #[msg("Batch relay nonce account is not the recipient's relay nonce PDA")]
+    BatchInvalidRelayNonce,
 
     // ============================================
     // Churn errors
@@ -68,15 +190,63 @@ pub enum ErrorCode {
     #[msg("Insufficient funds in churn vault")]
     InsufficientChurnFunds,
 
+    // ============================================
+    // Confidential pool errors
+    // ============================================
+    #[msg("Mint does not have the confidential transfer extension")]
+    MintNotConfidential,
+    #[msg("Confidential withdraw request is not available yet")]
+    ConfidentialWithdrawTooEarly,
+
     // ============================================
     // Relayer errors
     // ============================================
     #[msg("Invalid ed25519 signature instruction")]
     InvalidSignatureInstruction,
+    #[msg("Invalid secp256k1 signature instruction")]
+    InvalidSecp256k1Instruction,
     #[msg("Signer does not match pending withdrawal recipient")]
     SignerMismatch,
-    #[msg("Invalid claim message format (expected 'claim:<pda>')")]
+    #[msg("Invalid claim message format (expected 'claim:<pda>:<nonce>:<valid_until>')")]
     InvalidClaimMessage,
+    #[msg("No secp256k1 signer is registered for relayed claims")]
+    NoSecp256k1Signer,
+    #[msg("Session expiry must be in the future and within the maximum session length")]
+    InvalidSessionExpiry,
+    #[msg("Session permissions must be a non-empty set of known operations")]
+    InvalidSessionPermissions,
+    #[msg("Session key has expired")]
+    SessionExpired,
+    #[msg("Session key is not allowed to authorize this operation")]
+    SessionNotPermitted,
+    #[msg("Session key has not expired yet")]
+    SessionStillActive,
+    #[msg("Signed message does not match the expected authorization")]
+    SignedMessageMismatch,
+    #[msg("Authorization has expired or is valid for longer than allowed")]
+    AuthorizationExpired,
+    #[msg("Transaction does not start by advancing the authorization's durable nonce account")]
+    DurableNonceNotAdvanced,
+    #[msg("Escrow balance too low")]
+    InsufficientEscrowFunds,
+    #[msg("Escrow was used too recently to be refunded")]
+    EscrowStillActive,
+    #[msg("Relayer fee exceeds the pool's maximum")]
+    RelayFeeTooHigh,
+    #[msg("Relayer is not on the pool's relayer allowlist")]
+    RelayerNotAllowed,
+    #[msg("Relayer allowlist account is required while allowlist mode is on")]
+    RelayerAllowlistMissing,
+    #[msg("Relayer allowlist slot out of range")]
+    InvalidRelayerSlot,
+    #[msg("Relayer reached its claim limit for this epoch")]
+    RelayerRateLimited,
+    #[msg("Relayer fee is below the currently required fee")]
+    RelayFeeTooLow,
+    #[msg("Congestion multiplier must be at least 10000 basis points")]
+    InvalidCongestionMultiplier,
+    #[msg("Fee must be at most 10000 basis points")]
+    InvalidFeeBps,
 
     // ============================================
     // Commitment-based privacy errors
@@ -87,6 +257,54 @@ pub enum ErrorCode {
     InvalidCommitmentProof,
     #[msg("Commitment has already been spent")]
     CommitmentAlreadySpent,
+    #[msg("Commitment tree is full")]
+    CommitmentTreeFull,
+    #[msg("Note belongs to a different commitment tree")]
+    WrongCommitmentTree,
+    #[msg("Invalid commitment tree class or depth")]
+    InvalidTreeParameters,
+    #[msg("Invalid Merkle proof for commitment")]
+    InvalidMerkleProof,
+    #[msg("Merkle root is not a recent commitment tree root")]
+    UnknownMerkleRoot,
+    #[msg("Unsupported note version")]
+    UnsupportedNoteVersion,
+    #[msg("Note is already at the current version")]
+    NoteAlreadyCurrent,
+    #[msg("Unsupported note commitment scheme")]
+    UnsupportedNoteScheme,
+    #[msg("Batched spend needs 1-8 notes with one nullifier account each")]
+    InvalidNoteBatch,
+    #[msg("Deposits into this note scheme are disabled")]
+    NoteSchemeDeprecated,
+    #[msg("Spent bitmap chunk does not cover this leaf")]
+    WrongSpentBitmap,
+    #[msg("Nullifier cannot be closed yet")]
+    NullifierCloseTooEarly,
+    #[msg("Refund timelock has not expired")]
+    RefundLocked,
+    #[msg("Not enough later deposits of this denomination to spend the note yet")]
+    AnonymitySetTooSmall,
+    #[msg("Note announcement ciphertext is empty or too long")]
+    InvalidNoteCiphertext,
+    #[msg("Disclosure blob is empty or too long (max 256 bytes)")]
+    InvalidDisclosureLength,
+    #[msg("Timestamp granularity cannot be negative")]
+    InvalidTimestampGranularity,
+    #[msg("Spend intent does not commit to this spend")]
+    SpendIntentMismatch,
+    #[msg("Spend intent is too recent; wait for it to mature")]
+    SpendIntentTooRecent,
+
+    // ============================================
+    // Hidden-amount note errors
+    // ============================================
+    #[msg("Invalid Pedersen commitment point")]
+    InvalidPedersenPoint,
+    #[msg("Pedersen opening proof is invalid")]
+    InvalidOpeningProof,
+    #[msg("Range proof is missing or does not cover the change commitment")]
+    InvalidRangeProof,
 
     // ============================================
     // Invite system errors
@@ -103,6 +321,10 @@ pub enum ErrorCode {
     InviteNotFound,
     #[msg("Invite has no salary configured - use accept_invite instead")]
     InviteNoSalaryConfigured,
+    #[msg("Invite has expired")]
+    InviteExpired,
+    #[msg("Too many invites for one crank")]
+    TooManyCrankInvites,
 
     // ============================================
     // Streaming payroll errors
@@ -115,6 +337,80 @@ pub enum ErrorCode {
     InvalidSalaryRate,
     #[msg("Employee already exists")]
     EmployeeAlreadyExists,
+    #[msg("Employee must be terminated first")]
+    EmployeeNotTerminated,
+    #[msg("Employee still has accrued salary to settle")]
+    EmployeeHasAccruedSalary,
+    #[msg("Batch still has employees; close them first")]
+    BatchHasEmployees,
+    #[msg("Batch is not active")]
+    BatchNotActive,
+    #[msg("No batch ownership transfer is pending for this signer")]
+    NotPendingBatchOwner,
+    #[msg("Role permissions must be a non-empty set of known permissions")]
+    InvalidBatchRole,
+    #[msg("USD-denominated salary needs a Pyth price update")]
+    PriceFeedRequired,
+    #[msg("Price account is not a verified Pyth update for the batch feed")]
+    InvalidPriceFeed,
+    #[msg("Price update is too old")]
+    StalePrice,
+    #[msg("Price confidence interval is too wide")]
+    PriceTooUncertain,
+    #[msg("Roster page does not hold this employee index")]
+    InvalidRosterPage,
+    #[msg("Crank tip exceeds the maximum")]
+    InvalidCrankTip,
+    #[msg("Scheduled rate changes must take effect in the future")]
+    InvalidRateChange,
+    #[msg("Too many scheduled rate changes")]
+    RateScheduleFull,
+    #[msg("Salary recipient must be a fresh address, not the employee wallet")]
+    StealthRecipientReused,
+    #[msg("Claim cap for this period reached")]
+    ClaimCapExceeded,
+    #[msg("Claim cap needs a positive period length")]
+    InvalidClaimCap,
+    #[msg("Invalid pay schedule period or payday offset")]
+    InvalidPaySchedule,
+    #[msg("Employee's allocated budget is exhausted")]
+    EmployeeBudgetExhausted,
+    #[msg("Payday accounts must be (employee, payout address) pairs")]
+    InvalidPaydayAccounts,
+    #[msg("Employee has not opted in to pushed salary payments")]
+    PushPaymentsNotEnabled,
+    #[msg("Employee is paid in a different currency")]
+    InvalidPayMint,
+    #[msg("Batch still has token vaults; close them first")]
+    BatchHasTokenVaults,
+    #[msg("Payout splits must name up to 3 recipients with weights totalling at most 100%")]
+    InvalidPayoutSplits,
+    #[msg("Acknowledged payout splits do not match the proposal")]
+    PayoutSplitsMismatch,
+    #[msg("Employee claims are split; claim with claim_salary")]
+    PayoutSplitsActive,
+    #[msg("Split recipient accounts must follow the employee's payout splits")]
+    InvalidSplitRecipients,
+    #[msg("Employee is on hold; lift the hold first")]
+    EmployeeOnHold,
+    #[msg("Employee is not on hold")]
+    EmployeeNotOnHold,
+    #[msg("Severance must be between 1 and 730 days")]
+    InvalidSeverance,
+    #[msg("Payroll is paused")]
+    PayrollPaused,
+    #[msg("No payroll authority transfer is pending for this signer")]
+    NotPendingPayrollAuthority,
+    #[msg("Minimum claim interval must be between 0 and 30 days")]
+    InvalidClaimInterval,
+    #[msg("Salary was claimed too recently; wait for the batch's minimum claim interval")]
+    ClaimTooSoon,
+    #[msg("Account holds an unknown status, pay schedule or currency code")]
+    InvalidStateCode,
+    #[msg("Payroll account already uses the current layout")]
+    PayrollAccountCurrent,
+    #[msg("Account is not a Borsh-layout batch or employee at its own address")]
+    InvalidLegacyPayrollAccount,
 
     // ============================================
     // Anonymous receipt errors
@@ -0,0 +1,223 @@
+//! Events emitted by the Offuscate program
+//!
+//! Structured alternatives to log strings for indexers and relayers.
+
+use anchor_lang::prelude::*;
+
+/// Per-item status of a batch claim entry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchClaimStatus {
+    Claimed,
+    RecipientMismatch,
+    AlreadyClaimed,
+    NotReady,
+    Expired,
+    Voided,
+    Queued,
+    InsufficientFunds,
+    OutflowLimited,
+    SignatureMissing,  // relayed batches: no valid claim signature by the recipient
+    WrongShard,        // reserved on a different pool shard than the batch pays from
+}
+
+/// What a relay job asked the relayer to submit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelayJobKind {
+    Claim,
+    Withdraw,
+    Request,
+    Deposit,
+    Donation,
+    SessionKey,
+    CampaignWithdraw,
+}
+
+/// What happened to a relay job on-chain
+///
+/// A failing transaction emits nothing, so only batch entries can be skipped.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelayJobOutcome {
+    Settled,
+    Skipped,
+}
+
+/// Emitted by every relayed handler, once per job
+///
+/// `job_id` is the sha256 of the signed authorization message, which the
+/// relayer already holds for each job in its queue.
+#[event]
+pub struct RelayJobCompleted {
+    pub job_id: [u8; 32],
+    pub relayer: Pubkey,
+    pub kind: RelayJobKind,
+    pub fee: u64,
+    pub outcome: RelayJobOutcome,
+    pub timestamp: i64,
+}
+
+/// Outcome of a single (recipient, pending) pair in a batch claim
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchClaimItem {
+    pub index: u8,
+    pub amount: u64,
+    pub status: BatchClaimStatus,
+}
+
+/// Emitted once per `batch_claim_withdraw` or `batch_claim_relayed` with per-item results
+#[event]
+pub struct BatchClaimCompleted {
+    pub results: Vec<BatchClaimItem>,
+    pub success_count: u8,
+    pub total_claimed: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `reconcile_pool`: on-chain balances versus the pool's books
+#[event]
+pub struct PoolReconciled {
+    pub vault_balance: u64,
+    pub churned_balance: u64,
+    pub expected_balance: u64,
+    /// (vault + churned) - expected; positive is surplus (rent, dust), negative is a shortfall
+    pub discrepancy: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a deposit carries an encrypted memo
+///
+/// The ciphertext is opaque to the program; only the intended recipient's
+/// view key can decrypt it. No depositor identity is included.
+#[event]
+pub struct DepositMemo {
+    /// Set for commitment deposits so the recipient can match memo and note
+    pub commitment: Option<[u8; 32]>,
+    pub ciphertext: Vec<u8>,
+    pub timestamp: i64,
+}
+
+/// Emitted for every leaf appended to the commitment tree
+///
+/// Clients rebuild the tree from these to compute Merkle paths.
+#[event]
+pub struct CommitmentInserted {
+    pub tree_class: u8,
+    pub note_version: u8,
+    pub leaf: [u8; 32],
+    pub leaf_index: u64,
+    pub root: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// Emitted when a note is created for someone else
+///
+/// Recipients scan these with their view key to discover notes they can spend.
+#[event]
+pub struct NoteAnnounced {
+    pub tree_class: u8,
+    pub leaf_index: u64,
+    pub ephemeral_pubkey: [u8; 32],
+    pub view_tag: u8,
+    pub ciphertext: Vec<u8>,
+    pub timestamp: i64,
+}
+
+/// Emitted when a note is exited through the self-rescue path
+///
+/// The note is revealed in full, so this spend is linkable to its deposit.
+#[event]
+pub struct NoteRescued {
+    pub tree_class: u8,
+    pub leaf_index: u64,
+    pub nullifier: [u8; 32],
+    pub amount: u64,
+    pub recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted for every leaf appended to a per-mint token note tree
+///
+/// Token trees share a class, so the mint tells clients which tree to rebuild.
+#[event]
+pub struct TokenCommitmentInserted {
+    pub mint: Pubkey,
+    pub leaf: [u8; 32],
+    pub leaf_index: u64,
+    pub root: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// Emitted by salary claims when accrual outpaces funding
+///
+/// `claimable` is what the employee's budget share and the batch vault
+/// could pay; the rest of `accrued` stays owed.
+#[event]
+pub struct SalaryUnderfunded {
+    pub employee: Pubkey,
+    pub batch: Pubkey,
+    pub accrued: u64,
+    pub claimable: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when an employee rotates its stealth meta-address
+///
+/// The employer's scanner switches to `stealth_address` from here on.
+#[event]
+pub struct EmployeeStealthUpdated {
+    pub employee: Pubkey,
+    pub batch: Pubkey,
+    pub stealth_address: String,
+    pub timestamp: i64,
+}
+
+/// Emitted when the owner puts an employee on hold over a dispute
+#[event]
+pub struct EmployeeHoldPlaced {
+    pub employee: Pubkey,
+    pub batch: Pubkey,
+    pub reason_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// Emitted when a hold is lifted; `released` is the salary held back
+/// during the dispute that is now claimable again
+#[event]
+pub struct EmployeeHoldLifted {
+    pub employee: Pubkey,
+    pub batch: Pubkey,
+    pub reason_hash: [u8; 32],
+    pub released: u64,
+    pub held_seconds: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted for every leaf appended to the receipt membership tree
+///
+/// Employees rebuild the tree from these to compute membership paths.
+#[event]
+pub struct ReceiptLeafInserted {
+    pub leaf: [u8; 32],
+    pub leaf_index: u64,
+    pub root: [u8; 32],
+}
+
+/// Emitted when a holder proves a receipt from `employer` within a time range
+#[event]
+pub struct ReceiptMembershipProven {
+    pub holder: Pubkey,
+    pub employer: Pubkey,
+    pub range_start: i64,
+    pub range_end: i64,
+    pub root: [u8; 32],
+}
+
+/// Emitted when the owner edits a campaign's title, description or goal
+#[event]
+pub struct CampaignUpdated {
+    pub campaign: Pubkey,
+    pub title: String,
+    pub description: String,
+    pub previous_goal: u64,
+    pub goal: u64,
+    pub timestamp: i64,
+}
@@ -4,11 +4,19 @@
 
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use crate::constants::MAX_DEADLINE_EXTENSION;
 use crate::errors::ErrorCode;
-use crate::state::CampaignStatus;
-use crate::instructions::{CreateCampaign, Donate, Withdraw, CloseCampaign};
+use crate::events::CampaignUpdated;
+use crate::state::{Campaign, CampaignRegistry, CampaignRegistryPage, CampaignStatus};
+use crate::instructions::{
+    CreateCampaign, CreateStealthCampaign, UpdateCampaign, Donate, Withdraw, CloseCampaign, FinalizeCampaign,
+    CloseCampaignAccount, InitCampaignRegistry, InitCampaignRegistryPage, RegisterCampaign,
+};
 
 /// Create a new campaign
+///
+/// Listed in the campaign registry when the registry and its current page
+/// are passed; otherwise anyone can list it later with `register_campaign`.
 pub fn create(
     ctx: Context<CreateCampaign>,
     campaign_id: String,
@@ -16,6 +24,55 @@ pub fn create(
     description: String,
     goal: u64,
     deadline: i64,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    init_campaign(&mut ctx.accounts.campaign, owner, campaign_id, title, description, goal, deadline)?;
+    if let (Some(registry), Some(registry_page)) = (&mut ctx.accounts.registry, &ctx.accounts.registry_page) {
+        register_in(registry, registry_page, &mut ctx.accounts.campaign)?;
+    }
+    let campaign = &mut ctx.accounts.campaign;
+    campaign.vault_bump = ctx.bumps.vault;
+    campaign.campaign_bump = ctx.bumps.campaign;
+
+    msg!("Campaign created: {}", campaign.title);
+    msg!("Vault PDA: {}", ctx.accounts.vault.key());
+
+    Ok(())
+}
+
+/// Create a campaign for `owner`, typically a stealth key, paid for by someone else
+pub fn create_stealth_owned(
+    ctx: Context<CreateStealthCampaign>,
+    campaign_id: String,
+    title: String,
+    description: String,
+    goal: u64,
+    deadline: i64,
+    owner: Pubkey,
+) -> Result<()> {
+    init_campaign(&mut ctx.accounts.campaign, owner, campaign_id, title, description, goal, deadline)?;
+    if let (Some(registry), Some(registry_page)) = (&mut ctx.accounts.registry, &ctx.accounts.registry_page) {
+        register_in(registry, registry_page, &mut ctx.accounts.campaign)?;
+    }
+    let campaign = &mut ctx.accounts.campaign;
+    campaign.vault_bump = ctx.bumps.vault;
+    campaign.campaign_bump = ctx.bumps.campaign;
+
+    msg!("Stealth-owned campaign created: {}", campaign.title);
+    msg!("Vault PDA: {}", ctx.accounts.vault.key());
+
+    Ok(())
+}
+
+/// Validate and fill a new campaign, all but its bumps
+fn init_campaign(
+    campaign: &mut Campaign,
+    owner: Pubkey,
+    campaign_id: String,
+    title: String,
+    description: String,
+    goal: u64,
+    deadline: i64,
 ) -> Result<()> {
     require!(campaign_id.len() <= 32, ErrorCode::CampaignIdTooLong);
     require!(title.len() <= 64, ErrorCode::TitleTooLong);
@@ -23,8 +80,7 @@ pub fn create(
     require!(goal > 0, ErrorCode::InvalidGoal);
     require!(deadline > Clock::get()?.unix_timestamp, ErrorCode::InvalidDeadline);
 
-    let campaign = &mut ctx.accounts.campaign;
-    campaign.owner = ctx.accounts.owner.key();
+    campaign.owner = owner;
     campaign.campaign_id = campaign_id;
     campaign.title = title;
     campaign.description = description;
@@ -34,14 +90,69 @@ pub fn create(
     campaign.deadline = deadline;
     campaign.status = CampaignStatus::Active;
     campaign.created_at = Clock::get()?.unix_timestamp;
-    campaign.vault_bump = ctx.bumps.vault;
-    campaign.campaign_bump = ctx.bumps.campaign;
     campaign.stealth_meta_address = String::new();
     campaign.stealth_donations = 0;
     campaign.stealth_total = 0;
+    campaign.deadline_extension = 0;
+    campaign.withdraw_nonce = 0;
+    campaign.registered = false;
+    campaign.registry_index = 0;
 
-    msg!("Campaign created: {}", campaign.title);
-    msg!("Vault PDA: {}", ctx.accounts.vault.key());
+    Ok(())
+}
+
+/// Edit a campaign's title, description and goal (owner only)
+///
+/// The goal may be raised at any time, but only lowered before the first
+/// donation, so donors never see the target they gave towards shrink.
+pub fn update(ctx: Context<UpdateCampaign>, title: String, description: String, goal: u64) -> Result<()> {
+    require!(title.len() <= 64, ErrorCode::TitleTooLong);
+    require!(description.len() <= 256, ErrorCode::DescriptionTooLong);
+    require!(goal > 0, ErrorCode::InvalidGoal);
+
+    let campaign = &mut ctx.accounts.campaign;
+    require!(campaign.status == CampaignStatus::Active, ErrorCode::CampaignNotActive);
+    require!(
+        goal >= campaign.goal || !campaign.has_donations(),
+        ErrorCode::GoalDecreaseAfterDonations
+    );
+
+    let previous_goal = campaign.goal;
+    campaign.title = title;
+    campaign.description = description;
+    campaign.goal = goal;
+
+    emit!(CampaignUpdated {
+        campaign: campaign.key(),
+        title: campaign.title.clone(),
+        description: campaign.description.clone(),
+        previous_goal,
+        goal,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Campaign updated: {}", campaign.title);
+
+    Ok(())
+}
+
+/// Push back the deadline of a running campaign (owner only)
+///
+/// Never shortens it, and all extensions together stay within
+/// MAX_DEADLINE_EXTENSION of the deadline the campaign was created with.
+pub fn extend_deadline(ctx: Context<UpdateCampaign>, new_deadline: i64) -> Result<()> {
+    let campaign = &mut ctx.accounts.campaign;
+    require!(campaign.status == CampaignStatus::Active, ErrorCode::CampaignNotActive);
+    require!(Clock::get()?.unix_timestamp < campaign.deadline, ErrorCode::CampaignEnded);
+
+    let added = new_deadline.saturating_sub(campaign.deadline);
+    let extension = campaign.deadline_extension.saturating_add(added);
+    require!(added > 0 && extension <= MAX_DEADLINE_EXTENSION, ErrorCode::InvalidDeadlineExtension);
+
+    campaign.deadline = new_deadline;
+    campaign.deadline_extension = extension;
+
+    msg!("Campaign deadline extended to {} (+{}s in total)", new_deadline, extension);
 
     Ok(())
 }
@@ -129,3 +240,136 @@ pub fn close(ctx: Context<CloseCampaign>) -> Result<()> {
 
     Ok(())
 }
+
+/// Settle a campaign once its deadline has passed, sweeping the vault to the owner
+///
+/// An Active campaign becomes Completed if it reached its goal and Closed
+/// otherwise; one already closed early keeps its status. Draining the vault
+/// closes it, so its rent comes back too.
+pub fn finalize(ctx: Context<FinalizeCampaign>) -> Result<()> {
+    let campaign = &mut ctx.accounts.campaign;
+    if campaign.status == CampaignStatus::Active {
+        require!(Clock::get()?.unix_timestamp >= campaign.deadline, ErrorCode::CampaignNotEnded);
+        campaign.status = if campaign.total_raised >= campaign.goal {
+            CampaignStatus::Completed
+        } else {
+            CampaignStatus::Closed
+        };
+    }
+
+    let swept = sweep_vault(
+        campaign,
+        &ctx.accounts.vault,
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program,
+    )?;
+
+    msg!("Campaign finalized, {} lamports swept to owner", swept);
+
+    Ok(())
+}
+
+/// Close a finalized campaign account, returning its rent to the owner
+///
+/// Anything sent to the vault since finalizing is swept first.
+pub fn close_account(ctx: Context<CloseCampaignAccount>) -> Result<()> {
+    let campaign = &ctx.accounts.campaign;
+    require!(campaign.status != CampaignStatus::Active, ErrorCode::CampaignNotEnded);
+
+    // Clear the registry slot, so a campaign re-created under the same id
+    // is listed once, under its new index
+    if campaign.registered {
+        let registry_page = ctx.accounts.registry_page.as_ref().ok_or(ErrorCode::InvalidRegistryPage)?;
+        registry_page.load_mut()?.record(campaign.registry_index, Pubkey::default())?;
+    }
+
+    let swept = sweep_vault(
+        campaign,
+        &ctx.accounts.vault,
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program,
+    )?;
+
+    msg!("Campaign account closed, {} lamports swept to owner", swept);
+
+    Ok(())
+}
+
+/// Move the whole vault balance to the owner
+fn sweep_vault<'info>(
+    campaign: &Campaign,
+    vault: &SystemAccount<'info>,
+    owner: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<u64> {
+    let remaining = vault.lamports();
+    if remaining == 0 {
+        return Ok(0);
+    }
+
+    let campaign_id = campaign.campaign_id.as_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[b"vault", campaign_id, &[campaign.vault_bump]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: vault.to_account_info(),
+                to: owner.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        remaining,
+    )?;
+
+    Ok(remaining)
+}
+
+/// Create the campaign registry
+pub fn init_registry(ctx: Context<InitCampaignRegistry>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.campaign_count = 0;
+    registry.bump = ctx.bumps.registry;
+
+    msg!("Campaign registry initialized");
+    Ok(())
+}
+
+/// Create campaign registry page `page`
+pub fn init_registry_page(ctx: Context<InitCampaignRegistryPage>, page: u32) -> Result<()> {
+    let mut registry_page = ctx.accounts.registry_page.load_init()?;
+    registry_page.page = page;
+    registry_page.bump = ctx.bumps.registry_page;
+
+    msg!("Campaign registry page {} initialized", page);
+    Ok(())
+}
+
+/// List a campaign created before the registry existed
+///
+/// New campaigns are registered when created with the registry accounts;
+/// anyone can register an older or unlisted one, and each is listed once.
+pub fn register(ctx: Context<RegisterCampaign>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    register_in(&mut accounts.registry, &accounts.registry_page, &mut accounts.campaign)
+}
+
+/// Give a campaign the next registry index and list it on its page
+fn register_in(
+    registry: &mut CampaignRegistry,
+    registry_page: &AccountLoader<CampaignRegistryPage>,
+    campaign: &mut Account<Campaign>,
+) -> Result<()> {
+    require!(!campaign.registered, ErrorCode::CampaignAlreadyRegistered);
+
+    let index = registry.campaign_count;
+    registry_page.load_mut()?.record(index, campaign.key())?;
+
+    campaign.registered = true;
+    campaign.registry_index = index;
+    registry.campaign_count = index.checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    msg!("Campaign {} registered with index {}", campaign.campaign_id, index);
+    Ok(())
+}
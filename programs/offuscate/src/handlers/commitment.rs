@@ -4,108 +4,1058 @@
 
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_lang::solana_program::hash::hash;
-use crate::constants::ALLOWED_AMOUNTS;
+use anchor_lang::solana_program::hash::hashv;
+use crate::constants::{
+    NOTE_VERSION, REFUND_TIMELOCK_SECONDS, MAX_NOTE_CIPHERTEXT_LEN, MAX_DISCLOSURE_LEN,
+    NOTE_SCHEME_SHA256, NOTE_SCHEME_PEDERSEN, MAX_BATCH_SPENDS, COMMITMENT_DOMAIN, OWNED_COMMITMENT_DOMAIN,
+    NOTE_CLASS_COUNT, COMMITMENT_TREE_DEPTH,
+};
 use crate::errors::ErrorCode;
-use crate::instructions::{PrivateDeposit, PrivateWithdraw};
+use crate::events::{CommitmentInserted, NoteAnnounced, NoteRescued};
+use crate::instructions::{
+    InitCommitmentTree, PrivateDeposit, PrivateWithdraw, PrivateWithdrawDelegated, RescueOwnedNote, PostSpendIntent, CancelSpendIntent,
+    WithdrawCommitmentPda, MigrateNote, InitSpentBitmap, CloseNullifier,
+    PrivateDepositRefundable, RefundNote, RegisterDisclosure, CloseDisclosure, TransferNote,
+    PrivateDonate, FundBatchPrivate, UpdateCommitmentConfig, PrivateWithdrawBatch, GetShieldedStats,
+};
+use crate::handlers::privacy_pool::post_memo;
+use crate::handlers::relayer::{
+    check_authorization_window, require_ed25519_signature, require_secp256k1_signature, rescue_message, withdraw_message,
+};
+use crate::state::{
+    BatchStatus, CampaignStatus, CommitmentTree, DelegatedSpend, MerkleProof, NoteAnnouncement, NoteHeader,
+    NoteOwnerKey, NoteSpend, NullifierPDA, OwnedNoteOpening, SpendIntent, SpentBitmap, ShieldedStats,
+};
 
-/// Private deposit with commitment
+/// Create the empty commitment tree for note class `class`
+///
+/// `depth` sizes the tree to the class's expected volume (2^depth notes).
+pub fn init_tree(ctx: Context<InitCommitmentTree>, class: u8, depth: u8) -> Result<()> {
+    require!(
+        (class as usize) < NOTE_CLASS_COUNT && depth > 0 && depth as usize <= COMMITMENT_TREE_DEPTH,
+        ErrorCode::InvalidTreeParameters
+    );
+
+    let mut tree = ctx.accounts.commitment_tree.load_init()?;
+    tree.initialize(class, depth, ctx.bumps.commitment_tree);
+
+    msg!("Commitment tree for class {} initialized (depth {})", class, depth);
+
+    Ok(())
+}
+
+/// Set how coarsely nullifier and commitment timestamps are stored (pool authority only)
+///
+/// 0 omits them entirely. The transaction's own block time stays public;
+/// this only limits what account state and events reveal.
+pub fn set_timestamp_granularity(ctx: Context<UpdateCommitmentConfig>, granularity: i64) -> Result<()> {
+    require!(granularity >= 0, ErrorCode::InvalidTimestampGranularity);
+
+    let mut tree = ctx.accounts.commitment_tree.load_mut()?;
+    tree.timestamp_granularity = granularity;
+
+    msg!("Timestamp granularity set to {} seconds", granularity);
+
+    Ok(())
+}
+
+/// Set until when version 1 notes can be spent directly (pool authority only)
+///
+/// After the deadline they can only be moved with `migrate_note`.
+pub fn set_legacy_spend_deadline(ctx: Context<UpdateCommitmentConfig>, deadline: i64) -> Result<()> {
+    let mut tree = ctx.accounts.commitment_tree.load_mut()?;
+    tree.legacy_spends_until = deadline;
+
+    msg!("Version 1 notes spendable until {}", deadline);
+
+    Ok(())
+}
+
+/// Private deposit with commitment, optionally with an encrypted memo and a
+/// note announcement for a third-party recipient
 pub fn deposit(
     ctx: Context<PrivateDeposit>,
     commitment: [u8; 32],
     amount: u64,
+    scheme_id: u8,
+    memo: Option<Vec<u8>>,
+    announcement: Option<NoteAnnouncement>,
 ) -> Result<()> {
+    let header = NoteHeader::with_scheme(scheme_id, amount)?;
+    let leaf_index = deposit_note(ctx.accounts, &header, commitment)?;
+
+    msg!("Private deposit: {} lamports (leaf {})", amount, leaf_index);
+
+    announce_note(&header, leaf_index, announcement)?;
+    post_memo(memo, Some(commitment))
+}
+
+/// Private deposit with a refund clause for `refund_authority`
+pub fn deposit_refundable(
+    ctx: Context<PrivateDepositRefundable>,
+    commitment: [u8; 32],
+    amount: u64,
+    refund_authority: Pubkey,
+    memo: Option<Vec<u8>>,
+    announcement: Option<NoteAnnouncement>,
+) -> Result<()> {
+    let header = NoteHeader::current(amount)?;
+    let leaf_index = deposit_note(&mut ctx.accounts.deposit, &header, commitment)?;
+    let unlock_at = Clock::get()?.unix_timestamp.saturating_add(REFUND_TIMELOCK_SECONDS);
+
+    let ticket = &mut ctx.accounts.refund_ticket;
+    ticket.refund_authority = refund_authority;
+    ticket.commitment = commitment;
+    ticket.leaf_index = leaf_index;
+    ticket.amount = amount;
+    ticket.unlock_at = unlock_at;
+    ticket.bump = ctx.bumps.refund_ticket;
+
+    msg!("Refundable private deposit: {} lamports (leaf {}, refundable from {})", amount, leaf_index, unlock_at);
+
+    announce_note(&header, leaf_index, announcement)?;
+    post_memo(memo, Some(commitment))
+}
+
+/// Reclaim an unspent refundable deposit after its timelock
+pub fn refund_note(ctx: Context<RefundNote>) -> Result<()> {
+    let ticket = &ctx.accounts.refund_ticket;
+    require!(Clock::get()?.unix_timestamp >= ticket.unlock_at, ErrorCode::RefundLocked);
+    let amount = ticket.amount;
+    let leaf_index = ticket.leaf_index;
+
+    let mut bitmap = ctx.accounts.spent_bitmap.load_mut()?;
     require!(
-        ALLOWED_AMOUNTS.contains(&amount),
-        ErrorCode::InvalidWithdrawAmount
+        bitmap.class as usize == NoteHeader::current(amount)?.class(),
+        ErrorCode::WrongSpentBitmap
     );
+    if bitmap.is_spent(leaf_index)? {
+        msg!("Note at leaf {} already spent; refund ticket closed", leaf_index);
+        return Ok(());
+    }
+    bitmap.mark_spent(leaf_index)?;
+    drop(bitmap);
+
+    let pool = &ctx.accounts.pool;
+    let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
 
     system_program::transfer(
-        CpiContext::new(
+        CpiContext::new_with_signer(
             ctx.accounts.system_program.to_account_info(),
             system_program::Transfer {
-                from: ctx.accounts.depositor.to_account_info(),
-                to: ctx.accounts.pool_vault.to_account_info(),
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.refund_authority.to_account_info(),
             },
+            signer_seeds,
         ),
         amount,
     )?;
 
-    let commitment_pda = &mut ctx.accounts.commitment_pda;
-    commitment_pda.commitment = commitment;
-    commitment_pda.amount = amount;
-    commitment_pda.timestamp = Clock::get()?.unix_timestamp;
-    commitment_pda.spent = false;
-    commitment_pda.bump = ctx.bumps.commitment_pda;
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_withdrawal(&ctx.accounts.pool, amount)?;
 
-    let pool = &mut ctx.accounts.pool;
-    pool.total_deposited = pool.total_deposited.checked_add(amount)
-        .ok_or(ErrorCode::Overflow)?;
-    pool.deposit_count = pool.deposit_count.checked_add(1)
-        .ok_or(ErrorCode::Overflow)?;
+    msg!("Refunded {} lamports (leaf {})", amount, leaf_index);
+
+    Ok(())
+}
+
+/// Post the intent of a bare-secret spend, `SpendIntent::statement` of it
+///
+/// The spend can use it once SPEND_INTENT_DELAY_SLOTS have passed.
+pub fn post_spend_intent(ctx: Context<PostSpendIntent>, statement: [u8; 32]) -> Result<()> {
+    let intent = &mut ctx.accounts.spend_intent;
+    intent.statement = statement;
+    intent.payer = ctx.accounts.payer.key();
+    intent.created_slot = Clock::get()?.slot;
+    intent.bump = ctx.bumps.spend_intent;
 
-    msg!("Private deposit: {} lamports", amount);
+    msg!("Spend intent posted at slot {}", intent.created_slot);
+
+    Ok(())
+}
 
+/// Drop a spend intent that will not be used
+pub fn cancel_spend_intent(_ctx: Context<CancelSpendIntent>) -> Result<()> {
+    msg!("Spend intent cancelled");
     Ok(())
 }
 
 /// Private withdraw with nullifier
+///
+/// `scheme_id` picks the commitment check; withdrawals keep working for
+/// schemes whose deposits have been disabled. The spend intent must name
+/// the recipient and amount.
 pub fn withdraw(
     ctx: Context<PrivateWithdraw>,
     nullifier: [u8; 32],
     secret_hash: [u8; 32],
     amount: u64,
+    scheme_id: u8,
+    proof: MerkleProof,
+) -> Result<()> {
+    let spend_intent = ctx.accounts.spend_intent.as_ref().ok_or(ErrorCode::SpendIntentMismatch)?;
+    let statement = SpendIntent::statement(b"withdraw", &[nullifier, secret_hash], ctx.accounts.recipient.key.as_ref(), amount);
+    spend_intent.require_matured(&statement, Clock::get()?.slot)?;
+
+    let header = NoteHeader::with_scheme(scheme_id, amount)?;
+    withdraw_note(ctx.accounts, ctx.bumps.nullifier_pda, nullifier, secret_hash, &header, &proof, false)
+}
+
+/// Private withdraw of a delegated note, by its owner or its delegate
+///
+/// Delegated notes are owned notes. The owner or the delegate authorizes
+/// the spend with an ed25519 signature over `withdraw_message(nullifier,
+/// recipient, amount, 0, valid_until)`, so any fee payer may submit it and
+/// gets the nullifier rent back. Only whether the delegate spent the note
+/// is recorded, as a flag on the nullifier PDA.
+pub fn withdraw_delegated(
+    ctx: Context<PrivateWithdrawDelegated>,
+    nullifier: [u8; 32],
+    spend: DelegatedSpend,
+    amount: u64,
+    valid_until: i64,
+    proof: MerkleProof,
+) -> Result<()> {
+    check_authorization_window(valid_until)?;
+
+    let accounts = &mut ctx.accounts.withdraw;
+    let signer = if spend.by_delegate { spend.delegate } else { spend.owner };
+    require_ed25519_signature(
+        &ctx.accounts.instructions_sysvar,
+        &signer,
+        &withdraw_message(&nullifier, &accounts.recipient.key(), amount, 0, valid_until),
+        ErrorCode::SignedMessageMismatch,
+    )?;
+
+    let header = NoteHeader::current(amount)?;
+    withdraw_note(accounts, ctx.bumps.withdraw.nullifier_pda, nullifier, spend.secret_hash(), &header, &proof, true)?;
+    accounts.nullifier_pda.delegated = spend.by_delegate;
+
+    Ok(())
+}
+
+/// Spend a plain (`owned` false) or owned note and pay it to the recipient
+fn withdraw_note(
+    accounts: &mut PrivateWithdraw,
+    nullifier_bump: u8,
+    nullifier: [u8; 32],
+    secret_hash: [u8; 32],
+    header: &NoteHeader,
+    proof: &MerkleProof,
+    owned: bool,
+) -> Result<()> {
+    let amount = header.amount();
+    let tree = accounts.commitment_tree.load()?;
+    let mut bitmap = accounts.spent_bitmap.load_mut()?;
+    spend_note(&tree, &mut bitmap, nullifier, secret_hash, header, proof, owned)?;
+    let used_at = tree.coarse_timestamp()?;
+    drop(tree);
+    drop(bitmap);
+
+    let payer = accounts.payer.key();
+    accounts.nullifier_pda.record(nullifier, proof.leaf_index, payer, used_at, nullifier_bump);
+
+    let pool = &accounts.pool;
+    let vault_signer = pool.vault_signer(&accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: accounts.pool_vault.to_account_info(),
+                to: accounts.recipient.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    let shard = &mut accounts.pool_shard;
+    shard.record_withdrawal(&accounts.pool, amount)?;
+
+    msg!("Private withdrawal: {} lamports", amount);
+
+    Ok(())
+}
+
+/// Spend a deposit made with the original per-commitment PDA
+///
+/// Such deposits predate the commitment tree: hash(secret_hash || nullifier
+/// || amount) must match the stored commitment. Their lamports went to the
+/// pre-shard vault, which `drain_legacy_vault` moves into the shards.
+pub fn withdraw_commitment_pda(
+    ctx: Context<WithdrawCommitmentPda>,
+    nullifier: [u8; 32],
+    secret_hash: [u8; 32],
+) -> Result<()> {
+    let commitment_pda = &ctx.accounts.commitment_pda;
+    let amount = commitment_pda.amount;
+    let computed_commitment = hashv(&[&secret_hash, &nullifier, &amount.to_le_bytes()]).to_bytes();
+    require!(computed_commitment == commitment_pda.commitment, ErrorCode::InvalidCommitmentProof);
+    require!(!commitment_pda.spent, ErrorCode::NullifierAlreadyUsed);
+    let statement = SpendIntent::statement(b"withdraw", &[nullifier, secret_hash], ctx.accounts.recipient.key.as_ref(), amount);
+    ctx.accounts.spend_intent.require_matured(&statement, Clock::get()?.slot)?;
+
+    let pool = &ctx.accounts.pool;
+    let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.recipient.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_withdrawal(&ctx.accounts.pool, amount)?;
+
+    msg!("Commitment PDA withdrawal: {} lamports", amount);
+
+    Ok(())
+}
+
+/// Exit a note while the pool is frozen, without any admin signature
+///
+/// Only open once the pool has been paused for its rescue delay. Any note
+/// version is accepted and the anonymity-set wait is skipped: the note is
+/// revealed in full and the exit is published, trading privacy for access.
+/// The spend intent must name the recipient, as for `withdraw`.
+pub fn rescue_note(
+    ctx: Context<PrivateWithdraw>,
+    nullifier: [u8; 32],
+    secret_hash: [u8; 32],
+    amount: u64,
+    scheme_id: u8,
+    version: u8,
+    proof: MerkleProof,
+) -> Result<()> {
+    require!(version <= NOTE_VERSION, ErrorCode::UnsupportedNoteVersion);
+    require_rescue_intent(ctx.accounts, nullifier, secret_hash, amount)?;
+    let header = NoteHeader { version, ..NoteHeader::with_scheme(scheme_id, amount)? };
+    let leaf = CommitmentTree::leaf(&header, &note_commitment(&header, &secret_hash, &nullifier, false)?);
+    rescue_leaf(ctx.accounts, ctx.bumps.nullifier_pda, nullifier, &header, leaf, &proof, amount)
+}
+
+/// Exit an owned note while the pool is frozen, authorized by its key
+///
+/// Same conditions as `rescue_note`, for the ed25519-owner, secp256k1-owner
+/// and delegated notes the plain paths reject. Instead of a spend intent,
+/// the note's key (owner or delegate, for a delegated note) signs
+/// `rescue_message(nullifier, recipient, amount, valid_until)` in an ed25519 or
+/// secp256k1 instruction earlier in the transaction.
+pub fn rescue_owned_note(
+    ctx: Context<RescueOwnedNote>,
+    nullifier: [u8; 32],
+    owner: NoteOwnerKey,
+    amount: u64,
+    valid_until: i64,
+    proof: MerkleProof,
+) -> Result<()> {
+    check_authorization_window(valid_until)?;
+
+    let accounts = &mut ctx.accounts.withdraw;
+    let ix_sysvar = &ctx.accounts.instructions_sysvar;
+    let message = rescue_message(&nullifier, &accounts.recipient.key(), amount, valid_until);
+    match owner {
+        NoteOwnerKey::Ed25519 { owner, .. } => {
+            require_ed25519_signature(ix_sysvar, &owner, &message, ErrorCode::SignedMessageMismatch)?
+        }
+        NoteOwnerKey::Secp256k1(owner) => {
+            require_secp256k1_signature(ix_sysvar, &owner.eth_address, &message, ErrorCode::SignedMessageMismatch)?
+        }
+        NoteOwnerKey::Delegated(spend) => {
+            let signer = if spend.by_delegate { spend.delegate } else { spend.owner };
+            require_ed25519_signature(ix_sysvar, &signer, &message, ErrorCode::SignedMessageMismatch)?
+        }
+    }
+
+    let header = NoteHeader::current(amount)?;
+    let leaf = CommitmentTree::leaf(&header, &note_commitment(&header, &owner.secret_hash(), &nullifier, true)?);
+    rescue_leaf(accounts, ctx.bumps.withdraw.nullifier_pda, nullifier, &header, leaf, &proof, amount)?;
+    if let NoteOwnerKey::Delegated(spend) = owner {
+        accounts.nullifier_pda.delegated = spend.by_delegate;
+    }
+
+    Ok(())
+}
+
+/// Require the matured spend intent of a bare-secret rescue to `recipient`
+pub(crate) fn require_rescue_intent(
+    accounts: &PrivateWithdraw,
+    nullifier: [u8; 32],
+    secret_hash: [u8; 32],
+    amount: u64,
 ) -> Result<()> {
+    let spend_intent = accounts.spend_intent.as_ref().ok_or(ErrorCode::SpendIntentMismatch)?;
+    let statement = SpendIntent::statement(b"rescue", &[nullifier, secret_hash], accounts.recipient.key.as_ref(), amount);
+    spend_intent.require_matured(&statement, Clock::get()?.slot)
+}
+
+/// Mark the leaf spent, pay it out and publish the exit
+pub(crate) fn rescue_leaf(
+    accounts: &mut PrivateWithdraw,
+    nullifier_bump: u8,
+    nullifier: [u8; 32],
+    header: &NoteHeader,
+    leaf: [u8; 32],
+    proof: &MerkleProof,
+    amount: u64,
+) -> Result<()> {
+    let tree = accounts.commitment_tree.load()?;
+    let mut bitmap = accounts.spent_bitmap.load_mut()?;
+    tree.require_class(header)?;
+    require!(bitmap.class == tree.class, ErrorCode::WrongSpentBitmap);
+    tree.verify(leaf, proof)?;
+    bitmap.mark_spent(proof.leaf_index)?;
+    let tree_class = tree.class;
+    let used_at = tree.coarse_timestamp()?;
+    drop(tree);
+    drop(bitmap);
+
+    let payer = accounts.payer.key();
+    accounts.nullifier_pda.record(nullifier, proof.leaf_index, payer, used_at, nullifier_bump);
+
+    let pool = &accounts.pool;
+    let vault_signer = pool.vault_signer(&accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: accounts.pool_vault.to_account_info(),
+                to: accounts.recipient.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    let shard = &mut accounts.pool_shard;
+    shard.record_rescue(&accounts.pool, amount)?;
+
+    emit!(NoteRescued {
+        tree_class,
+        leaf_index: proof.leaf_index,
+        nullifier,
+        amount,
+        recipient: accounts.recipient.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Self-rescue: {} lamports (leaf {})", amount, proof.leaf_index);
+
+    Ok(())
+}
+
+/// Spend several notes with one multiproof and pay the total in one transfer
+pub fn withdraw_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PrivateWithdrawBatch<'info>>,
+    spends: Vec<NoteSpend>,
+    root: [u8; 32],
+    siblings: Vec<[u8; 32]>,
+) -> Result<()> {
+    let nullifier_accounts = ctx.remaining_accounts;
     require!(
-        ALLOWED_AMOUNTS.contains(&amount),
-        ErrorCode::InvalidWithdrawAmount
+        !spends.is_empty() && spends.len() <= MAX_BATCH_SPENDS && nullifier_accounts.len() == spends.len(),
+        ErrorCode::InvalidNoteBatch
     );
 
-    let mut preimage = Vec::with_capacity(72);
-    preimage.extend_from_slice(&secret_hash);
-    preimage.extend_from_slice(&nullifier);
-    preimage.extend_from_slice(&amount.to_le_bytes());
+    // All notes of a batch share one tree, so they are the same denomination
+    let tree = ctx.accounts.commitment_tree.load()?;
+    let mut headers = Vec::with_capacity(spends.len());
+    let mut total: u64 = 0;
+    for spend in spends.iter() {
+        let header = NoteHeader::with_scheme(spend.scheme_id, spend.amount)?;
+        tree.require_class(&header)?;
+        headers.push(header);
+        total = total.checked_add(spend.amount).ok_or(ErrorCode::Overflow)?;
+    }
+    let notes: Vec<[u8; 32]> = spends.iter().flat_map(|spend| [spend.nullifier, spend.secret_hash]).collect();
+    let statement = SpendIntent::statement(b"withdraw-batch", &notes, ctx.accounts.recipient.key.as_ref(), total);
+    ctx.accounts.spend_intent.require_matured(&statement, Clock::get()?.slot)?;
+
+    // A batch is either all current-format or, while the legacy window is
+    // open, all version 1 notes
+    if let Err(current_error) = tree.verify_many(&batch_leaves(&spends, &headers, NOTE_VERSION)?, &root, &siblings) {
+        if !tree.legacy_spends_open()? {
+            return Err(current_error);
+        }
+        tree.verify_many(&batch_leaves(&spends, &headers, NOTE_VERSION - 1)?, &root, &siblings)?;
+    }
+    let mut bitmap = ctx.accounts.spent_bitmap.load_mut()?;
+    require!(bitmap.class == tree.class, ErrorCode::WrongSpentBitmap);
+    for spend in spends.iter() {
+        tree.require_anonymity_set(spend.leaf_index)?;
+        bitmap.mark_spent(spend.leaf_index)?;
+    }
+    let used_at = tree.coarse_timestamp()?;
+    drop(tree);
+    drop(bitmap);
+
+    for (spend, nullifier_account) in spends.iter().zip(nullifier_accounts.iter()) {
+        create_nullifier_pda(&ctx, nullifier_account, spend, used_at)?;
+    }
+
+    let pool = &ctx.accounts.pool;
+    let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.recipient.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        total,
+    )?;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_withdrawal(&ctx.accounts.pool, total)?;
+
+    msg!("Batched private withdrawal: {} notes, {} lamports", spends.len(), total);
 
-    let computed_commitment = hash(&preimage).to_bytes();
+    Ok(())
+}
 
-    let commitment_pda = &mut ctx.accounts.commitment_pda;
+/// Tree leaves of a batch's notes, all encoded at `version`
+fn batch_leaves(spends: &[NoteSpend], headers: &[NoteHeader], version: u8) -> Result<Vec<(u64, [u8; 32])>> {
+    spends.iter().zip(headers.iter()).map(|(spend, header)| {
+        let header = NoteHeader { version, ..*header };
+        let commitment = note_commitment(&header, &spend.secret_hash, &spend.nullifier, false)?;
+        Ok((spend.leaf_index, CommitmentTree::leaf(&header, &commitment)))
+    }).collect()
+}
+
+/// Create and fill the nullifier PDA for one note of a batch
+///
+/// An existing PDA means the nullifier was spent, which aborts the whole
+/// batch just like the `init` constraint does for single spends.
+fn create_nullifier_pda<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, PrivateWithdrawBatch<'info>>,
+    nullifier_account: &AccountInfo<'info>,
+    spend: &NoteSpend,
+    used_at: i64,
+) -> Result<()> {
+    let (expected, bump) = Pubkey::find_program_address(&[b"nullifier", spend.nullifier.as_ref()], ctx.program_id);
+    require_keys_eq!(nullifier_account.key(), expected, ErrorCode::InvalidNoteBatch);
     require!(
-        computed_commitment == commitment_pda.commitment,
-        ErrorCode::InvalidCommitmentProof
+        nullifier_account.owner == &system_program::ID && nullifier_account.data_is_empty(),
+        ErrorCode::NullifierAlreadyUsed
     );
-    require!(!commitment_pda.spent, ErrorCode::NullifierAlreadyUsed);
-    require!(commitment_pda.amount == amount, ErrorCode::InvalidAmount);
 
-    commitment_pda.spent = true;
+    let seeds: &[&[&[u8]]] = &[&[b"nullifier", spend.nullifier.as_ref(), &[bump]]];
+    create_pda_account(
+        &ctx.accounts.payer.to_account_info(),
+        nullifier_account,
+        &ctx.accounts.system_program.to_account_info(),
+        NullifierPDA::SPACE,
+        ctx.program_id,
+        seeds,
+    )?;
+
+    let mut record = NullifierPDA {
+        nullifier: [0u8; 32],
+        used_at: 0,
+        bump: 0,
+        leaf_index: 0,
+        rent_payer: Pubkey::default(),
+        delegated: false,
+    };
+    record.record(spend.nullifier, spend.leaf_index, ctx.accounts.payer.key(), used_at, bump);
+    let mut data = nullifier_account.try_borrow_mut_data()?;
+    record.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+/// Create the PDA `account` with `space` bytes, owned by `owner`
+///
+/// Same steps as Anchor's `init`: lamports already sent to the address are
+/// kept and only the rent shortfall is paid, so pre-funding the address
+/// cannot block its creation the way it blocks `create_account`.
+pub(crate) fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let shortfall = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer { from: payer.clone(), to: account.clone() },
+            ),
+            shortfall,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate { account_to_allocate: account.clone() },
+            seeds,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Assign { account_to_assign: account.clone() },
+            seeds,
+        ),
+        owner,
+    )?;
+    Ok(())
+}
+
+/// Spend an older-version note and append `new_commitment` in the current format
+pub fn migrate_note(
+    ctx: Context<MigrateNote>,
+    nullifier: [u8; 32],
+    secret_hash: [u8; 32],
+    amount: u64,
+    version: u8,
+    proof: MerkleProof,
+    new_commitment: [u8; 32],
+) -> Result<()> {
+    require!(version < NOTE_VERSION, ErrorCode::NoteAlreadyCurrent);
+    let statement = SpendIntent::statement(b"migrate", &[nullifier, secret_hash], &new_commitment, amount);
+    ctx.accounts.spend_intent.require_matured(&statement, Clock::get()?.slot)?;
+    let old_header = NoteHeader::with_version(version, amount)?;
+    let new_header = NoteHeader::current(amount)?;
+
+    let mut tree = ctx.accounts.commitment_tree.load_mut()?;
+    let mut bitmap = ctx.accounts.spent_bitmap.load_mut()?;
+    spend_note(&tree, &mut bitmap, nullifier, secret_hash, &old_header, &proof, false)?;
+
+    let leaf_index = append_note(&mut tree, &new_header, &new_commitment)?;
+    let used_at = tree.coarse_timestamp()?;
+    drop(tree);
+    drop(bitmap);
+
+    let payer = ctx.accounts.payer.key();
+    ctx.accounts.nullifier_pda.record(nullifier, proof.leaf_index, payer, used_at, ctx.bumps.nullifier_pda);
+
+    msg!("Note migrated from v{} to v{} (leaf {})", version, new_header.version, leaf_index);
+
+    Ok(())
+}
+
+/// Spend a note and append `new_commitment` for the same amount
+pub fn transfer_note(
+    ctx: Context<TransferNote>,
+    nullifier: [u8; 32],
+    secret_hash: [u8; 32],
+    amount: u64,
+    proof: MerkleProof,
+    new_commitment: [u8; 32],
+    announcement: Option<NoteAnnouncement>,
+) -> Result<()> {
+    let statement = SpendIntent::statement(b"transfer", &[nullifier, secret_hash], &new_commitment, amount);
+    ctx.accounts.spend_intent.require_matured(&statement, Clock::get()?.slot)?;
+    let header = NoteHeader::current(amount)?;
+
+    let mut tree = ctx.accounts.commitment_tree.load_mut()?;
+    let mut bitmap = ctx.accounts.spent_bitmap.load_mut()?;
+    spend_note(&tree, &mut bitmap, nullifier, secret_hash, &header, &proof, false)?;
+
+    let leaf_index = append_note(&mut tree, &header, &new_commitment)?;
+    let used_at = tree.coarse_timestamp()?;
+    drop(tree);
+    drop(bitmap);
 
-    let nullifier_pda = &mut ctx.accounts.nullifier_pda;
-    nullifier_pda.nullifier = nullifier;
-    nullifier_pda.used_at = Clock::get()?.unix_timestamp;
-    nullifier_pda.bump = ctx.bumps.nullifier_pda;
+    let payer = ctx.accounts.payer.key();
+    ctx.accounts.nullifier_pda.record(nullifier, proof.leaf_index, payer, used_at, ctx.bumps.nullifier_pda);
+
+    msg!("Note transferred (new leaf {})", leaf_index);
+
+    announce_note(&header, leaf_index, announcement)
+}
+
+/// Spend a note as a donation, crediting the campaign's stealth total
+pub fn private_donate(
+    ctx: Context<PrivateDonate>,
+    nullifier: [u8; 32],
+    secret_hash: [u8; 32],
+    amount: u64,
+    proof: MerkleProof,
+) -> Result<()> {
+    let campaign = &ctx.accounts.campaign;
+    require!(campaign.status == CampaignStatus::Active, ErrorCode::CampaignNotActive);
+    require!(Clock::get()?.unix_timestamp < campaign.deadline, ErrorCode::CampaignEnded);
+    let statement = SpendIntent::statement(b"donate", &[nullifier, secret_hash], campaign.key().as_ref(), amount);
+    ctx.accounts.spend_intent.require_matured(&statement, Clock::get()?.slot)?;
+
+    let header = NoteHeader::current(amount)?;
+    let tree = ctx.accounts.commitment_tree.load()?;
+    let mut bitmap = ctx.accounts.spent_bitmap.load_mut()?;
+    spend_note(&tree, &mut bitmap, nullifier, secret_hash, &header, &proof, false)?;
+    let used_at = tree.coarse_timestamp()?;
+    drop(tree);
+    drop(bitmap);
+
+    let payer = ctx.accounts.payer.key();
+    ctx.accounts.nullifier_pda.record(nullifier, proof.leaf_index, payer, used_at, ctx.bumps.nullifier_pda);
 
     let pool = &ctx.accounts.pool;
-    let vault_bump = pool.vault_bump;
-    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &[vault_bump]]];
+    let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
 
     system_program::transfer(
         CpiContext::new_with_signer(
             ctx.accounts.system_program.to_account_info(),
             system_program::Transfer {
                 from: ctx.accounts.pool_vault.to_account_info(),
-                to: ctx.accounts.recipient.to_account_info(),
+                to: ctx.accounts.campaign_vault.to_account_info(),
             },
             signer_seeds,
         ),
         amount,
     )?;
 
-    let pool = &mut ctx.accounts.pool;
-    pool.total_withdrawn = pool.total_withdrawn.checked_add(amount)
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_withdrawal(&ctx.accounts.pool, amount)?;
+
+    let campaign = &mut ctx.accounts.campaign;
+    campaign.stealth_donations = campaign.stealth_donations.checked_add(1)
         .ok_or(ErrorCode::Overflow)?;
-    pool.withdraw_count = pool.withdraw_count.checked_add(1)
+    campaign.stealth_total = campaign.stealth_total.checked_add(amount)
         .ok_or(ErrorCode::Overflow)?;
 
-    msg!("Private withdrawal: {} lamports", amount);
+    msg!("Private donation: {} lamports", amount);
+
+    Ok(())
+}
+
+/// Spend a note to fund a payroll batch, in place of `fund_batch`
+///
+/// Counts towards the batch budget exactly like a public deposit.
+pub fn fund_batch_private(
+    ctx: Context<FundBatchPrivate>,
+    nullifier: [u8; 32],
+    secret_hash: [u8; 32],
+    amount: u64,
+    proof: MerkleProof,
+) -> Result<()> {
+    require!(ctx.accounts.batch.load()?.status()? == BatchStatus::Active, ErrorCode::BatchNotActive);
+    ctx.accounts.master_vault.require_not_paused()?;
+    let statement = SpendIntent::statement(b"fund-batch", &[nullifier, secret_hash], ctx.accounts.batch.key().as_ref(), amount);
+    ctx.accounts.spend_intent.require_matured(&statement, Clock::get()?.slot)?;
+
+    let header = NoteHeader::current(amount)?;
+    let tree = ctx.accounts.commitment_tree.load()?;
+    let mut bitmap = ctx.accounts.spent_bitmap.load_mut()?;
+    spend_note(&tree, &mut bitmap, nullifier, secret_hash, &header, &proof, false)?;
+    let used_at = tree.coarse_timestamp()?;
+    drop(tree);
+    drop(bitmap);
+
+    let payer = ctx.accounts.payer.key();
+    ctx.accounts.nullifier_pda.record(nullifier, proof.leaf_index, payer, used_at, ctx.bumps.nullifier_pda);
+
+    let pool = &ctx.accounts.pool;
+    let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.batch_vault.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_withdrawal(&ctx.accounts.pool, amount)?;
+
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    batch.total_budget = batch.total_budget.checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    batch.remaining_budget = batch.remaining_budget.checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    let master = &mut ctx.accounts.master_vault;
+    master.total_deposited = master.total_deposited.checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    msg!("Batch funded privately: {} lamports", amount);
+
+    Ok(())
+}
+
+/// Commitment counts and roots, returned as return data
+///
+/// Only note class trees are counted; receipt and token trees are rejected.
+pub fn get_shielded_stats<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetShieldedStats>,
+) -> Result<ShieldedStats> {
+    let mut stats = ShieldedStats::default();
+
+    for account in ctx.remaining_accounts.iter() {
+        let loader = AccountLoader::<CommitmentTree>::try_from(account)?;
+        let tree = loader.load()?;
+        let class = tree.class as usize;
+        require!(class < NOTE_CLASS_COUNT, ErrorCode::InvalidTreeParameters);
+        stats.class_commitments[class] = tree.next_index;
+        stats.class_roots[class] = tree.current_root();
+    }
+    stats.commitments = stats.class_commitments.iter().sum();
+
+    Ok(stats)
+}
+
+/// Create one chunk of the spent-leaf bitmap of the tree for `class`
+pub fn init_spent_bitmap(ctx: Context<InitSpentBitmap>, class: u8, chunk: u64) -> Result<()> {
+    require!((class as usize) < NOTE_CLASS_COUNT, ErrorCode::InvalidTreeParameters);
+
+    let mut bitmap = ctx.accounts.spent_bitmap.load_init()?;
+    bitmap.class = class;
+    bitmap.chunk = chunk;
+    bitmap.bump = ctx.bumps.spent_bitmap;
+
+    msg!("Spent bitmap chunk {} of class {} initialized", chunk, class);
+
+    Ok(())
+}
+
+/// Close an old nullifier PDA, refunding rent to its payer
+pub fn close_nullifier(ctx: Context<CloseNullifier>) -> Result<()> {
+    let nullifier_pda = &ctx.accounts.nullifier_pda;
+    let bitmap = ctx.accounts.spent_bitmap.load()?;
+    // The bitmap must already carry the spend before the PDA disappears
+    require!(bitmap.is_spent(nullifier_pda.leaf_index)?, ErrorCode::WrongSpentBitmap);
+
+    msg!("Nullifier closed (leaf {})", nullifier_pda.leaf_index);
+
+    Ok(())
+}
+
+/// Publish a disclosure blob for `commitment`, encrypted to `auditor`
+///
+/// Only the owner of an ed25519-owner note may disclose it: the signing
+/// `owner` must open `commitment`, so nobody can squat a note's disclosure
+/// slot or attach a forged blob to someone else's note.
+pub fn register_disclosure(
+    ctx: Context<RegisterDisclosure>,
+    commitment: [u8; 32],
+    opening: OwnedNoteOpening,
+    auditor: Pubkey,
+    ciphertext: Vec<u8>,
+) -> Result<()> {
+    require!(
+        !ciphertext.is_empty() && ciphertext.len() <= MAX_DISCLOSURE_LEN,
+        ErrorCode::InvalidDisclosureLength
+    );
+
+    let header = NoteHeader::with_scheme(opening.scheme_id, opening.amount)?;
+    let secret_hash = hashv(&[b"ed25519-owner", ctx.accounts.owner.key.as_ref(), &opening.owner_salt]).to_bytes();
+    require!(
+        note_commitment(&header, &secret_hash, &opening.nullifier, true)? == commitment,
+        ErrorCode::InvalidCommitmentProof
+    );
+
+    let disclosure = &mut ctx.accounts.disclosure;
+    disclosure.owner = ctx.accounts.owner.key();
+    disclosure.commitment = commitment;
+    disclosure.auditor = auditor;
+    disclosure.ciphertext = ciphertext;
+    disclosure.created_at = Clock::get()?.unix_timestamp;
+    disclosure.bump = ctx.bumps.disclosure;
+
+    msg!("Disclosure registered for auditor {}", auditor);
+
+    Ok(())
+}
+
+/// Remove a disclosure account
+///
+/// The blob stays in transaction history; closing only stops new readers
+/// finding it through account lookups.
+pub fn close_disclosure(_ctx: Context<CloseDisclosure>) -> Result<()> {
+    msg!("Disclosure closed");
+    Ok(())
+}
+
+/// Move the deposit into the vault and append its note, returning the leaf index
+fn deposit_note(accounts: &mut PrivateDeposit, header: &NoteHeader, commitment: [u8; 32]) -> Result<u64> {
+    let amount = header.amount();
+
+    system_program::transfer(
+        CpiContext::new(
+            accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: accounts.depositor.to_account_info(),
+                to: accounts.pool_vault.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let mut tree = accounts.commitment_tree.load_mut()?;
+    let leaf_index = append_note(&mut tree, header, &commitment)?;
+
+    accounts.pool_shard.record_deposit(&accounts.pool, amount)?;
+
+    Ok(leaf_index)
+}
+
+/// Append a new note, unless deposits into its scheme are disabled
+pub(crate) fn append_note(tree: &mut CommitmentTree, header: &NoteHeader, commitment: &[u8; 32]) -> Result<u64> {
+    tree.require_deposits_enabled(header)?;
+    insert_note(tree, header, commitment)
+}
+
+/// Append a note's leaf to the commitment tree and announce it
+///
+/// Skips the deprecation check; used for change notes of a spend, which
+/// must keep working after a scheme is closed to deposits.
+pub(crate) fn insert_note(tree: &mut CommitmentTree, header: &NoteHeader, commitment: &[u8; 32]) -> Result<u64> {
+    tree.require_class(header)?;
+    let leaf = CommitmentTree::leaf(header, commitment);
+    let leaf_index = tree.insert(leaf)?;
+
+    emit!(CommitmentInserted {
+        tree_class: tree.class,
+        note_version: header.version,
+        leaf,
+        leaf_index,
+        root: tree.current_root(),
+        timestamp: tree.coarse_timestamp()?,
+    });
+
+    Ok(leaf_index)
+}
+
+/// Emit an encrypted note announcement for the note at `leaf_index`, if one was attached
+pub(crate) fn announce_note(header: &NoteHeader, leaf_index: u64, announcement: Option<NoteAnnouncement>) -> Result<()> {
+    let Some(announcement) = announcement else {
+        return Ok(());
+    };
+    require!(
+        !announcement.ciphertext.is_empty() && announcement.ciphertext.len() <= MAX_NOTE_CIPHERTEXT_LEN,
+        ErrorCode::InvalidNoteCiphertext
+    );
+
+    emit!(NoteAnnounced {
+        tree_class: header.class() as u8,
+        leaf_index,
+        ephemeral_pubkey: announcement.ephemeral_pubkey,
+        view_tag: announcement.view_tag,
+        ciphertext: announcement.ciphertext,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
+
+/// Verify a note against the commitment tree and mark its leaf spent
+///
+/// Shared by direct and relayed withdrawals. The spent bitmap rejects double
+/// spends; the caller still records the nullifier PDA, whose `init`
+/// constraint catches reuse of a nullifier while it is open. `owned` is
+/// set by the signed spends of owner-keyed notes.
+pub(crate) fn spend_note(
+    tree: &CommitmentTree,
+    bitmap: &mut SpentBitmap,
+    nullifier: [u8; 32],
+    secret_hash: [u8; 32],
+    header: &NoteHeader,
+    proof: &MerkleProof,
+    owned: bool,
+) -> Result<()> {
+    let leaf = current_or_legacy_leaf(tree, header, proof, |header| {
+        Ok(CommitmentTree::leaf(header, &note_commitment(header, &secret_hash, &nullifier, owned)?))
+    })?;
+    spend_leaf(tree, bitmap, header, leaf, proof)
+}
+
+/// Leaf of a current-format note, or of its version 1 encoding when only
+/// that one is in the tree and the legacy window is still open
+pub(crate) fn current_or_legacy_leaf(
+    tree: &CommitmentTree,
+    header: &NoteHeader,
+    proof: &MerkleProof,
+    leaf_of: impl Fn(&NoteHeader) -> Result<[u8; 32]>,
+) -> Result<[u8; 32]> {
+    let leaf = leaf_of(header)?;
+    if header.version != NOTE_VERSION || tree.verify(leaf, proof).is_ok() || !tree.legacy_spends_open()? {
+        return Ok(leaf);
+    }
+    leaf_of(&NoteHeader { version: NOTE_VERSION - 1, ..*header })
+}
+
+/// Recompute a note's commitment from its revealed preimage, per scheme
+///
+/// From note version 2 the sha256 preimage starts with `COMMITMENT_DOMAIN`.
+/// Owned notes always start with `OWNED_COMMITMENT_DOMAIN`, so a spend that
+/// reveals only the secret hash can never match an owned note's leaf.
+fn note_commitment(header: &NoteHeader, secret_hash: &[u8; 32], nullifier: &[u8; 32], owned: bool) -> Result<[u8; 32]> {
+    let amount = header.amount();
+    match header.scheme_id {
+        NOTE_SCHEME_SHA256 => {
+            let domain: &[u8] = if owned {
+                OWNED_COMMITMENT_DOMAIN
+            } else if header.version >= 2 {
+                COMMITMENT_DOMAIN
+            } else {
+                &[]
+            };
+            Ok(hashv(&[domain, secret_hash, nullifier, &amount.to_le_bytes()]).to_bytes())
+        }
+        _ => err!(ErrorCode::UnsupportedNoteScheme),
+    }
+}
+
+/// Enable or disable deposits into a note scheme (pool authority only)
+///
+/// Existing notes of a disabled scheme can still be withdrawn.
+pub fn set_scheme_deposits(ctx: Context<UpdateCommitmentConfig>, scheme_id: u8, enabled: bool) -> Result<()> {
+    require!(
+        scheme_id == NOTE_SCHEME_SHA256 || scheme_id == NOTE_SCHEME_PEDERSEN,
+        ErrorCode::UnsupportedNoteScheme
+    );
+
+    let mut tree = ctx.accounts.commitment_tree.load_mut()?;
+    if enabled {
+        tree.disabled_deposit_schemes &= !(1 << scheme_id);
+    } else {
+        tree.disabled_deposit_schemes |= 1 << scheme_id;
+    }
+
+    msg!("Deposits into note scheme {} enabled: {}", scheme_id, enabled);
+
+    Ok(())
+}
+
+/// Check `leaf` is in the tree under a recent root, has enough later
+/// same-class deposits, and mark it spent
+pub(crate) fn spend_leaf(
+    tree: &CommitmentTree,
+    bitmap: &mut SpentBitmap,
+    header: &NoteHeader,
+    leaf: [u8; 32],
+    proof: &MerkleProof,
+) -> Result<()> {
+    tree.require_class(header)?;
+    require!(bitmap.class == tree.class, ErrorCode::WrongSpentBitmap);
+    tree.verify(leaf, proof)?;
+    tree.require_anonymity_set(proof.leaf_index)?;
+    bitmap.mark_spent(proof.leaf_index)
+}
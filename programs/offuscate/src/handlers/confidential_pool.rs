@@ -0,0 +1,207 @@
+//! Confidential Pool Handlers
+//!
+//! Business logic for the Token-2022 confidential transfer pool. The program
+//! owns the vault token account and signs the confidential transfer CPIs;
+//! the zero-knowledge proofs themselves are pre-verified into context state
+//! accounts by the ZK ElGamal proof program.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_interface::get_mint_extension_data;
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use spl_token_2022::extension::confidential_transfer::{
+    instruction as ct_instruction, ConfidentialTransferMint, DecryptableBalance,
+};
+use spl_token_confidential_transfer_proof_extraction::instruction::ProofLocation;
+use crate::constants::CONFIDENTIAL_WITHDRAW_DELAY_SECONDS;
+use crate::errors::ErrorCode;
+use crate::handlers::commitment::create_pda_account;
+use crate::instructions::{
+    InitConfidentialPool, ConfidentialPoolApplyPending, ConfidentialPoolWithdraw, RequestConfidentialWithdraw,
+};
+
+/// Maximum pending deposits before the operator must apply them
+const MAX_PENDING_BALANCE_CREDITS: u64 = 65_536;
+
+/// Create the confidential pool and configure its vault for confidential transfers
+///
+/// The vault is created with room for every extension the mint requires plus
+/// the confidential transfer extension, so no reallocation is needed.
+pub fn init(ctx: Context<InitConfidentialPool>, decryptable_zero_balance: [u8; 36]) -> Result<()> {
+    get_mint_extension_data::<ConfidentialTransferMint>(&ctx.accounts.mint.to_account_info())
+        .map_err(|_| ErrorCode::MintNotConfidential)?;
+
+    let pool = &mut ctx.accounts.confidential_pool;
+    pool.authority = ctx.accounts.authority.key();
+    pool.mint = ctx.accounts.mint.key();
+    pool.vault = ctx.accounts.vault.key();
+    pool.apply_count = 0;
+    pool.withdraw_count = 0;
+    pool.bump = ctx.bumps.confidential_pool;
+    pool.vault_bump = ctx.bumps.vault;
+
+    let mint_key = pool.mint;
+    let pool_seeds: &[&[&[u8]]] = &[&[b"confidential_pool", mint_key.as_ref(), &[pool.bump]]];
+    let token_program = ctx.accounts.token_program.key();
+
+    let mint_info = ctx.accounts.mint.to_account_info();
+    let mut extensions = {
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?)
+    };
+    extensions.push(ExtensionType::ConfidentialTransferAccount);
+    let vault_len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&extensions)?;
+
+    let vault_seeds: &[&[&[u8]]] = &[&[b"confidential_vault", mint_key.as_ref(), &[pool.vault_bump]]];
+    create_pda_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        vault_len,
+        &token_program,
+        vault_seeds,
+    )?;
+
+    let init_vault_ix = spl_token_2022::instruction::initialize_account3(
+        &token_program,
+        &ctx.accounts.vault.key(),
+        &mint_key,
+        &pool.key(),
+    )?;
+    invoke_signed(
+        &init_vault_ix,
+        &[ctx.accounts.vault.to_account_info(), mint_info.clone()],
+        &[],
+    )?;
+
+    let configure_ix = ct_instruction::inner_configure_account(
+        &token_program,
+        &ctx.accounts.vault.key(),
+        &mint_key,
+        decryptable_balance(decryptable_zero_balance),
+        MAX_PENDING_BALANCE_CREDITS,
+        &pool.key(),
+        &[],
+        ProofLocation::ContextStateAccount(&ctx.accounts.pubkey_validity_proof.key()),
+    )?;
+    invoke_signed(
+        &configure_ix,
+        &[
+            ctx.accounts.vault.to_account_info(),
+            mint_info,
+            ctx.accounts.pubkey_validity_proof.to_account_info(),
+            pool.to_account_info(),
+        ],
+        pool_seeds,
+    )?;
+
+    msg!("Confidential pool initialized for mint: {}", mint_key);
+    msg!("Confidential vault: {}", pool.vault);
+
+    Ok(())
+}
+
+/// Apply pending confidential deposits to the vault's available balance
+pub fn apply_pending(
+    ctx: Context<ConfidentialPoolApplyPending>,
+    expected_pending_balance_credit_counter: u64,
+    new_decryptable_available_balance: [u8; 36],
+) -> Result<()> {
+    let pool = &ctx.accounts.confidential_pool;
+    let pool_seeds: &[&[&[u8]]] = &[&[b"confidential_pool", pool.mint.as_ref(), &[pool.bump]]];
+
+    let ix = ct_instruction::inner_apply_pending_balance(
+        &ctx.accounts.token_program.key(),
+        &ctx.accounts.vault.key(),
+        expected_pending_balance_credit_counter,
+        decryptable_balance(new_decryptable_available_balance),
+        &pool.key(),
+        &[],
+    )?;
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.vault.to_account_info(),
+            pool.to_account_info(),
+        ],
+        pool_seeds,
+    )?;
+
+    let pool = &mut ctx.accounts.confidential_pool;
+    pool.apply_count = pool.apply_count.checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    msg!("Confidential pending balance applied ({} credits)", expected_pending_balance_credit_counter);
+
+    Ok(())
+}
+
+/// Open a withdraw request for the signer's destination token account
+///
+/// The operator can pay it out once CONFIDENTIAL_WITHDRAW_DELAY_SECONDS have
+/// passed; the delay leaves time to notice an unexpected request.
+pub fn request_withdraw(ctx: Context<RequestConfidentialWithdraw>) -> Result<()> {
+    let request = &mut ctx.accounts.request;
+    request.pool = ctx.accounts.confidential_pool.key();
+    request.destination = ctx.accounts.destination.key();
+    request.owner = ctx.accounts.owner.key();
+    request.available_at = Clock::get()?.unix_timestamp
+        .checked_add(CONFIDENTIAL_WITHDRAW_DELAY_SECONDS)
+        .ok_or(ErrorCode::Overflow)?;
+    request.bump = ctx.bumps.request;
+
+    msg!("Confidential withdraw requested to {}", request.destination);
+    msg!("Available at: {}", request.available_at);
+
+    Ok(())
+}
+
+/// Confidentially transfer funds out of the vault to a requested destination
+pub fn withdraw(
+    ctx: Context<ConfidentialPoolWithdraw>,
+    new_source_decryptable_available_balance: [u8; 36],
+) -> Result<()> {
+    let pool = &ctx.accounts.confidential_pool;
+    let pool_seeds: &[&[&[u8]]] = &[&[b"confidential_pool", pool.mint.as_ref(), &[pool.bump]]];
+
+    let ix = ct_instruction::inner_transfer(
+        &ctx.accounts.token_program.key(),
+        &ctx.accounts.vault.key(),
+        &ctx.accounts.mint.key(),
+        &ctx.accounts.destination.key(),
+        decryptable_balance(new_source_decryptable_available_balance),
+        &pool.key(),
+        &[],
+        ProofLocation::ContextStateAccount(&ctx.accounts.equality_proof.key()),
+        ProofLocation::ContextStateAccount(&ctx.accounts.ciphertext_validity_proof.key()),
+        ProofLocation::ContextStateAccount(&ctx.accounts.range_proof.key()),
+    )?;
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.destination.to_account_info(),
+            ctx.accounts.equality_proof.to_account_info(),
+            ctx.accounts.ciphertext_validity_proof.to_account_info(),
+            ctx.accounts.range_proof.to_account_info(),
+            pool.to_account_info(),
+        ],
+        pool_seeds,
+    )?;
+
+    let pool = &mut ctx.accounts.confidential_pool;
+    pool.withdraw_count = pool.withdraw_count.checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    msg!("Confidential withdrawal to {}", ctx.accounts.destination.key());
+
+    Ok(())
+}
+
+/// Reinterpret raw AE ciphertext bytes from instruction data
+fn decryptable_balance(bytes: [u8; 36]) -> DecryptableBalance {
+    bytemuck::cast(bytes)
+}
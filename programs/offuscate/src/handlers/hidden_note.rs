@@ -0,0 +1,234 @@
+//! Hidden-Amount Note Handlers
+//!
+//! Business logic for Pedersen-commitment notes. A note's value commitment
+//! is `amount * G + blinding * H`; the program only ever checks relations
+//! between commitments, using the curve25519 syscalls.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::solana_program::hash::hashv;
+use solana_curve25519::ristretto::{
+    add_ristretto, multiply_ristretto, subtract_ristretto, PodRistrettoPoint,
+};
+use solana_curve25519::scalar::PodScalar;
+use solana_zk_sdk::zk_elgamal_proof_program::proof_data::{
+    BatchedRangeProofContext, BatchedRangeProofU64Data,
+};
+use spl_token_confidential_transfer_proof_extraction::instruction::verify_and_extract_context;
+use crate::constants::{PEDERSEN_G, PEDERSEN_H, HIDDEN_NOTE_DOMAIN, HIDDEN_COMMITMENT_DOMAIN, NOTE_VERSION};
+use crate::errors::ErrorCode;
+use crate::handlers::commitment::{
+    announce_note, append_note, current_or_legacy_leaf, insert_note, require_rescue_intent, rescue_leaf, spend_leaf,
+};
+use crate::instructions::{PrivateDepositHidden, PrivateWithdraw, PrivateWithdrawHidden};
+use crate::state::{
+    CommitmentTree, HiddenChange, HiddenNoteOpening, MerkleProof, NoteAnnouncement, NoteHeader,
+    OpeningProof,
+};
+
+/// Deposit `amount` into a hidden-amount note
+pub fn deposit(
+    ctx: Context<PrivateDepositHidden>,
+    commitment: [u8; 32],
+    value_commitment: [u8; 32],
+    amount: u64,
+    opening: OpeningProof,
+    announcement: Option<NoteAnnouncement>,
+) -> Result<()> {
+    ctx.accounts.pool.check_deposit_amount(amount)?;
+
+    // value_commitment - amount * G must be a commitment to zero
+    let blinding_part = subtract(&value_commitment, &amount_point(amount)?)?;
+    verify_opening(&blinding_part, &opening, &commitment)?;
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.depositor.to_account_info(),
+                to: ctx.accounts.pool_vault.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let mut tree = ctx.accounts.commitment_tree.load_mut()?;
+    let header = NoteHeader::pedersen();
+    let leaf_index = append_note(&mut tree, &header, &hidden_commitment(&header, &commitment, &value_commitment))?;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_deposit(&ctx.accounts.pool, amount)?;
+
+    msg!("Hidden-amount deposit (leaf {})", leaf_index);
+
+    announce_note(&header, leaf_index, announcement)
+}
+
+/// Withdraw `amount` from a hidden-amount note and re-commit the change
+pub fn withdraw(
+    ctx: Context<PrivateWithdrawHidden>,
+    nullifier: [u8; 32],
+    note: HiddenNoteOpening,
+    amount: u64,
+    proof: MerkleProof,
+    change: HiddenChange,
+    balance_proof: OpeningProof,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    // input - amount * G - change must be a commitment to zero; binding the
+    // challenge to nullifier, recipient, amount and the change note stops the
+    // proof being replayed or its change being redirected to another owner
+    let withdrawn = add(&amount_point(amount)?, &change.value_commitment)?;
+    let blinding_part = subtract(&note.value_commitment, &withdrawn)?;
+    let mut context = Vec::with_capacity(32 + 32 + 8 + 32 + 32);
+    context.extend_from_slice(&nullifier);
+    context.extend_from_slice(ctx.accounts.recipient.key.as_ref());
+    context.extend_from_slice(&amount.to_le_bytes());
+    context.extend_from_slice(&change.commitment);
+    context.extend_from_slice(&change.value_commitment);
+    verify_opening(&blinding_part, &balance_proof, &context)?;
+
+    verify_change_range(&ctx.accounts.range_proof, &change.value_commitment)?;
+
+    let header = NoteHeader::pedersen();
+
+    let mut tree = ctx.accounts.commitment_tree.load_mut()?;
+    let mut bitmap = ctx.accounts.spent_bitmap.load_mut()?;
+    let leaf = current_or_legacy_leaf(&tree, &header, &proof, |header| {
+        let inner = hidden_note_commitment(header, &note.secret_hash, &nullifier);
+        Ok(CommitmentTree::leaf(header, &hidden_commitment(header, &inner, &note.value_commitment)))
+    })?;
+    spend_leaf(&tree, &mut bitmap, &header, leaf, &proof)?;
+    let change_index = insert_note(
+        &mut tree,
+        &header,
+        &hidden_commitment(&header, &change.commitment, &change.value_commitment),
+    )?;
+    let used_at = tree.coarse_timestamp()?;
+    drop(tree);
+    drop(bitmap);
+
+    let payer = ctx.accounts.payer.key();
+    ctx.accounts.nullifier_pda.record(nullifier, proof.leaf_index, payer, used_at, ctx.bumps.nullifier_pda);
+
+    let pool = &ctx.accounts.pool;
+    let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.recipient.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_withdrawal(&ctx.accounts.pool, amount)?;
+
+    msg!("Hidden-amount withdrawal: {} lamports (change leaf {})", amount, change_index);
+
+    Ok(())
+}
+
+/// Self-rescue of a hidden-amount note, by opening its value commitment
+///
+/// Same conditions as `commitment::rescue_note`; revealing `blinding`
+/// makes the amount public.
+pub fn rescue(
+    ctx: Context<PrivateWithdraw>,
+    nullifier: [u8; 32],
+    note: HiddenNoteOpening,
+    amount: u64,
+    blinding: [u8; 32],
+    version: u8,
+    proof: MerkleProof,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!((1..=NOTE_VERSION).contains(&version), ErrorCode::UnsupportedNoteVersion);
+
+    let blinding_part = multiply_ristretto(&PodScalar(blinding), &PodRistrettoPoint(PEDERSEN_H))
+        .ok_or(ErrorCode::InvalidOpeningProof)?;
+    let opened = add(&amount_point(amount)?, &blinding_part.0)?;
+    require!(opened == note.value_commitment, ErrorCode::InvalidOpeningProof);
+
+    let header = NoteHeader { version, ..NoteHeader::pedersen() };
+    let inner = hidden_note_commitment(&header, &note.secret_hash, &nullifier);
+    let leaf = CommitmentTree::leaf(&header, &hidden_commitment(&header, &inner, &note.value_commitment));
+    require_rescue_intent(ctx.accounts, nullifier, note.secret_hash, amount)?;
+    rescue_leaf(ctx.accounts, ctx.bumps.nullifier_pda, nullifier, &header, leaf, &proof, amount)
+}
+
+/// Inner commitment of a hidden-amount note: hash(tag || secret_hash || nullifier)
+fn hidden_note_commitment(header: &NoteHeader, secret_hash: &[u8; 32], nullifier: &[u8; 32]) -> [u8; 32] {
+    let domain: &[u8] = if header.version >= 2 { HIDDEN_NOTE_DOMAIN } else { &[] };
+    hashv(&[domain, secret_hash, nullifier]).to_bytes()
+}
+
+/// Tree commitment of a hidden-amount note: hash(tag || note commitment || value commitment)
+fn hidden_commitment(header: &NoteHeader, commitment: &[u8; 32], value_commitment: &[u8; 32]) -> [u8; 32] {
+    let domain: &[u8] = if header.version >= 2 { HIDDEN_COMMITMENT_DOMAIN } else { &[] };
+    hashv(&[domain, commitment, value_commitment]).to_bytes()
+}
+
+/// Check a Schnorr proof that `point` = r * H, with the challenge bound to `context`
+///
+/// Verifies z * H == R + c * point, where c = hash(context || point || R)
+/// truncated to 252 bits so it is always a canonical scalar.
+fn verify_opening(point: &[u8; 32], opening: &OpeningProof, context: &[u8]) -> Result<()> {
+    let mut challenge = hashv(&[b"offuscate:pedersen-opening", context, point, &opening.nonce_point])
+        .to_bytes();
+    challenge[31] &= 0x0f;
+
+    let lhs = multiply_ristretto(&PodScalar(opening.response), &PodRistrettoPoint(PEDERSEN_H))
+        .ok_or(ErrorCode::InvalidOpeningProof)?;
+    let challenged = multiply_ristretto(&PodScalar(challenge), &PodRistrettoPoint(*point))
+        .ok_or(ErrorCode::InvalidPedersenPoint)?;
+    let rhs = add(&opening.nonce_point, &challenged.0)?;
+
+    require!(lhs.0 == rhs, ErrorCode::InvalidOpeningProof);
+    Ok(())
+}
+
+/// Require a verified 64-bit range proof over exactly `value_commitment`
+fn verify_change_range(range_proof: &AccountInfo, value_commitment: &[u8; 32]) -> Result<()> {
+    let context = verify_and_extract_context::<BatchedRangeProofU64Data, BatchedRangeProofContext>(
+        &mut std::slice::from_ref(range_proof).iter(),
+        0,
+        None,
+    )
+    .map_err(|_| ErrorCode::InvalidRangeProof)?;
+
+    require!(
+        bytemuck::bytes_of(&context.commitments[0]) == value_commitment.as_slice()
+            && context.bit_lengths[0] == 64,
+        ErrorCode::InvalidRangeProof
+    );
+    Ok(())
+}
+
+/// amount * G
+fn amount_point(amount: u64) -> Result<[u8; 32]> {
+    let mut scalar = [0u8; 32];
+    scalar[..8].copy_from_slice(&amount.to_le_bytes());
+    let point = multiply_ristretto(&PodScalar(scalar), &PodRistrettoPoint(PEDERSEN_G))
+        .ok_or(ErrorCode::InvalidPedersenPoint)?;
+    Ok(point.0)
+}
+
+fn add(left: &[u8; 32], right: &[u8; 32]) -> Result<[u8; 32]> {
+    let point = add_ristretto(&PodRistrettoPoint(*left), &PodRistrettoPoint(*right))
+        .ok_or(ErrorCode::InvalidPedersenPoint)?;
+    Ok(point.0)
+}
+
+fn subtract(left: &[u8; 32], right: &[u8; 32]) -> Result<[u8; 32]> {
+    let point = subtract_ristretto(&PodRistrettoPoint(*left), &PodRistrettoPoint(*right))
+        .ok_or(ErrorCode::InvalidPedersenPoint)?;
+    Ok(point.0)
+}
@@ -4,9 +4,11 @@
 
 use anchor_lang::prelude::*;
 use crate::errors::ErrorCode;
-use crate::state::{InviteStatus, EmployeeStatus};
+use crate::constants::MAX_CRANK_INVITES;
+use crate::handlers::payroll::pay_crank_tip;
+use crate::state::{Invite, InviteStatus, EmployeeStatus, RosterPage};
 use crate::instructions::{
-    CreateInvite, CreateBatchInvite, AcceptInvite, RevokeInvite, AcceptInviteStreaming,
+    CreateInvite, CreateBatchInvite, AcceptInvite, RevokeInvite, AcceptInviteStreaming, CrankExpireInvites,
 };
 
 /// Create an invite for a campaign
@@ -59,7 +61,7 @@ pub fn create_batch_invite(
     invite.accepted_at = 0;
     invite.bump = ctx.bumps.invite;
 
-    msg!("Invite created for payroll batch: {}", ctx.accounts.batch.title);
+    msg!("Invite created for payroll batch: {}", ctx.accounts.batch.load()?.title());
     if salary_rate > 0 {
         msg!("Streaming salary configured: {} lamports/sec", salary_rate);
     }
@@ -77,11 +79,13 @@ pub fn accept(
 
     let invite = &mut ctx.accounts.invite;
     require!(invite.status == InviteStatus::Pending, ErrorCode::InviteNotPending);
+    let now = Clock::get()?.unix_timestamp;
+    require!(!invite.is_expired(now), ErrorCode::InviteExpired);
 
     invite.recipient = ctx.accounts.recipient.key();
     invite.recipient_stealth_address = stealth_meta_address.clone();
     invite.status = InviteStatus::Accepted;
-    invite.accepted_at = Clock::get()?.unix_timestamp;
+    invite.accepted_at = now;
 
     msg!("Invite accepted by: {}", ctx.accounts.recipient.key());
     msg!("Stealth address registered: {}", stealth_meta_address);
@@ -114,26 +118,31 @@ pub fn accept_streaming(
     require!(invite.salary_rate > 0, ErrorCode::InviteNoSalaryConfigured);
 
     let now = Clock::get()?.unix_timestamp;
+    require!(!invite.is_expired(now), ErrorCode::InviteExpired);
 
     invite.recipient = ctx.accounts.payer.key();
     invite.recipient_stealth_address = stealth_meta_address.clone();
     invite.status = InviteStatus::Accepted;
     invite.accepted_at = now;
 
-    let employee = &mut ctx.accounts.employee;
-    let batch = &mut ctx.accounts.batch;
+    let batch_key = ctx.accounts.batch.key();
+    let mut employee = ctx.accounts.employee.load_init()?;
+    let mut batch = ctx.accounts.batch.load_mut()?;
     let master = &mut ctx.accounts.master_vault;
 
-    employee.batch = batch.key();
+    employee.batch = batch_key;
     employee.wallet = ctx.accounts.employee_stealth_pubkey.key();
-    employee.index = batch.employee_count;
-    employee.stealth_address = stealth_meta_address;
+    employee.index = batch.next_employee_index();
+    employee.set_stealth_address(&stealth_meta_address)?;
     employee.salary_rate = invite.salary_rate;
     employee.start_time = now;
     employee.last_claimed_at = now;
-    employee.total_claimed = 0;
-    employee.status = EmployeeStatus::Active;
+    employee.status = EmployeeStatus::Active as u8;
     employee.bump = ctx.bumps.employee;
+    employee.batch_paused_seconds = batch.paused_seconds;
+    employee.rent_payer = ctx.accounts.payer.key();
+
+    ctx.accounts.roster.load_mut()?.record(employee.index, employee.wallet, RosterPage::ACTIVE)?;
 
     batch.employee_count = batch.employee_count.checked_add(1)
         .ok_or(ErrorCode::Overflow)?;
@@ -147,3 +156,43 @@ pub fn accept_streaming(
 
     Ok(())
 }
+
+/// Mark every listed invite of the batch that has expired (anyone can call)
+///
+/// Remaining accounts are the batch's invites. Invites that are not pending
+/// or not yet expired are skipped, so rerunning a crank is harmless; each
+/// expired invite earns the cranker the batch's crank tip.
+pub fn crank_expire_invites<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CrankExpireInvites<'info>>,
+) -> Result<()> {
+    let remaining = ctx.remaining_accounts;
+    require!(remaining.len() <= MAX_CRANK_INVITES, ErrorCode::TooManyCrankInvites);
+
+    let accounts = &mut *ctx.accounts;
+    let now = Clock::get()?.unix_timestamp;
+    let mut expired: u32 = 0;
+
+    for info in remaining.iter() {
+        let mut invite: Account<Invite> = Account::try_from(info)?;
+        require!(invite.batch == accounts.batch.key(), ErrorCode::Unauthorized);
+        if !invite.is_expired(now) {
+            continue;
+        }
+
+        invite.status = InviteStatus::Expired;
+        invite.exit(&crate::ID)?;
+        expired += 1;
+    }
+
+    pay_crank_tip(
+        &accounts.batch,
+        &accounts.batch_vault,
+        accounts.cranker.to_account_info(),
+        &accounts.system_program,
+        expired,
+        u64::MAX,
+    )?;
+
+    msg!("Invite crank: {} expired", expired);
+    Ok(())
+}
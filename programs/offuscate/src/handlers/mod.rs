@@ -12,6 +12,10 @@
 //! - payroll: Streaming payroll operations
 //! - receipt: Anonymous receipt operations
 //! - commitment: Commitment-based privacy operations
+//! - hidden_note: Hidden-amount (Pedersen) notes
+//! - token_note: Shielded SPL token notes
+//! - wsol: Wrapped SOL deposits and claims, swap claims
+//! - confidential_pool: Token-2022 confidential transfer pool
 
 pub mod privacy_pool;
 pub mod relayer;
@@ -21,3 +25,7 @@ pub mod invite;
 pub mod payroll;
 pub mod receipt;
 pub mod commitment;
+pub mod hidden_note;
+pub mod token_note;
+pub mod wsol;
+pub mod confidential_pool;
@@ -4,11 +4,23 @@
 
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use anchor_spl::token;
 use crate::errors::ErrorCode;
-use crate::state::{BatchStatus, EmployeeStatus};
+use crate::events::{EmployeeHoldLifted, EmployeeHoldPlaced, EmployeeStealthUpdated, SalaryUnderfunded};
+use crate::constants::{
+    BPS_DENOMINATOR, MAX_MONTHLY_PAYDAY_OFFSET, MAX_PAYDAY_EMPLOYEES, MAX_PAYOUT_SPLITS, MAX_SALARY_CRANK_TIP_BPS,
+    MAX_CRANK_TIP, MAX_MIN_CLAIM_INTERVAL, MAX_SEVERANCE_DAYS, MAX_PRICE_AGE_SECONDS, MAX_PRICE_CONF_BPS, PYTH_RECEIVER_PROGRAM_ID,
+};
+use crate::handlers::commitment::append_note;
+use crate::handlers::relayer::{check_authorization_window, require_ed25519_signature};
+use crate::state::{
+    BatchRole, BatchRunway, BatchStatus, EarningSnapshot, Employee, EmployeeStatus, LegacyEmployee, LegacyPayrollBatch,
+    MasterVault, NoteHeader, PaySchedule, PayoutSplit, PayrollBatch, RateChange, RosterPage, SalaryCurrency, UsdPrice,
+};
 use crate::instructions::{
-    InitMasterVault, CreateBatch, AddEmployee, FundBatch,
-    ClaimSalary, UpdateSalaryRate, SetEmployeeStatus,
+    InitMasterVault, UpdatePayrollConfig, AcceptPayrollAuthority, CreateBatch, AddEmployee, FundBatch, FundTreasury, AllocateToBatch,
+    InitBatchTokenVault, FundBatchToken, ClaimSalaryToken, SetEmployeePayMint, CloseBatchTokenVault,
+    ClaimSalary, ClaimSalaryStealth, ClaimSalaryToCommitment, ClaimSalaryToPool, SetMinClaimInterval, UpdateSalaryRate, SetClaimCap, ProposePayoutSplits, AcceptPayoutSplits, SetPaySchedule, SetSalaryCurrency, AllocateEmployeeBudget, RunPayday, CrankClaimReadySalaries, SetCrankTip, SetPayoutAddress, UpdateEmployeeStealth, SetPushPayments, PushSalary, SetEmployeeStatus, TerminateEmployee, InitRosterPage, SnapshotEmployeePeriod, GetBatchRunway, CloseEmployee, TransferEmployee, CloseBatch, MigratePayrollBatch, MigrateEmployee, SetBatchStatus, TransferBatchOwnership, AcceptBatchOwnership, GrantBatchRole, RevokeBatchRole,
 };
 
 /// Initialize the master vault
@@ -20,27 +32,60 @@ pub fn init_master_vault(ctx: Context<InitMasterVault>) -> Result<()> {
     vault.total_deposited = 0;
     vault.total_paid = 0;
     vault.bump = ctx.bumps.master_vault;
+    vault.closed_batches = 0;
+    vault.pending_authority = Pubkey::default();
+    vault.paused = false;
 
     msg!("Master vault initialized");
     Ok(())
 }
 
+/// Propose a new payroll authority (e.g. a multisig); it takes effect once accepted
+///
+/// Proposing the default pubkey cancels a pending transfer.
+pub fn transfer_payroll_authority(ctx: Context<UpdatePayrollConfig>, new_authority: Pubkey) -> Result<()> {
+    let vault = &mut ctx.accounts.master_vault;
+    vault.pending_authority = new_authority;
+
+    msg!("Payroll authority transfer proposed: {} -> {}", vault.authority, new_authority);
+    Ok(())
+}
+
+/// Accept a proposed payroll authority transfer
+pub fn accept_payroll_authority(ctx: Context<AcceptPayrollAuthority>) -> Result<()> {
+    let vault = &mut ctx.accounts.master_vault;
+    let previous = vault.authority;
+    vault.authority = ctx.accounts.new_authority.key();
+    vault.pending_authority = Pubkey::default();
+
+    msg!("Payroll authority transferred: {} -> {}", previous, vault.authority);
+    Ok(())
+}
+
+/// Pause or resume batch creation and funding across all of payroll
+pub fn set_payroll_paused(ctx: Context<UpdatePayrollConfig>, paused: bool) -> Result<()> {
+    ctx.accounts.master_vault.paused = paused;
+
+    msg!("Payroll paused: {}", paused);
+    Ok(())
+}
+
 /// Create a new payroll batch
 pub fn create_batch(ctx: Context<CreateBatch>, title: String) -> Result<()> {
     require!(title.len() <= 64, ErrorCode::TitleTooLong);
+    ctx.accounts.master_vault.require_not_paused()?;
 
     let master = &mut ctx.accounts.master_vault;
-    let batch = &mut ctx.accounts.batch;
+    let mut batch = ctx.accounts.batch.load_init()?;
 
     batch.master_vault = master.key();
     batch.owner = ctx.accounts.owner.key();
     batch.index = master.batch_count;
-    batch.title = title;
-    batch.employee_count = 0;
-    batch.total_budget = 0;
-    batch.total_paid = 0;
+    batch.set_title(&title)?;
+    batch.pay_schedule = PaySchedule::Streaming as u8;
+    batch.salary_currency = SalaryCurrency::Lamports as u8;
     batch.created_at = Clock::get()?.unix_timestamp;
-    batch.status = BatchStatus::Active;
+    batch.status = BatchStatus::Active as u8;
     batch.vault_bump = ctx.bumps.batch_vault;
     batch.batch_bump = ctx.bumps.batch;
 
@@ -60,22 +105,26 @@ pub fn add_employee(
     require!(stealth_address.len() <= 200, ErrorCode::MetaAddressTooLong);
     require!(salary_rate > 0, ErrorCode::InvalidSalaryRate);
 
-    let batch = &mut ctx.accounts.batch;
-    let employee = &mut ctx.accounts.employee;
+    let batch_key = ctx.accounts.batch.key();
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    let mut employee = ctx.accounts.employee.load_init()?;
     let master = &mut ctx.accounts.master_vault;
 
     let now = Clock::get()?.unix_timestamp;
 
-    employee.batch = batch.key();
+    employee.batch = batch_key;
     employee.wallet = ctx.accounts.employee_wallet.key();
-    employee.index = batch.employee_count;
-    employee.stealth_address = stealth_address;
+    employee.index = batch.next_employee_index();
+    employee.set_stealth_address(&stealth_address)?;
     employee.salary_rate = salary_rate;
     employee.start_time = now;
     employee.last_claimed_at = now;
-    employee.total_claimed = 0;
-    employee.status = EmployeeStatus::Active;
+    employee.status = EmployeeStatus::Active as u8;
     employee.bump = ctx.bumps.employee;
+    employee.batch_paused_seconds = batch.paused_seconds;
+    employee.rent_payer = ctx.accounts.owner.key();
+
+    ctx.accounts.roster.load_mut()?.record(employee.index, employee.wallet, RosterPage::ACTIVE)?;
 
     batch.employee_count = batch.employee_count.checked_add(1)
         .ok_or(ErrorCode::Overflow)?;
@@ -90,6 +139,8 @@ pub fn add_employee(
 /// Fund a batch's vault
 pub fn fund_batch(ctx: Context<FundBatch>, amount: u64) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
+    ctx.accounts.master_vault.require_not_paused()?;
+    require!(ctx.accounts.batch.load()?.status()? == BatchStatus::Active, ErrorCode::BatchNotActive);
 
     system_program::transfer(
         CpiContext::new(
@@ -102,11 +153,13 @@ pub fn fund_batch(ctx: Context<FundBatch>, amount: u64) -> Result<()> {
         amount,
     )?;
 
-    let batch = &mut ctx.accounts.batch;
+    let mut batch = ctx.accounts.batch.load_mut()?;
     let master = &mut ctx.accounts.master_vault;
 
     batch.total_budget = batch.total_budget.checked_add(amount)
         .ok_or(ErrorCode::Overflow)?;
+    batch.remaining_budget = batch.remaining_budget.checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
     master.total_deposited = master.total_deposited.checked_add(amount)
         .ok_or(ErrorCode::Overflow)?;
 
@@ -114,60 +167,878 @@ pub fn fund_batch(ctx: Context<FundBatch>, amount: u64) -> Result<()> {
     Ok(())
 }
 
+/// Deposit into the owner's payroll treasury, to be split across its batches later
+///
+/// Funding one treasury and allocating internally keeps the company wallet
+/// from showing up next to every batch vault it funds.
+pub fn fund_treasury(ctx: Context<FundTreasury>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    ctx.accounts.master_vault.require_not_paused()?;
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let master = &mut ctx.accounts.master_vault;
+    master.total_deposited = master.total_deposited.checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    msg!("Treasury funded: {} lamports", amount);
+    Ok(())
+}
+
+/// Move `amount` from the owner's treasury into one of its batch vaults
+pub fn allocate_to_batch(ctx: Context<AllocateToBatch>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    ctx.accounts.master_vault.require_not_paused()?;
+
+    let treasury = &ctx.accounts.treasury;
+    let remaining = treasury.lamports().checked_sub(amount)
+        .ok_or(ErrorCode::InsufficientFunds)?;
+    require!(
+        remaining == 0 || remaining >= Rent::get()?.minimum_balance(0),
+        ErrorCode::InsufficientFunds
+    );
+
+    let master_key = ctx.accounts.master_vault.key();
+    let owner_key = ctx.accounts.owner.key();
+    let treasury_seeds: &[&[u8]] = &[
+        b"payroll_treasury",
+        master_key.as_ref(),
+        owner_key.as_ref(),
+        &[ctx.bumps.treasury],
+    ];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: treasury.to_account_info(),
+                to: ctx.accounts.batch_vault.to_account_info(),
+            },
+            &[treasury_seeds],
+        ),
+        amount,
+    )?;
+
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    batch.total_budget = batch.total_budget.checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    batch.remaining_budget = batch.remaining_budget.checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    msg!("Allocated {} lamports from treasury to batch {}", amount, batch.index);
+    Ok(())
+}
+
+/// Open the batch's vault for `mint`, next to its SOL vault
+///
+/// Each mint gets one token account owned by the batch vault PDA, so a
+/// batch can pay some employees in SOL and others in tokens.
+pub fn init_batch_token_vault(ctx: Context<InitBatchTokenVault>) -> Result<()> {
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    batch.token_vault_count = batch.token_vault_count.checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    msg!("Batch {} token vault opened for mint {}", batch.index, ctx.accounts.mint.key());
+    Ok(())
+}
+
+/// Fund one of a batch's token vaults
+///
+/// Batch budgets only count lamports; a token vault's balance is what its
+/// employees can be paid.
+pub fn fund_batch_token(ctx: Context<FundBatchToken>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    ctx.accounts.master_vault.require_not_paused()?;
+    let batch_index = {
+        let batch = ctx.accounts.batch.load()?;
+        require!(batch.status()? == BatchStatus::Active, ErrorCode::BatchNotActive);
+        batch.index
+    };
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.funder_token.to_account_info(),
+                to: ctx.accounts.token_vault.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    msg!("Batch {} funded: {} tokens of mint {}", batch_index, amount, ctx.accounts.mint.key());
+    Ok(())
+}
+
+/// Close one of a batch's token vaults, returning its balance to the owner
+pub fn close_batch_token_vault(ctx: Context<CloseBatchTokenVault>) -> Result<()> {
+    let batch_key = ctx.accounts.batch.key();
+    let vault_bump = ctx.accounts.batch.load()?.vault_bump;
+    let vault_seeds: &[&[u8]] = &[
+        b"batch_vault",
+        batch_key.as_ref(),
+        &[vault_bump],
+    ];
+
+    let remaining = ctx.accounts.token_vault.amount;
+    if remaining > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.token_vault.to_account_info(),
+                    to: ctx.accounts.owner_token.to_account_info(),
+                    authority: ctx.accounts.batch_vault.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            remaining,
+        )?;
+    }
+
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token::CloseAccount {
+            account: ctx.accounts.token_vault.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.batch_vault.to_account_info(),
+        },
+        &[vault_seeds],
+    ))?;
+
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    batch.token_vault_count = batch.token_vault_count.saturating_sub(1);
+
+    msg!("Batch {} token vault closed, {} tokens returned", batch.index, remaining);
+    Ok(())
+}
+
 /// Employee claims accrued salary
-pub fn claim_salary(ctx: Context<ClaimSalary>) -> Result<()> {
-    let employee = &mut ctx.accounts.employee;
-    let batch = &mut ctx.accounts.batch;
+///
+/// USD-denominated batches pass the batch's Pyth SOL/USD price update.
+/// Employees with payout splits pass the split recipients as remaining
+/// accounts, in order; their shares are forwarded from the claim.
+pub fn claim_salary<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimSalary<'info>>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let price = batch_price(&*accounts.batch.load()?, accounts.price_update.as_ref())?;
+    let claimed = pay_accrued(
+        &accounts.employee,
+        &accounts.batch,
+        &mut accounts.master_vault,
+        &accounts.batch_vault,
+        accounts.recipient.to_account_info(),
+        &accounts.system_program,
+        price,
+        None,
+    )?;
+    pay_splits(&*accounts.employee.load()?, &accounts.recipient, &accounts.system_program, ctx.remaining_accounts, claimed)
+}
 
-    require!(employee.status == EmployeeStatus::Active, ErrorCode::EmployeeNotActive);
+/// Forward each payout split's share of `claimed` from the employee wallet
+/// to its recipient, the accounts of which are `recipients`, in split order
+fn pay_splits<'info>(
+    employee: &Employee,
+    wallet: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    recipients: &'info [AccountInfo<'info>],
+    claimed: u64,
+) -> Result<()> {
+    let splits = employee.payout_splits();
+    require!(recipients.len() == splits.len(), ErrorCode::InvalidSplitRecipients);
+
+    for (split, recipient) in splits.iter().zip(recipients) {
+        require!(recipient.key() == split.recipient, ErrorCode::InvalidSplitRecipients);
+        let share = split.share(claimed);
+        if share == 0 {
+            continue;
+        }
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: wallet.to_account_info(),
+                    to: recipient.clone(),
+                },
+            ),
+            share,
+        )?;
+        msg!("Payout split: {} lamports to {}", share, split.recipient);
+    }
+    Ok(())
+}
+
+/// Employee claims accrued salary from the batch's vault of its pay mint
+///
+/// Capped by the token vault balance and the employee's claim cap; the
+/// rate is in base units of the mint, whatever the batch salary currency.
+pub fn claim_salary_token(ctx: Context<ClaimSalaryToken>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let batch_key = accounts.batch.key();
+    let employee_key = accounts.employee.key();
+    let batch = accounts.batch.load()?;
+    require!(batch.status()? == BatchStatus::Active, ErrorCode::BatchNotActive);
+    let mut employee = accounts.employee.load_mut()?;
+    require!(employee.status()? == EmployeeStatus::Active, ErrorCode::EmployeeNotActive);
+    require!(!employee.has_payout_splits(), ErrorCode::PayoutSplitsActive);
 
     let now = Clock::get()?.unix_timestamp;
-    let elapsed = now.checked_sub(employee.last_claimed_at)
-        .ok_or(ErrorCode::Overflow)? as u64;
+    require!(batch.claim_interval_elapsed(employee.last_paid_at(), now), ErrorCode::ClaimTooSoon);
+    employee.catch_up(&batch, now)?;
+    let cutoff = batch.accrual_cutoff(now)?;
+    let accrued = employee.accrued(cutoff)?;
+    require!(accrued > 0, ErrorCode::NoSalaryToClaim);
+
+    let available = accounts.token_vault.amount;
+    if accrued > available {
+        emit!(SalaryUnderfunded {
+            employee: employee_key,
+            batch: batch_key,
+            accrued,
+            claimable: available,
+            timestamp: now,
+        });
+    }
+
+    let claim_amount = accrued.min(available);
+    require!(claim_amount > 0, ErrorCode::InsufficientFunds);
+    let claim_amount = claim_amount.min(employee.claim_allowance(now));
+    require!(claim_amount > 0, ErrorCode::ClaimCapExceeded);
 
-    let accrued = employee.salary_rate.checked_mul(elapsed)
+    let vault_seeds: &[&[u8]] = &[
+        b"batch_vault",
+        batch_key.as_ref(),
+        &[batch.vault_bump],
+    ];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: accounts.token_vault.to_account_info(),
+                to: accounts.destination.to_account_info(),
+                authority: accounts.batch_vault.to_account_info(),
+            },
+            &[vault_seeds],
+        ),
+        claim_amount,
+    )?;
+
+    employee.consume_accrued(claim_amount, cutoff)?;
+    employee.record_period_claim(claim_amount, now)?;
+    employee.record_claim_history(claim_amount, now);
+    employee.total_claimed = employee.total_claimed.checked_add(claim_amount)
         .ok_or(ErrorCode::Overflow)?;
 
+    msg!("Salary claimed: {} tokens of mint {}", claim_amount, employee.pay_mint);
+    Ok(())
+}
+
+/// Claim accrued salary to a fresh stealth address, never to `employee.wallet`
+///
+/// The employee wallet signs `Employee::stealth_claim_message` off-chain,
+/// naming the recipient; anyone may submit the claim. `recipient` must be an
+/// unused address, so no payout address is ever reused.
+pub fn claim_salary_stealth(ctx: Context<ClaimSalaryStealth>, valid_until: i64) -> Result<()> {
+    check_authorization_window(valid_until)?;
+
+    let accounts = &mut *ctx.accounts;
+    let employee_key = accounts.employee.key();
+    let mut employee = accounts.employee.load_mut()?;
+    require_ed25519_signature(
+        &accounts.instructions_sysvar,
+        &employee.wallet,
+        &Employee::stealth_claim_message(&employee_key, &accounts.recipient.key(), employee.claim_nonce, valid_until),
+        ErrorCode::SignedMessageMismatch,
+    )?;
+
+    require!(!employee.has_payout_splits(), ErrorCode::PayoutSplitsActive);
+    employee.claim_nonce = employee.claim_nonce.checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+    drop(employee);
+
+    pay_accrued(
+        &accounts.employee,
+        &accounts.batch,
+        &mut accounts.master_vault,
+        &accounts.batch_vault,
+        accounts.recipient.to_account_info(),
+        &accounts.system_program,
+        None,
+        None,
+    )?;
+    Ok(())
+}
+
+/// Register the stealth address `run_payday` pays this employee's salary to
+pub fn set_payout_address(ctx: Context<SetPayoutAddress>, payout_address: Pubkey) -> Result<()> {
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    require!(payout_address != employee.wallet, ErrorCode::StealthRecipientReused);
+    employee.payout_address = payout_address;
+
+    msg!("Payout address registered for employee {}", employee.index);
+    Ok(())
+}
+
+/// Rotate the employee's stealth meta-address, signed by the employee wallet
+pub fn update_employee_stealth(ctx: Context<UpdateEmployeeStealth>, stealth_address: String) -> Result<()> {
+    require!(stealth_address.len() <= 200, ErrorCode::MetaAddressTooLong);
+
+    let employee_key = ctx.accounts.employee.key();
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    employee.set_stealth_address(&stealth_address)?;
+
+    emit!(EmployeeStealthUpdated {
+        employee: employee_key,
+        batch: employee.batch,
+        stealth_address,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Stealth meta-address updated for employee {}", employee.index);
+    Ok(())
+}
+
+/// Opt in or out of owner-pushed salary, signed by the employee wallet
+///
+/// Meant to be set at onboarding, while the employee still holds its key.
+pub fn set_push_payments(ctx: Context<SetPushPayments>, enabled: bool) -> Result<()> {
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    employee.push_enabled = enabled as u8;
+
+    msg!("Push payments {} for employee {}", if enabled { "enabled" } else { "disabled" }, employee.index);
+    Ok(())
+}
+
+/// Push an employee's accrued salary to its wallet without its signature
+/// (batch owner only, for employees that opted in)
+pub fn push_salary(ctx: Context<PushSalary>) -> Result<()> {
+    let employee = ctx.accounts.employee.load()?;
+    require!(employee.push_enabled(), ErrorCode::PushPaymentsNotEnabled);
+    require!(!employee.has_payout_splits(), ErrorCode::PayoutSplitsActive);
+    drop(employee);
+
+    let accounts = &mut *ctx.accounts;
+    pay_accrued(
+        &accounts.employee,
+        &accounts.batch,
+        &mut accounts.master_vault,
+        &accounts.batch_vault,
+        accounts.wallet.to_account_info(),
+        &accounts.system_program,
+        None,
+        None,
+    )?;
+    Ok(())
+}
+
+/// Push accrued salary to every listed employee's payout address (batch owner)
+///
+/// Remaining accounts are (employee, payout address) pairs. Employees that
+/// have nothing claimable or no payout address are skipped; the run stops
+/// early once the vault is empty.
+pub fn run_payday<'info>(ctx: Context<'_, '_, 'info, 'info, RunPayday<'info>>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let (paid_count, total_paid) = pay_payout_pairs(
+        &accounts.batch,
+        &mut accounts.master_vault,
+        &accounts.batch_vault,
+        &accounts.system_program,
+        ctx.remaining_accounts,
+    )?;
+
+    msg!("Payday complete: {} employees paid, {} lamports", paid_count, total_paid);
+    Ok(())
+}
+
+/// Pay every listed employee whose salary is ready (anyone can call)
+///
+/// Same accounts and rules as `run_payday`, so paid employees are skipped on
+/// a rerun; the cranker earns the batch's crank tip per employee paid, at
+/// most `MAX_SALARY_CRANK_TIP_BPS` of the salary paid.
+pub fn crank_claim_ready_salaries<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CrankClaimReadySalaries<'info>>,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let (paid_count, total_paid) = pay_payout_pairs(
+        &accounts.batch,
+        &mut accounts.master_vault,
+        &accounts.batch_vault,
+        &accounts.system_program,
+        ctx.remaining_accounts,
+    )?;
+    pay_crank_tip(
+        &accounts.batch,
+        &accounts.batch_vault,
+        accounts.cranker.to_account_info(),
+        &accounts.system_program,
+        paid_count,
+        total_paid.saturating_mul(MAX_SALARY_CRANK_TIP_BPS) / BPS_DENOMINATOR,
+    )?;
+
+    msg!("Salary crank: {} employees paid, {} lamports", paid_count, total_paid);
+    Ok(())
+}
+
+/// Set the tip paid per crank job from the batch vault (0 = no tip)
+pub fn set_crank_tip(ctx: Context<SetCrankTip>, tip: u64) -> Result<()> {
+    require!(tip <= MAX_CRANK_TIP, ErrorCode::InvalidCrankTip);
+
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    batch.crank_tip = tip;
+
+    msg!("Crank tip for batch {} set to {} lamports", batch.index, tip);
+    Ok(())
+}
+
+/// Require at least `interval_seconds` between two payouts to the same
+/// employee (batch owner only; 0 = no minimum)
+///
+/// Applies to every way salary is paid; `run_payday` and the salary crank
+/// skip employees paid too recently.
+pub fn set_min_claim_interval(ctx: Context<SetMinClaimInterval>, interval_seconds: i64) -> Result<()> {
+    require!(
+        (0..=MAX_MIN_CLAIM_INTERVAL).contains(&interval_seconds),
+        ErrorCode::InvalidClaimInterval
+    );
+
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    batch.min_claim_interval = interval_seconds;
+
+    msg!("Minimum claim interval for batch {} set to {}s", batch.index, interval_seconds);
+    Ok(())
+}
+
+/// Pay a crank's tip for `jobs` completed jobs from the batch vault to
+/// `cranker`, at most `max_tip`
+///
+/// Limited to what the vault holds above rent, so a crank never fails on it.
+pub(crate) fn pay_crank_tip<'info>(
+    batch_loader: &AccountLoader<'info, PayrollBatch>,
+    batch_vault: &SystemAccount<'info>,
+    cranker: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    jobs: u32,
+    max_tip: u64,
+) -> Result<()> {
+    let mut batch = batch_loader.load_mut()?;
+    let available = batch_vault.lamports().saturating_sub(Rent::get()?.minimum_balance(0));
+    let tip = batch.crank_tip.saturating_mul(u64::from(jobs)).min(max_tip).min(available);
+    if tip == 0 {
+        return Ok(());
+    }
+
+    let batch_key = batch_loader.key();
+    let vault_seeds: &[&[u8]] = &[
+        b"batch_vault",
+        batch_key.as_ref(),
+        &[batch.vault_bump],
+    ];
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: batch_vault.to_account_info(),
+                to: cranker,
+            },
+            &[vault_seeds],
+        ),
+        tip,
+    )?;
+    batch.remaining_budget = batch.remaining_budget.saturating_sub(tip);
+
+    msg!("Crank tip: {} lamports for {} jobs", tip, jobs);
+    Ok(())
+}
+
+/// Pay each (employee, payout address) pair whose salary is ready
+///
+/// Returns how many employees were paid and the lamports paid in total.
+fn pay_payout_pairs<'info>(
+    batch_loader: &AccountLoader<'info, PayrollBatch>,
+    master: &mut Account<'info, MasterVault>,
+    batch_vault: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    remaining: &'info [AccountInfo<'info>],
+) -> Result<(u32, u64)> {
+    require!(!remaining.is_empty() && remaining.len().is_multiple_of(2), ErrorCode::InvalidPaydayAccounts);
+    require!(remaining.len() <= MAX_PAYDAY_EMPLOYEES * 2, ErrorCode::BatchTooLarge);
+    require!(batch_loader.load()?.status()? == BatchStatus::Active, ErrorCode::BatchNotActive);
+
+    let now = Clock::get()?.unix_timestamp;
+    let rent = Rent::get()?.minimum_balance(0);
+    let mut paid_count: u32 = 0;
+    let mut total_paid: u64 = 0;
+
+    for pair in remaining.chunks_exact(2) {
+        let employee_loader: AccountLoader<Employee> = AccountLoader::try_from(&pair[0])?;
+        let payout = &pair[1];
+        {
+            let batch = batch_loader.load()?;
+            let mut employee = employee_loader.load_mut()?;
+            require!(employee.batch == batch_loader.key(), ErrorCode::Unauthorized);
+            employee.catch_up(&batch, now)?;
+
+            let payable = employee.status()? == EmployeeStatus::Active
+                && employee.paid_in_sol()
+                && !employee.has_payout_splits()
+                && employee.payout_address != Pubkey::default()
+                && employee.accrued(batch.accrual_cutoff(now)?)? > 0
+                && employee.claim_allowance(now) > 0
+                && employee.budget_share() > 0
+                && batch.claim_interval_elapsed(employee.last_paid_at(), now);
+            if !payable || payout.key() != employee.payout_address {
+                msg!("Payday: employee {} skipped", employee.index);
+                continue;
+            }
+        }
+        if batch_vault.lamports() <= rent {
+            msg!("Payday: batch vault empty, stopping");
+            break;
+        }
+
+        let amount = pay_accrued(
+            &employee_loader,
+            batch_loader,
+            master,
+            batch_vault,
+            payout.clone(),
+            system_program,
+            None,
+            None,
+        )?;
+
+        paid_count += 1;
+        total_paid = total_paid.saturating_add(amount);
+    }
+
+    Ok((paid_count, total_paid))
+}
+
+/// Pay an employee's accrued salary from the batch vault to `to`
+///
+/// With no `amount` this pays the whole accrual, or what the vault, budget
+/// and claim cap allow; with an `amount` it pays exactly that or fails. Only
+/// the time that pays for the payout is consumed, so leftover accrual can be
+/// claimed later.
+#[allow(clippy::too_many_arguments)]
+fn pay_accrued<'info>(
+    employee_loader: &AccountLoader<'info, Employee>,
+    batch_loader: &AccountLoader<'info, PayrollBatch>,
+    master: &mut Account<'info, MasterVault>,
+    batch_vault: &SystemAccount<'info>,
+    to: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    price: Option<UsdPrice>,
+    amount: Option<u64>,
+) -> Result<u64> {
+    let mut batch = batch_loader.load_mut()?;
+    let mut employee = employee_loader.load_mut()?;
+    require!(batch.status()? == BatchStatus::Active, ErrorCode::BatchNotActive);
+    let price = match batch.salary_currency()? {
+        SalaryCurrency::Lamports => None,
+        SalaryCurrency::UsdCents => Some(price.ok_or(ErrorCode::PriceFeedRequired)?),
+    };
+    require!(employee.status()? == EmployeeStatus::Active, ErrorCode::EmployeeNotActive);
+    require!(employee.paid_in_sol(), ErrorCode::InvalidPayMint);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(batch.claim_interval_elapsed(employee.last_paid_at(), now), ErrorCode::ClaimTooSoon);
+    employee.catch_up(&batch, now)?;
+    let cutoff = batch.accrual_cutoff(now)?;
+    let elapsed = cutoff.saturating_sub(employee.last_claimed_at);
+    let accrued_units = employee.accrued(cutoff)?;
+    let accrued = match price {
+        Some(price) => price.to_lamports(accrued_units)?,
+        None => accrued_units,
+    };
+
     require!(accrued > 0, ErrorCode::NoSalaryToClaim);
 
-    let vault_balance = ctx.accounts.batch_vault.lamports();
+    let vault_balance = batch_vault.lamports();
     let rent = Rent::get()?.minimum_balance(0);
     let available = vault_balance.saturating_sub(rent);
 
-    let claim_amount = accrued.min(available);
-    require!(claim_amount > 0, ErrorCode::InsufficientFunds);
+    let claim_amount = match amount {
+        Some(amount) => {
+            require!(accrued >= amount, ErrorCode::NoSalaryToClaim);
+            require!(amount <= employee.budget_share(), ErrorCode::EmployeeBudgetExhausted);
+            require!(available >= amount, ErrorCode::InsufficientFunds);
+            require!(amount <= employee.claim_allowance(now), ErrorCode::ClaimCapExceeded);
+            amount
+        }
+        None => {
+            let funded = available.min(employee.budget_share());
+            if accrued > funded {
+                emit!(SalaryUnderfunded {
+                    employee: employee_loader.key(),
+                    batch: batch_loader.key(),
+                    accrued,
+                    claimable: funded,
+                    timestamp: now,
+                });
+            }
 
-    let batch_key = batch.key();
+            let claim_amount = accrued.min(funded);
+            require!(claim_amount > 0, ErrorCode::InsufficientFunds);
+            let claim_amount = claim_amount.min(employee.claim_allowance(now));
+            require!(claim_amount > 0, ErrorCode::ClaimCapExceeded);
+            claim_amount
+        }
+    };
+
+    let batch_key = batch_loader.key();
     let vault_seeds: &[&[u8]] = &[
         b"batch_vault",
         batch_key.as_ref(),
         &[batch.vault_bump],
     ];
 
+    let recipient = to.key();
     anchor_lang::system_program::transfer(
         CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
+            system_program.to_account_info(),
             anchor_lang::system_program::Transfer {
-                from: ctx.accounts.batch_vault.to_account_info(),
-                to: ctx.accounts.recipient.to_account_info(),
+                from: batch_vault.to_account_info(),
+                to,
             },
             &[vault_seeds],
         ),
         claim_amount,
     )?;
 
-    employee.last_claimed_at = now;
+    // A partial payout only consumes what it pays for
+    let consumed = match price {
+        _ if claim_amount == accrued => accrued_units,
+        Some(price) => price.to_cents_ceil(claim_amount)?.min(accrued_units),
+        None => claim_amount,
+    };
+    employee.consume_accrued(consumed, cutoff)?;
+    employee.record_period_claim(claim_amount, now)?;
+    employee.record_claim_history(claim_amount, now);
+    spend_budget(&mut employee, &mut batch, claim_amount);
     employee.total_claimed = employee.total_claimed.checked_add(claim_amount)
         .ok_or(ErrorCode::Overflow)?;
 
     batch.total_paid = batch.total_paid.checked_add(claim_amount)
         .ok_or(ErrorCode::Overflow)?;
+    batch.remaining_budget = batch.remaining_budget.saturating_sub(claim_amount);
 
-    let master = &mut ctx.accounts.master_vault;
     master.total_paid = master.total_paid.checked_add(claim_amount)
         .ok_or(ErrorCode::Overflow)?;
 
-    msg!("Salary claimed: {} lamports (accrued over {} seconds)", claim_amount, elapsed);
+    msg!("Salary claimed: {} lamports to {} (accrued over {} seconds)", claim_amount, recipient, elapsed);
+    Ok(claim_amount)
+}
+
+/// Claim `amount` of accrued salary into a new pool commitment
+///
+/// `amount` must be a standard denomination. Only the time that pays for it
+/// is consumed, so leftover accrual can be claimed later.
+pub fn claim_salary_to_commitment(
+    ctx: Context<ClaimSalaryToCommitment>,
+    commitment: [u8; 32],
+    amount: u64,
+) -> Result<()> {
+    let header = NoteHeader::current(amount)?;
+    let accounts = &mut *ctx.accounts;
+    require!(!accounts.employee.load()?.has_payout_splits(), ErrorCode::PayoutSplitsActive);
+    let price = batch_price(&*accounts.batch.load()?, accounts.price_update.as_ref())?;
+    pay_accrued(
+        &accounts.employee,
+        &accounts.batch,
+        &mut accounts.master_vault,
+        &accounts.batch_vault,
+        accounts.pool_vault.to_account_info(),
+        &accounts.system_program,
+        price,
+        Some(amount),
+    )?;
+
+    let mut tree = ctx.accounts.commitment_tree.load_mut()?;
+    let leaf_index = append_note(&mut tree, &header, &commitment)?;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_deposit(&ctx.accounts.pool, amount)?;
+
+    msg!("Salary claimed into commitment: {} lamports (leaf {})", amount, leaf_index);
+    Ok(())
+}
+
+/// Claim `amount` of accrued salary into a new pool commitment, with a
+/// separate fee payer
+///
+/// Like `claim_salary_to_commitment`, the salary moves from the batch vault
+/// into the pool behind a note only the employee can open, so nothing in
+/// the transaction names where it is later withdrawn to; `payer` covers the
+/// fees so the employee wallet only signs.
+pub fn claim_salary_to_pool(ctx: Context<ClaimSalaryToPool>, commitment: [u8; 32], amount: u64) -> Result<()> {
+    let header = NoteHeader::current(amount)?;
+    let accounts = &mut *ctx.accounts;
+    require!(!accounts.employee.load()?.has_payout_splits(), ErrorCode::PayoutSplitsActive);
+    let price = batch_price(&*accounts.batch.load()?, accounts.price_update.as_ref())?;
+    pay_accrued(
+        &accounts.employee,
+        &accounts.batch,
+        &mut accounts.master_vault,
+        &accounts.batch_vault,
+        accounts.pool_vault.to_account_info(),
+        &accounts.system_program,
+        price,
+        Some(amount),
+    )?;
+
+    let mut tree = accounts.commitment_tree.load_mut()?;
+    let leaf_index = append_note(&mut tree, &header, &commitment)?;
+
+    accounts.pool_shard.record_deposit(&accounts.pool, amount)?;
+
+    msg!("Salary claimed into the pool: {} lamports (leaf {})", amount, leaf_index);
+    Ok(())
+}
+
+/// SOL/USD price for a payout from `batch`, read from `price_update` when
+/// the batch is USD-denominated
+fn batch_price(batch: &PayrollBatch, price_update: Option<&UncheckedAccount>) -> Result<Option<UsdPrice>> {
+    match batch.salary_currency()? {
+        SalaryCurrency::Lamports => Ok(None),
+        SalaryCurrency::UsdCents => {
+            let price_update = price_update.ok_or(ErrorCode::PriceFeedRequired)?;
+            Ok(Some(read_usd_price(price_update, &batch.price_feed_id)?))
+        }
+    }
+}
+
+/// Read a fresh, tight SOL/USD price from a fully verified Pyth `PriceUpdateV2`
+///
+/// Layout: discriminator (8), write authority (32), verification level
+/// (1, Full), then the price message: feed id (32), price (i64), conf (u64),
+/// exponent (i32), publish time (i64), ...
+fn read_usd_price(price_update: &AccountInfo, feed_id: &[u8; 32]) -> Result<UsdPrice> {
+    const DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+    const VERIFICATION_FULL: u8 = 1;
+
+    require!(*price_update.owner == PYTH_RECEIVER_PROGRAM_ID, ErrorCode::InvalidPriceFeed);
+    let data = price_update.try_borrow_data()?;
+    require!(data.len() >= 101 && data[..8] == DISCRIMINATOR, ErrorCode::InvalidPriceFeed);
+    require!(data[40] == VERIFICATION_FULL, ErrorCode::InvalidPriceFeed);
+    require!(data[41..73] == feed_id[..], ErrorCode::InvalidPriceFeed);
+
+    let word = |offset: usize| <[u8; 8]>::try_from(&data[offset..offset + 8]).unwrap();
+    let price = i64::from_le_bytes(word(73));
+    let conf = u64::from_le_bytes(word(81));
+    let exponent = i32::from_le_bytes(data[89..93].try_into().unwrap());
+    let publish_time = i64::from_le_bytes(word(93));
+
+    require!(price > 0 && (-18..=18).contains(&exponent), ErrorCode::InvalidPriceFeed);
+    let age = Clock::get()?.unix_timestamp.saturating_sub(publish_time);
+    require!(age <= MAX_PRICE_AGE_SECONDS, ErrorCode::StalePrice);
+    let price = price as u64;
+    require!(
+        u128::from(conf) * 10_000 <= u128::from(price) * u128::from(MAX_PRICE_CONF_BPS),
+        ErrorCode::PriceTooUncertain
+    );
+
+    Ok(UsdPrice { price, exponent })
+}
+
+/// Draw a claim from the employee's allocated budget, if it has one
+fn spend_budget(employee: &mut Employee, batch: &mut PayrollBatch, amount: u64) {
+    if employee.budget_tracked() {
+        employee.budget_remaining = employee.budget_remaining.saturating_sub(amount);
+        batch.allocated_budget = batch.allocated_budget.saturating_sub(amount);
+    }
+}
+
+/// Set aside `amount` of the batch vault for one employee
+///
+/// From the first allocation on, the employee's claims are capped at what
+/// remains allocated to it, so accrual never promises more than is funded.
+pub fn allocate_employee_budget(ctx: Context<AllocateEmployeeBudget>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let rent = Rent::get()?.minimum_balance(0);
+    let available = ctx.accounts.batch_vault.lamports().saturating_sub(rent);
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    require!(
+        available.saturating_sub(batch.allocated_budget) >= amount,
+        ErrorCode::InsufficientFunds
+    );
+
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    require!(employee.paid_in_sol(), ErrorCode::InvalidPayMint);
+    employee.budget_tracked = 1;
+    employee.budget_remaining = employee.budget_remaining.checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    batch.allocated_budget = batch.allocated_budget.checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    msg!("Allocated {} lamports to employee {}", amount, employee.index);
+    Ok(())
+}
+
+/// Pause, resume or close a batch (batch owner only)
+///
+/// While the batch is not Active nothing can be claimed and no salary accrues.
+pub fn set_batch_status(ctx: Context<SetBatchStatus>, new_status: BatchStatus) -> Result<()> {
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    batch.set_status(new_status, Clock::get()?.unix_timestamp)?;
+
+    msg!("Batch {} status updated", batch.index);
+    Ok(())
+}
+
+/// Grant `admin` a subset of BatchRole permissions on the batch
+pub fn grant_batch_role(ctx: Context<GrantBatchRole>, admin: Pubkey, permissions: u8) -> Result<()> {
+    require!(
+        permissions != 0 && permissions & !BatchRole::ALL == 0,
+        ErrorCode::InvalidBatchRole
+    );
+
+    let role = &mut ctx.accounts.role;
+    role.batch = ctx.accounts.batch.key();
+    role.admin = admin;
+    role.permissions = permissions;
+    role.bump = ctx.bumps.role;
+
+    msg!("Batch {} role granted to {}: {:#04b}", ctx.accounts.batch.load()?.index, admin, permissions);
+    Ok(())
+}
+
+/// Revoke a secondary admin's role, returning its rent to the owner
+pub fn revoke_batch_role(ctx: Context<RevokeBatchRole>) -> Result<()> {
+    msg!("Batch {} role revoked from {}", ctx.accounts.batch.load()?.index, ctx.accounts.role.admin);
+    Ok(())
+}
+
+/// Propose a new batch owner (e.g. a multisig); it takes effect once accepted
+///
+/// Proposing the default pubkey cancels a pending transfer.
+pub fn transfer_batch_ownership(ctx: Context<TransferBatchOwnership>, new_owner: Pubkey) -> Result<()> {
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    batch.pending_owner = new_owner;
+
+    msg!("Batch {} ownership transfer proposed: {} -> {}", batch.index, batch.owner, new_owner);
+    Ok(())
+}
+
+/// Accept a proposed batch ownership transfer
+pub fn accept_batch_ownership(ctx: Context<AcceptBatchOwnership>) -> Result<()> {
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    let previous = batch.owner;
+    batch.owner = ctx.accounts.new_owner.key();
+    batch.pending_owner = Pubkey::default();
+
+    msg!("Batch {} ownership transferred: {} -> {}", batch.index, previous, batch.owner);
     Ok(())
 }
 
@@ -175,18 +1046,616 @@ pub fn claim_salary(ctx: Context<ClaimSalary>) -> Result<()> {
 pub fn update_salary_rate(ctx: Context<UpdateSalaryRate>, new_rate: u64) -> Result<()> {
     require!(new_rate > 0, ErrorCode::InvalidSalaryRate);
 
-    let employee = &mut ctx.accounts.employee;
+    // Price the time worked so far at the old rate before switching
+    let now = Clock::get()?.unix_timestamp;
+    let batch = ctx.accounts.batch.load()?;
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    employee.catch_up(&batch, now)?;
+    employee.checkpoint(batch.accrual_end(now))?;
     employee.salary_rate = new_rate;
 
     msg!("Salary rate updated to: {} lamports/sec", new_rate);
     Ok(())
 }
 
+/// Schedule a raise or rate ramp step taking effect at `effective_at`
+///
+/// Applied automatically by the first operation on the employee after it is
+/// due; time skipped by a batch pause shifts when the new rate starts counting.
+pub fn schedule_rate_change(ctx: Context<UpdateSalaryRate>, effective_at: i64, rate: u64) -> Result<()> {
+    require!(rate > 0, ErrorCode::InvalidSalaryRate);
+    require!(effective_at > Clock::get()?.unix_timestamp, ErrorCode::InvalidRateChange);
+
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    employee.schedule_rate(RateChange { effective_at, rate })?;
+
+    msg!("Salary rate {} scheduled for {}", rate, effective_at);
+    Ok(())
+}
+
+/// Drop every scheduled rate change that has not taken effect yet
+pub fn clear_rate_schedule(ctx: Context<UpdateSalaryRate>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let batch = ctx.accounts.batch.load()?;
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    employee.catch_up(&batch, now)?;
+    employee.clear_rate_schedule();
+
+    msg!("Rate schedule cleared for employee {}", employee.index);
+    Ok(())
+}
+
+/// Pay an employee from the batch token vault passed in, or from the SOL
+/// vault when none is, at `salary_rate` base units of that currency per second
+///
+/// Nothing may be owed in the old currency, so settle first (e.g. with a
+/// claim earlier in the same transaction); scheduled rate changes are
+/// dropped, as they were priced in the old currency.
+pub fn set_employee_pay_mint(ctx: Context<SetEmployeePayMint>, salary_rate: u64) -> Result<()> {
+    require!(salary_rate > 0, ErrorCode::InvalidSalaryRate);
+
+    let accounts = &mut *ctx.accounts;
+    let pay_mint = accounts.token_vault.as_ref().map_or(Pubkey::default(), |vault| vault.mint);
+    let mut employee = accounts.employee.load_mut()?;
+    require!(pay_mint == Pubkey::default() || !employee.budget_tracked(), ErrorCode::InvalidPayMint);
+
+    let now = Clock::get()?.unix_timestamp;
+    employee.catch_up(&*accounts.batch.load()?, now)?;
+    require!(employee.accrued(now)? == 0, ErrorCode::EmployeeHasAccruedSalary);
+
+    employee.pay_mint = pay_mint;
+    employee.salary_rate = salary_rate;
+    employee.clear_rate_schedule();
+
+    msg!("Employee {} now paid in mint {} at {}/sec", employee.index, pay_mint, salary_rate);
+    Ok(())
+}
+
+/// Cap what an employee may claim per period (0 = uncapped)
+///
+/// Limits the damage of a compromised employee key to one period's cap.
+pub fn set_claim_cap(ctx: Context<SetClaimCap>, max_claim_per_period: u64, period_seconds: i64) -> Result<()> {
+    require!(max_claim_per_period == 0 || period_seconds > 0, ErrorCode::InvalidClaimCap);
+
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    employee.max_claim_per_period = max_claim_per_period;
+    employee.claim_period_seconds = period_seconds;
+    employee.period_start = 0;
+    employee.period_claimed = 0;
+
+    msg!("Claim cap set: {} lamports per {}s", max_claim_per_period, period_seconds);
+    Ok(())
+}
+
+/// Propose splitting every claim of an employee between up to
+/// MAX_PAYOUT_SPLITS recipients (batch owner only)
+///
+/// Weights are basis points of each claim; the employee keeps the rest.
+/// Nothing changes until the employee acknowledges; an empty proposal,
+/// once acknowledged, removes the splits.
+pub fn propose_payout_splits(ctx: Context<ProposePayoutSplits>, splits: Vec<PayoutSplit>) -> Result<()> {
+    require!(splits.len() <= MAX_PAYOUT_SPLITS, ErrorCode::InvalidPayoutSplits);
+    let mut total_bps: u32 = 0;
+    for split in splits.iter() {
+        require!(split.bps > 0 && split.recipient != Pubkey::default(), ErrorCode::InvalidPayoutSplits);
+        total_bps += u32::from(split.bps);
+    }
+    require!(total_bps <= 10_000, ErrorCode::InvalidPayoutSplits);
+
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    employee.propose_payout_splits(&splits);
+
+    msg!("Payout splits proposed for employee {}", employee.index);
+    Ok(())
+}
+
+/// Acknowledge the proposed payout splits, signed by the employee wallet
+///
+/// `splits` must repeat the proposal, so a proposal changed after the
+/// employee reviewed it is never acknowledged.
+pub fn accept_payout_splits(ctx: Context<AcceptPayoutSplits>, splits: Vec<PayoutSplit>) -> Result<()> {
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    let proposed = employee.proposed_payout_splits().ok_or(ErrorCode::PayoutSplitsMismatch)?;
+    require!(proposed == splits, ErrorCode::PayoutSplitsMismatch);
+    employee.accept_proposed_payout_splits();
+
+    msg!("Payout splits active for employee {}: {} recipients", employee.index, employee.split_count);
+    Ok(())
+}
+
+/// Choose how the batch releases salary: streaming, or on discrete paydays
+///
+/// `Periodic` pays up to the latest `payday_offset + k * period_seconds`;
+/// `Monthly` pays up to `payday_offset` seconds into the current month.
+pub fn set_pay_schedule(
+    ctx: Context<SetPaySchedule>,
+    schedule: PaySchedule,
+    period_seconds: i64,
+    payday_offset: i64,
+) -> Result<()> {
+    let valid = match schedule {
+        PaySchedule::Streaming => true,
+        PaySchedule::Periodic => period_seconds > 0 && (0..=Clock::get()?.unix_timestamp).contains(&payday_offset),
+        PaySchedule::Monthly => (0..MAX_MONTHLY_PAYDAY_OFFSET).contains(&payday_offset),
+    };
+    require!(valid, ErrorCode::InvalidPaySchedule);
+
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    batch.pay_schedule = schedule as u8;
+    batch.pay_period_seconds = period_seconds;
+    batch.payday_offset = payday_offset;
+
+    msg!("Pay schedule updated for batch {}", batch.index);
+    Ok(())
+}
+
+/// Denominate the batch's salary rates in lamports or in USD cents
+///
+/// Only while the batch has no employees, since existing rates would
+/// change meaning.
+pub fn set_salary_currency(
+    ctx: Context<SetSalaryCurrency>,
+    currency: SalaryCurrency,
+    price_feed_id: [u8; 32],
+) -> Result<()> {
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    require!(batch.employee_count == 0, ErrorCode::BatchHasEmployees);
+    require!(
+        currency == SalaryCurrency::Lamports || price_feed_id != [0u8; 32],
+        ErrorCode::InvalidPriceFeed
+    );
+
+    batch.salary_currency = currency as u8;
+    batch.price_feed_id = price_feed_id;
+
+    msg!("Salary currency updated for batch {}", batch.index);
+    Ok(())
+}
+
 /// Set employee status
+///
+/// Pausing or terminating halts accrual; that interval is never paid, even
+/// after the employee is reactivated.
 pub fn set_employee_status(ctx: Context<SetEmployeeStatus>, new_status: EmployeeStatus) -> Result<()> {
-    let employee = &mut ctx.accounts.employee;
-    employee.status = new_status;
+    let now = Clock::get()?.unix_timestamp;
+    let batch = ctx.accounts.batch.load()?;
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    // Holds are only placed and lifted through their own instructions
+    require!(employee.status()? != EmployeeStatus::OnHold, ErrorCode::EmployeeOnHold);
+    require!(new_status != EmployeeStatus::OnHold, ErrorCode::EmployeeNotActive);
+    employee.catch_up(&batch, now)?;
+    employee.set_status(new_status, batch.accrual_end(now))?;
+    ctx.accounts.roster.load_mut()?
+        .record(employee.index, employee.wallet, RosterPage::status_code(new_status))?;
 
     msg!("Employee status updated");
     Ok(())
 }
+
+/// Put an active employee on hold over a dispute (batch owner only)
+///
+/// Salary keeps accruing but none of it can be claimed, and the employee
+/// cannot be paused, terminated or moved, until the hold is lifted.
+pub fn place_employee_hold(ctx: Context<SetEmployeeStatus>, reason_hash: [u8; 32]) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let batch = ctx.accounts.batch.load()?;
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    require!(employee.status()? == EmployeeStatus::Active, ErrorCode::EmployeeNotActive);
+    employee.catch_up(&batch, now)?;
+    employee.set_status(EmployeeStatus::OnHold, batch.accrual_end(now))?;
+    employee.hold_reason = reason_hash;
+    employee.held_at = now;
+    ctx.accounts.roster.load_mut()?.record(employee.index, employee.wallet, RosterPage::ON_HOLD)?;
+
+    emit!(EmployeeHoldPlaced {
+        employee: ctx.accounts.employee.key(),
+        batch: ctx.accounts.batch.key(),
+        reason_hash,
+        timestamp: now,
+    });
+
+    msg!("Employee {} put on hold", employee.index);
+    Ok(())
+}
+
+/// Lift a hold, making everything accrued during it claimable (batch owner only)
+pub fn lift_employee_hold(ctx: Context<SetEmployeeStatus>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let batch = ctx.accounts.batch.load()?;
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    require!(employee.status()? == EmployeeStatus::OnHold, ErrorCode::EmployeeNotOnHold);
+    employee.catch_up(&batch, now)?;
+    employee.set_status(EmployeeStatus::Active, batch.accrual_end(now))?;
+    ctx.accounts.roster.load_mut()?.record(employee.index, employee.wallet, RosterPage::ACTIVE)?;
+
+    emit!(EmployeeHoldLifted {
+        employee: ctx.accounts.employee.key(),
+        batch: ctx.accounts.batch.key(),
+        reason_hash: employee.hold_reason,
+        released: employee.accrued(batch.accrual_end(now))?,
+        held_seconds: now.saturating_sub(employee.held_at),
+        timestamp: now,
+    });
+
+    employee.hold_reason = [0u8; 32];
+    employee.held_at = 0;
+
+    msg!("Hold lifted for employee {}", employee.index);
+    Ok(())
+}
+
+/// Terminate an employee, optionally paying `severance_days` of salary at
+/// its current rate from the batch vault to its wallet (batch owner only)
+///
+/// Severance is counted in `severance_paid`, apart from streamed salary;
+/// salary accrued until now stays owed as with any termination.
+pub fn terminate_employee(ctx: Context<TerminateEmployee>, severance_days: Option<u16>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let accounts = &mut *ctx.accounts;
+    let batch_key = accounts.batch.key();
+    let mut batch = accounts.batch.load_mut()?;
+    let mut employee = accounts.employee.load_mut()?;
+    require!(employee.status()? != EmployeeStatus::OnHold, ErrorCode::EmployeeOnHold);
+    require!(employee.status()? != EmployeeStatus::Terminated, ErrorCode::EmployeeNotActive);
+    employee.catch_up(&batch, now)?;
+
+    let mut severance = 0;
+    if let Some(days) = severance_days {
+        require!((1..=MAX_SEVERANCE_DAYS).contains(&days), ErrorCode::InvalidSeverance);
+        require!(batch.salary_currency()? == SalaryCurrency::Lamports, ErrorCode::PriceFeedRequired);
+        require!(employee.paid_in_sol(), ErrorCode::InvalidPayMint);
+        require!(!employee.has_payout_splits(), ErrorCode::PayoutSplitsActive);
+
+        severance = employee.salary_rate.checked_mul(u64::from(days) * 86_400)
+            .ok_or(ErrorCode::Overflow)?;
+        // Unallocated vault funds, plus whatever is still allocated to this employee
+        let rent = Rent::get()?.minimum_balance(0);
+        let available = accounts.batch_vault.lamports().saturating_sub(rent)
+            .saturating_sub(batch.allocated_budget.saturating_sub(employee.budget_remaining));
+        require!(available >= severance, ErrorCode::InsufficientFunds);
+
+        let vault_seeds: &[&[u8]] = &[
+            b"batch_vault",
+            batch_key.as_ref(),
+            &[batch.vault_bump],
+        ];
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: accounts.batch_vault.to_account_info(),
+                    to: accounts.wallet.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            severance,
+        )?;
+
+        spend_budget(&mut employee, &mut batch, severance);
+        employee.severance_paid = employee.severance_paid.checked_add(severance)
+            .ok_or(ErrorCode::Overflow)?;
+        batch.total_paid = batch.total_paid.checked_add(severance)
+            .ok_or(ErrorCode::Overflow)?;
+        batch.remaining_budget = batch.remaining_budget.saturating_sub(severance);
+        accounts.master_vault.total_paid = accounts.master_vault.total_paid.checked_add(severance)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    employee.set_status(EmployeeStatus::Terminated, batch.accrual_end(now))?;
+    accounts.roster.load_mut()?.record(employee.index, employee.wallet, RosterPage::TERMINATED)?;
+
+    msg!("Employee {} terminated, {} lamports severance", employee.index, severance);
+    Ok(())
+}
+
+/// Create roster page `page` of a batch
+pub fn init_roster_page(ctx: Context<InitRosterPage>, page: u32) -> Result<()> {
+    let mut roster = ctx.accounts.roster.load_init()?;
+    roster.batch = ctx.accounts.batch.key();
+    roster.page = page;
+    roster.bump = ctx.bumps.roster;
+
+    msg!("Roster page {} initialized for batch {}", page, ctx.accounts.batch.load()?.index);
+    Ok(())
+}
+
+/// Record an employee's earnings for the current month (anyone can call)
+pub fn snapshot_employee_period(ctx: Context<SnapshotEmployeePeriod>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let batch = ctx.accounts.batch.load()?;
+
+    // Accrue on a copy, so snapshotting never changes the employee account
+    let mut employee = *ctx.accounts.employee.load()?;
+    employee.catch_up(&batch, now)?;
+
+    let snapshot = &mut ctx.accounts.snapshot;
+    snapshot.employee = ctx.accounts.employee.key();
+    snapshot.period = EarningSnapshot::period_of(now);
+    snapshot.accrued = employee.accrued(batch.accrual_end(now))?;
+    snapshot.total_claimed = employee.total_claimed;
+    snapshot.salary_rate = employee.salary_rate;
+    snapshot.taken_at = now;
+    snapshot.bump = ctx.bumps.snapshot;
+
+    msg!("Earning snapshot {} for employee {}", snapshot.period, employee.index);
+    Ok(())
+}
+
+/// Project when the batch budget runs out, from the employees passed as
+/// remaining accounts
+///
+/// Only the SOL vault is projected; employees paid in tokens are skipped.
+pub fn get_batch_runway<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetBatchRunway<'info>>,
+) -> Result<BatchRunway> {
+    let batch = ctx.accounts.batch.load()?;
+    require!(batch.salary_currency()? == SalaryCurrency::Lamports, ErrorCode::PriceFeedRequired);
+
+    let now = Clock::get()?.unix_timestamp;
+    let mut runway = BatchRunway {
+        remaining_budget: batch.remaining_budget,
+        ..BatchRunway::default()
+    };
+
+    for info in ctx.remaining_accounts.iter() {
+        let mut employee = *AccountLoader::<Employee>::try_from(info)?.load()?;
+        require!(employee.batch == ctx.accounts.batch.key(), ErrorCode::Unauthorized);
+        if !employee.paid_in_sol() {
+            continue;
+        }
+        employee.catch_up(&batch, now)?;
+
+        runway.outstanding = runway.outstanding.saturating_add(employee.accrued(batch.accrual_end(now))?);
+        if matches!(employee.status()?, EmployeeStatus::Active | EmployeeStatus::OnHold) {
+            runway.burn_rate = runway.burn_rate.saturating_add(employee.salary_rate);
+        }
+    }
+
+    let uncommitted = runway.remaining_budget.saturating_sub(runway.outstanding);
+    if runway.burn_rate > 0 && batch.status()? == BatchStatus::Active {
+        runway.seconds_remaining = (uncommitted / runway.burn_rate) as i64;
+        runway.depletes_at = now.saturating_add(runway.seconds_remaining);
+    }
+
+    Ok(runway)
+}
+
+/// Close a terminated employee with nothing left to claim, returning its
+/// rent to whoever paid it
+pub fn close_employee(ctx: Context<CloseEmployee>) -> Result<()> {
+    let mut employee = ctx.accounts.employee.load_mut()?;
+    require!(employee.status()? == EmployeeStatus::Terminated, ErrorCode::EmployeeNotTerminated);
+    let now = Clock::get()?.unix_timestamp;
+    let mut batch = ctx.accounts.batch.load_mut()?;
+    employee.catch_up(&batch, now)?;
+    require!(employee.accrued(now)? == 0, ErrorCode::EmployeeHasAccruedSalary);
+
+    ctx.accounts.roster.load_mut()?.record(employee.index, employee.wallet, RosterPage::REMOVED)?;
+
+    batch.allocated_budget = batch.allocated_budget.saturating_sub(employee.budget_remaining);
+    batch.employee_count = batch.employee_count.checked_sub(1)
+        .ok_or(ErrorCode::Overflow)?;
+    batch.removed_count = batch.removed_count.checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let master = &mut ctx.accounts.master_vault;
+    master.total_employees = master.total_employees.saturating_sub(1);
+
+    msg!("Employee {} removed from batch {}", employee.index, batch.index);
+    Ok(())
+}
+
+/// Move an employee to another batch of the same owner
+///
+/// Everything earned so far is paid to the employee wallet from the old
+/// batch, pay schedule aside; the old account is closed and a new one created
+/// in `new_batch`, carrying over the claim history and settings.
+pub fn transfer_employee(ctx: Context<TransferEmployee>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let accounts = &mut *ctx.accounts;
+    let batch_key = accounts.batch.key();
+    let mut batch = accounts.batch.load_mut()?;
+    let mut old = *accounts.employee.load()?;
+    require!(old.status()? == EmployeeStatus::Active, ErrorCode::EmployeeNotActive);
+    require!(!old.has_payout_splits(), ErrorCode::PayoutSplitsActive);
+    old.catch_up(&batch, now)?;
+
+    let settled = old.accrued(batch.accrual_end(now))?;
+    if settled > 0 {
+        require!(batch.salary_currency()? == SalaryCurrency::Lamports, ErrorCode::PriceFeedRequired);
+        require!(settled <= old.budget_share(), ErrorCode::EmployeeBudgetExhausted);
+        // Unallocated vault funds, plus whatever is still allocated to this employee
+        let rent = Rent::get()?.minimum_balance(0);
+        let available = accounts.batch_vault.lamports().saturating_sub(rent)
+            .saturating_sub(batch.allocated_budget.saturating_sub(old.budget_remaining));
+        require!(available >= settled, ErrorCode::InsufficientFunds);
+
+        let vault_seeds: &[&[u8]] = &[
+            b"batch_vault",
+            batch_key.as_ref(),
+            &[batch.vault_bump],
+        ];
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: accounts.batch_vault.to_account_info(),
+                    to: accounts.wallet.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            settled,
+        )?;
+
+        spend_budget(&mut old, &mut batch, settled);
+        batch.total_paid = batch.total_paid.checked_add(settled)
+            .ok_or(ErrorCode::Overflow)?;
+        batch.remaining_budget = batch.remaining_budget.saturating_sub(settled);
+        accounts.master_vault.total_paid = accounts.master_vault.total_paid.checked_add(settled)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    batch.allocated_budget = batch.allocated_budget.saturating_sub(old.budget_remaining);
+    batch.employee_count = batch.employee_count.checked_sub(1)
+        .ok_or(ErrorCode::Overflow)?;
+    batch.removed_count = batch.removed_count.checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let new_batch_key = accounts.new_batch.key();
+    let mut new_batch = accounts.new_batch.load_mut()?;
+    let mut employee = accounts.new_employee.load_init()?;
+    employee.batch = new_batch_key;
+    employee.wallet = old.wallet;
+    employee.index = new_batch.next_employee_index();
+    employee.stealth_address = old.stealth_address;
+    employee.stealth_address_len = old.stealth_address_len;
+    employee.salary_rate = old.salary_rate;
+    employee.start_time = old.start_time;
+    employee.last_claimed_at = now;
+    employee.total_claimed = old.total_claimed.checked_add(settled)
+        .ok_or(ErrorCode::Overflow)?;
+    employee.status = EmployeeStatus::Active as u8;
+    employee.bump = ctx.bumps.new_employee;
+    employee.claim_nonce = old.claim_nonce;
+    employee.max_claim_per_period = old.max_claim_per_period;
+    employee.claim_period_seconds = old.claim_period_seconds;
+    employee.period_start = old.period_start;
+    employee.period_claimed = old.period_claimed;
+    employee.payout_address = old.payout_address;
+    employee.push_enabled = old.push_enabled;
+    employee.batch_paused_seconds = new_batch.paused_seconds;
+    employee.rate_schedule = old.rate_schedule;
+    employee.rate_schedule_len = old.rate_schedule_len;
+    employee.pay_mint = old.pay_mint;
+    employee.proposed_split_recipients = old.proposed_split_recipients;
+    employee.proposed_split_bps = old.proposed_split_bps;
+    employee.proposed_split_count = old.proposed_split_count;
+    employee.split_proposal_pending = old.split_proposal_pending;
+    employee.severance_paid = old.severance_paid;
+    employee.rent_payer = accounts.owner.key();
+    employee.claim_history = old.claim_history;
+    employee.claim_history_next = old.claim_history_next;
+    if settled > 0 {
+        employee.record_claim_history(settled, now);
+    }
+
+    accounts.roster.load_mut()?.record(old.index, old.wallet, RosterPage::REMOVED)?;
+    accounts.new_roster.load_mut()?.record(employee.index, employee.wallet, RosterPage::ACTIVE)?;
+
+    new_batch.employee_count = new_batch.employee_count.checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    msg!(
+        "Employee moved from batch {} to batch {} (index {}), {} lamports settled",
+        batch.index, new_batch.index, employee.index, settled
+    );
+    Ok(())
+}
+
+/// Close an empty batch, returning whatever is left in its vault to the owner
+pub fn close_batch(ctx: Context<CloseBatch>) -> Result<()> {
+    let batch = ctx.accounts.batch.load()?;
+    require!(batch.employee_count == 0, ErrorCode::BatchHasEmployees);
+    require!(batch.token_vault_count == 0, ErrorCode::BatchHasTokenVaults);
+    let (batch_index, vault_bump) = (batch.index, batch.vault_bump);
+    drop(batch);
+
+    let batch_key = ctx.accounts.batch.key();
+    let vault_seeds: &[&[u8]] = &[
+        b"batch_vault",
+        batch_key.as_ref(),
+        &[vault_bump],
+    ];
+
+    // Draining the vault completely closes it
+    let remaining = ctx.accounts.batch_vault.lamports();
+    if remaining > 0 {
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.batch_vault.to_account_info(),
+                    to: ctx.accounts.owner.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            remaining,
+        )?;
+    }
+
+    let master = &mut ctx.accounts.master_vault;
+    master.closed_batches = master.closed_batches.checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    msg!("Batch {} closed, {} lamports returned", batch_index, remaining);
+    Ok(())
+}
+
+/// Convert a batch from the Borsh layout to the zero-copy one
+///
+/// Every field carries over unchanged; fields the Borsh layout lacked start
+/// at zero, which is how the batch behaved without them.
+pub fn migrate_payroll_batch(ctx: Context<MigratePayrollBatch>) -> Result<()> {
+    let info = ctx.accounts.batch.to_account_info();
+    let legacy = LegacyPayrollBatch::read(&info.try_borrow_data()?)?;
+    let expected_address = Pubkey::create_program_address(
+        &[b"batch", legacy.master_vault.as_ref(), &legacy.index.to_le_bytes(), &[legacy.batch_bump]],
+        &crate::ID,
+    ).map_err(|_| ErrorCode::InvalidLegacyPayrollAccount)?;
+    require_keys_eq!(expected_address, info.key(), ErrorCode::InvalidLegacyPayrollAccount);
+
+    resize_for_migration(&info, PayrollBatch::SPACE, &ctx.accounts.payer, &ctx.accounts.system_program)?;
+    let mut data = info.try_borrow_mut_data()?;
+    data[..8].copy_from_slice(PayrollBatch::DISCRIMINATOR);
+    legacy.migrate(bytemuck::from_bytes_mut(&mut data[8..8 + std::mem::size_of::<PayrollBatch>()]))?;
+
+    msg!("Batch {} migrated to the zero-copy layout", legacy.index);
+    Ok(())
+}
+
+/// Convert an employee from the Borsh layout to the zero-copy one
+///
+/// Like `migrate_payroll_batch`, nothing about the employee changes.
+pub fn migrate_employee(ctx: Context<MigrateEmployee>) -> Result<()> {
+    let info = ctx.accounts.employee.to_account_info();
+    let legacy = LegacyEmployee::read(&info.try_borrow_data()?)?;
+    let expected_address = Pubkey::create_program_address(
+        &[b"employee", legacy.batch.as_ref(), &legacy.index.to_le_bytes(), &[legacy.bump]],
+        &crate::ID,
+    ).map_err(|_| ErrorCode::InvalidLegacyPayrollAccount)?;
+    require_keys_eq!(expected_address, info.key(), ErrorCode::InvalidLegacyPayrollAccount);
+
+    resize_for_migration(&info, Employee::SPACE, &ctx.accounts.payer, &ctx.accounts.system_program)?;
+    let mut data = info.try_borrow_mut_data()?;
+    data[..8].copy_from_slice(Employee::DISCRIMINATOR);
+    legacy.migrate(bytemuck::from_bytes_mut(&mut data[8..8 + std::mem::size_of::<Employee>()]))?;
+
+    msg!("Employee {} migrated to the zero-copy layout", legacy.index);
+    Ok(())
+}
+
+/// Resize a migrating account to `space` zeroed bytes, `payer` topping up its rent
+fn resize_for_migration<'info>(
+    info: &AccountInfo<'info>,
+    space: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let shortfall = Rent::get()?.minimum_balance(space).saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    info.resize(space)?;
+    info.try_borrow_mut_data()?.fill(0);
+    Ok(())
+}
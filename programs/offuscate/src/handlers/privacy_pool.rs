@@ -4,13 +4,85 @@
 
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::constants::{ALLOWED_AMOUNTS, MIN_DELAY_SECONDS, MAX_DELAY_SECONDS};
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar::slot_hashes::PodSlotHashes;
+use crate::constants::{
+    ALLOWED_AMOUNTS, CHURN_VAULT_COUNT, DENOMINATION_COUNT, MIN_DELAY_SECONDS, MAX_DELAY_SECONDS, MAX_BATCH_CLAIMS,
+    CLAIM_WINDOW_SECONDS, PENDING_VOID_TIMEOUT_SECONDS, MAX_CLAIM_TIP_BPS, MAX_CLAIM_SPLITS, MIN_SPLIT_OUTPUT, VAULT_SHARD_COUNT,
+    MAX_MEMO_LEN, MAX_SWAP_PROGRAMS, BPS_DENOMINATOR, SHUFFLE_SEED_DELAY_SLOTS,
+    DEFAULT_RESCUE_DELAY_EPOCHS, MIN_RESCUE_DELAY_EPOCHS, MAX_RESCUE_DELAY_EPOCHS,
+};
 use crate::errors::ErrorCode;
+use crate::events::{BatchClaimCompleted, BatchClaimItem, BatchClaimStatus, DepositMemo, PoolReconciled};
+use crate::state::{EpochStats, PrivacyPool, PoolShard, PendingWithdraw, PendingStatus, WithdrawQueue};
 use crate::instructions::{
-    InitPrivacyPool, PoolDeposit, RequestWithdraw, ClaimWithdraw,
-    GetPoolStats, BatchClaimWithdraw, InitChurnVault, PoolChurn, PoolUnchurn,
+    MigratePrivacyPool, InitPrivacyPool, InitPoolShard, DrainLegacyVault, UpdatePoolConfig, AcceptPoolAuthority, SweepDust, PoolDeposit, RequestWithdraw, ClaimWithdraw, ReopenWithdraw,
+    OfferClaimTip, ExecuteTippedClaim, ExpirePendingWithdraw, ClosePendingWithdraw, MigratePendingWithdraw, InitWithdrawQueue, RequestWithdrawQueued, ShuffleWithdrawQueue,
+    ReleaseQueuedWithdraw, GetPendingStatus, GetPoolStats, InitEpochSnapshot, FinalizeEpochSnapshot, ReconcilePool, BatchClaimWithdraw, InitChurnVault, PoolChurn, PoolUnchurn,
 };
 
+/// Resize the pool account created with the original layout
+///
+/// The original fields keep their offsets and values. The new tail is
+/// zeroed, then given the settings `init` would choose: the signer becomes
+/// the authority, and the shard vault addresses and bumps are derived (the
+/// byte that held the pre-shard vault bump is now shard 0's). Deposits in the
+/// pre-shard vault are booked later by `init_pool_shard` and `drain_legacy_vault`.
+pub fn migrate_pool(ctx: Context<MigratePrivacyPool>) -> Result<()> {
+    let info = ctx.accounts.pool.to_account_info();
+    let old_len = info.data_len();
+    require!(
+        info.try_borrow_data()?.starts_with(PrivacyPool::DISCRIMINATOR),
+        ErrorCode::InvalidPoolAccount
+    );
+    require!(old_len < PrivacyPool::SPACE, ErrorCode::PoolAccountCurrent);
+
+    let shortfall = Rent::get()?.minimum_balance(PrivacyPool::SPACE)
+        .saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    info.resize(PrivacyPool::SPACE)?;
+    let mut data = info.try_borrow_mut_data()?;
+    data[old_len..].fill(0);
+
+    let mut pool = PrivacyPool::try_deserialize(&mut &data[..])?;
+    let epoch = Clock::get()?.epoch;
+    pool.authority = ctx.accounts.authority.key();
+    pool.outflow_epoch = epoch;
+    pool.stats_epoch = epoch;
+    pool.previous_stats_epoch = epoch;
+    pool.rescue_delay_epochs = DEFAULT_RESCUE_DELAY_EPOCHS;
+    pool.relay_congestion_bps = BPS_DENOMINATOR as u32;
+    set_vault_shards(&mut pool);
+    pool.try_serialize(&mut &mut data[..])?;
+
+    msg!("Privacy Pool migrated: {} -> {} bytes", old_len, PrivacyPool::SPACE);
+
+    Ok(())
+}
+
+/// Derive the shard vault addresses and bumps
+///
+/// Shard vaults are plain system accounts, so only their addresses are needed.
+fn set_vault_shards(pool: &mut PrivacyPool) {
+    for shard in 0..VAULT_SHARD_COUNT {
+        let (address, bump) = Pubkey::find_program_address(&[b"pool_vault", &[shard as u8]], &crate::ID);
+        pool.vault_shards[shard] = address;
+        pool.vault_bumps[shard] = bump;
+    }
+}
+
 /// Initialize the global privacy pool
 pub fn init(ctx: Context<InitPrivacyPool>) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
@@ -20,37 +92,318 @@ pub fn init(ctx: Context<InitPrivacyPool>) -> Result<()> {
     pool.withdraw_count = 0;
     pool.churn_count = 0;
     pool.bump = ctx.bumps.pool;
-    pool.vault_bump = ctx.bumps.pool_vault;
+    pool.denomination_deposits = [0; DENOMINATION_COUNT];
+    pool.denomination_withdrawals = [0; DENOMINATION_COUNT];
+    pool.total_reserved = 0;
+    pool.void_count = 0;
+    pool.authority = ctx.accounts.authority.key();
+    pool.pending_authority = Pubkey::default();
+    pool.max_epoch_outflow = 0;
+    pool.outflow_epoch = Clock::get()?.epoch;
+    pool.epoch_outflow = 0;
+    pool.total_dust_swept = 0;
+    pool.next_deposit_shard = 0;
+    pool.vault_balance = 0;
+    pool.swap_programs = [Pubkey::default(); MAX_SWAP_PROGRAMS];
+    pool.standard_deposits_only = false;
+    pool.stats_epoch = pool.outflow_epoch;
+    pool.current_epoch_stats = EpochStats::default();
+    pool.previous_stats_epoch = pool.outflow_epoch;
+    pool.previous_epoch_stats = EpochStats::default();
+    pool.paused = false;
+    pool.paused_epoch = 0;
+    pool.rescue_delay_epochs = DEFAULT_RESCUE_DELAY_EPOCHS;
+    pool.max_relay_fee_bps = 0;
+    pool.relay_base_fee = 0;
+    pool.relay_congestion_bps = BPS_DENOMINATOR as u32;
+    pool.relayer_allowlist_enabled = false;
+    pool.max_relayer_claims_per_epoch = 0;
+    pool.rescue_delay_locked = false;
+    pool.resumed_epoch = 0;
+    set_vault_shards(pool);
 
     msg!("Privacy Pool initialized");
-    msg!("Pool vault: {}", ctx.accounts.pool_vault.key());
+    msg!("Pool vault shards: {:?}", pool.vault_shards);
 
     Ok(())
 }
 
-/// Deposit SOL into the privacy pool
-pub fn deposit(ctx: Context<PoolDeposit>, amount: u64) -> Result<()> {
-    require!(amount > 0, ErrorCode::InvalidAmount);
+/// Create the booking account of vault shard `index`
+///
+/// The shard takes over its vault's share of the pool's booked balance, and
+/// shard 0 the reservations of pending withdrawals made before pool shards
+/// existed (they all carry shard 0).
+pub fn init_pool_shard(ctx: Context<InitPoolShard>, index: u8) -> Result<()> {
+    let epoch = Clock::get()?.epoch;
+    let pool = &mut ctx.accounts.pool;
+    let booked = ctx.accounts.pool_vault.lamports().min(pool.vault_balance);
+    pool.vault_balance -= booked;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.index = index;
+    shard.bump = ctx.bumps.pool_shard;
+    shard.vault_balance = booked;
+    shard.total_reserved = 0;
+    if index == 0 {
+        shard.total_reserved = pool.total_reserved;
+        pool.total_reserved = 0;
+    }
+    shard.total_deposited = 0;
+    shard.total_withdrawn = 0;
+    shard.deposit_count = 0;
+    shard.withdraw_count = 0;
+    shard.churn_count = 0;
+    shard.void_count = 0;
+    shard.denomination_deposits = [0; DENOMINATION_COUNT];
+    shard.denomination_withdrawals = [0; DENOMINATION_COUNT];
+    shard.outflow_epoch = epoch;
+    shard.epoch_outflow = 0;
+    shard.stats_epoch = epoch;
+    shard.current_epoch_stats = EpochStats::default();
+    shard.previous_stats_epoch = epoch;
+    shard.previous_epoch_stats = EpochStats::default();
+
+    msg!("Pool shard {} initialized: {} lamports booked", index, booked);
+
+    Ok(())
+}
 
+/// Move every lamport of the pre-shard `[b"pool_vault"]` vault into a shard
+///
+/// Deposits made before sharding stay withdrawable: once drained, their
+/// lamports are booked on the receiving shard like any other balance.
+pub fn drain_legacy_vault(ctx: Context<DrainLegacyVault>) -> Result<()> {
+    let amount = ctx.accounts.legacy_vault.lamports();
+    require!(amount > 0, ErrorCode::LegacyVaultEmpty);
+
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &[ctx.bumps.legacy_vault]]];
     system_program::transfer(
-        CpiContext::new(
+        CpiContext::new_with_signer(
             ctx.accounts.system_program.to_account_info(),
             system_program::Transfer {
-                from: ctx.accounts.depositor.to_account_info(),
+                from: ctx.accounts.legacy_vault.to_account_info(),
                 to: ctx.accounts.pool_vault.to_account_info(),
             },
+            signer_seeds,
         ),
         amount,
     )?;
 
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.credit_vault(amount)?;
+
+    msg!("Legacy vault drained: {} lamports to shard {}", amount, shard.index);
+
+    Ok(())
+}
+
+/// Propose a new pool authority (e.g. a multisig); it takes effect once accepted
+///
+/// Proposing the default pubkey cancels a pending transfer.
+pub fn transfer_authority(ctx: Context<UpdatePoolConfig>, new_authority: Pubkey) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
-    pool.total_deposited = pool.total_deposited.checked_add(amount)
-        .ok_or(ErrorCode::Overflow)?;
-    pool.deposit_count = pool.deposit_count.checked_add(1)
+    pool.pending_authority = new_authority;
+
+    msg!("Pool authority transfer proposed: {} -> {}", pool.authority, new_authority);
+
+    Ok(())
+}
+
+/// Accept a proposed pool authority transfer
+pub fn accept_authority(ctx: Context<AcceptPoolAuthority>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let previous = pool.authority;
+    pool.authority = ctx.accounts.new_authority.key();
+    pool.pending_authority = Pubkey::default();
+
+    msg!("Pool authority transferred: {} -> {}", previous, pool.authority);
+
+    Ok(())
+}
+
+/// Set the maximum lamports that may leave the pool per epoch (0 = unlimited)
+pub fn set_outflow_limit(ctx: Context<UpdatePoolConfig>, max_epoch_outflow: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.max_epoch_outflow = max_epoch_outflow;
+
+    msg!("Pool epoch outflow limit set: {} lamports", max_epoch_outflow);
+
+    Ok(())
+}
+
+/// Require (or stop requiring) deposits to use the standard denominations
+pub fn set_standard_deposits(ctx: Context<UpdatePoolConfig>, enabled: bool) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.standard_deposits_only = enabled;
+
+    msg!("Standard deposits only: {}", enabled);
+
+    Ok(())
+}
+
+/// Pause or resume every deposit and withdrawal path
+///
+/// A pause starting less than one rescue delay after the last one ended
+/// continues it, so a brief resume cannot restart the self-rescue wait.
+pub fn set_paused(ctx: Context<UpdatePoolConfig>, paused: bool) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let epoch = Clock::get()?.epoch;
+    if paused && !pool.paused {
+        if pool.resumed_epoch == 0 || epoch >= pool.resumed_epoch.saturating_add(pool.rescue_delay()) {
+            pool.paused_epoch = epoch;
+        }
+    } else if !paused && pool.paused {
+        pool.resumed_epoch = epoch;
+    }
+    pool.paused = paused;
+
+    msg!("Pool paused: {}", paused);
+
+    Ok(())
+}
+
+/// Set how many paused epochs pass before users may self-rescue
+///
+/// Can be set once, within MIN_RESCUE_DELAY_EPOCHS..=MAX_RESCUE_DELAY_EPOCHS
+/// and not while paused, so the escape hatch can neither be switched off
+/// nor pushed back once users rely on it.
+pub fn set_rescue_delay(ctx: Context<UpdatePoolConfig>, rescue_delay_epochs: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    require!(!pool.paused && !pool.rescue_delay_locked, ErrorCode::RescueDelayLocked);
+    require!(
+        (MIN_RESCUE_DELAY_EPOCHS..=MAX_RESCUE_DELAY_EPOCHS).contains(&rescue_delay_epochs),
+        ErrorCode::InvalidRescueDelay
+    );
+    pool.rescue_delay_epochs = rescue_delay_epochs;
+    pool.rescue_delay_locked = true;
+
+    msg!("Self-rescue delay set: {} epochs", rescue_delay_epochs);
+
+    Ok(())
+}
+
+/// Set the largest relayer fee, in basis points of the withdrawn amount
+pub fn set_max_relay_fee(ctx: Context<UpdatePoolConfig>, max_relay_fee_bps: u16) -> Result<()> {
+    require!(max_relay_fee_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidFeeBps);
+
+    let pool = &mut ctx.accounts.pool;
+    pool.max_relay_fee_bps = max_relay_fee_bps;
+
+    msg!("Max relayer fee set: {} bps", max_relay_fee_bps);
+
+    Ok(())
+}
+
+/// Set the required relayer fee: `base_fee` lamports times `congestion_bps` / 10000
+pub fn set_relay_fee_schedule(ctx: Context<UpdatePoolConfig>, base_fee: u64, congestion_bps: u32) -> Result<()> {
+    require!(congestion_bps as u64 >= BPS_DENOMINATOR, ErrorCode::InvalidCongestionMultiplier);
+
+    let pool = &mut ctx.accounts.pool;
+    pool.relay_base_fee = base_fee;
+    pool.relay_congestion_bps = congestion_bps;
+
+    msg!("Relayer fee schedule set: {} lamports x {} bps", base_fee, congestion_bps);
+
+    Ok(())
+}
+
+/// Turn relayer allowlist mode on or off
+///
+/// While on, relayed instructions only accept relayers on the allowlist.
+pub fn set_relayer_allowlist_mode(ctx: Context<UpdatePoolConfig>, enabled: bool) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.relayer_allowlist_enabled = enabled;
+
+    msg!("Relayer allowlist mode: {}", if enabled { "on" } else { "off" });
+
+    Ok(())
+}
+
+/// Set how many relayed claims one relayer may submit per epoch (0 = unlimited)
+pub fn set_relayer_rate_limit(ctx: Context<UpdatePoolConfig>, max_claims_per_epoch: u32) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.max_relayer_claims_per_epoch = max_claims_per_epoch;
+
+    msg!("Relayer claim limit set: {} per epoch", max_claims_per_epoch);
+
+    Ok(())
+}
+
+/// Move balance that can never match a denomination into the treasury
+///
+/// Dust is whatever is left of the shard's unreserved booked balance after
+/// removing whole multiples of the smallest denomination. The treasury must
+/// already be rent-exempt, since dust alone rarely is.
+pub fn sweep_dust(ctx: Context<SweepDust>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let dust = ctx.accounts.pool_shard.unreserved_balance() % ALLOWED_AMOUNTS[0];
+    require!(dust > 0, ErrorCode::NoDustToSweep);
+
+    let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        dust,
+    )?;
+
+    ctx.accounts.pool_shard.debit_vault(dust)?;
+    let pool = &mut ctx.accounts.pool;
+    pool.total_dust_swept = pool.total_dust_swept.checked_add(dust)
         .ok_or(ErrorCode::Overflow)?;
 
+    msg!("Dust swept: {} lamports to treasury", dust);
+    msg!("Total dust swept: {} lamports", pool.total_dust_swept);
+
+    Ok(())
+}
+
+/// Deposit SOL into the privacy pool, optionally with an encrypted memo
+pub fn deposit(ctx: Context<PoolDeposit>, amount: u64, memo: Option<Vec<u8>>) -> Result<()> {
+    ctx.accounts.pool.check_deposit_amount(amount)?;
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.depositor.to_account_info(),
+                to: ctx.accounts.pool_vault.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_deposit(&ctx.accounts.pool, amount)?;
+
     msg!("Pool deposit: {} lamports", amount);
-    msg!("Pool total: {} lamports", pool.total_deposited);
+    msg!("Shard total: {} lamports", shard.total_deposited);
+
+    post_memo(memo, None)
+}
+
+/// Emit an encrypted deposit memo, if one was attached
+pub(crate) fn post_memo(memo: Option<Vec<u8>>, commitment: Option<[u8; 32]>) -> Result<()> {
+    let Some(ciphertext) = memo else {
+        return Ok(());
+    };
+    require!(
+        !ciphertext.is_empty() && ciphertext.len() <= MAX_MEMO_LEN,
+        ErrorCode::InvalidMemoLength
+    );
+
+    emit!(DepositMemo {
+        commitment,
+        ciphertext,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
@@ -62,27 +415,24 @@ pub fn request_withdraw(ctx: Context<RequestWithdraw>, amount: u64) -> Result<()
         ErrorCode::InvalidWithdrawAmount
     );
 
-    let pool_balance = ctx.accounts.pool_vault.lamports();
-    require!(amount <= pool_balance, ErrorCode::InsufficientPoolFunds);
+    let shard = &mut ctx.accounts.pool_shard;
+    let available = shard.unreserved_balance();
+    require!(amount <= available, ErrorCode::InsufficientPoolFunds);
+    shard.reserve(amount)?;
 
-    let pending = &mut ctx.accounts.pending_withdraw;
+    let mut pending = ctx.accounts.pending_withdraw.load_init()?;
     let clock = Clock::get()?;
     let now = clock.unix_timestamp;
 
-    let recipient_bytes = ctx.accounts.recipient.key().to_bytes();
-    let entropy = clock.slot
-        .wrapping_add(recipient_bytes[0] as u64)
-        .wrapping_add(recipient_bytes[31] as u64)
-        .wrapping_mul(0x5851F42D4C957F2D);
-
-    let delay_range = (MAX_DELAY_SECONDS - MIN_DELAY_SECONDS) as u64;
-    let variable_delay = MIN_DELAY_SECONDS + ((entropy % delay_range) as i64);
+    let variable_delay = variable_delay(clock.slot, &ctx.accounts.recipient.key());
 
     pending.recipient = ctx.accounts.recipient.key();
     pending.amount = amount;
     pending.requested_at = now;
     pending.available_at = now + variable_delay;
-    pending.claimed = false;
+    pending.expires_at = pending.available_at + CLAIM_WINDOW_SECONDS;
+    pending.claimed = 0;
+    pending.shard = ctx.accounts.pool_shard.index;
     pending.bump = ctx.bumps.pending_withdraw;
 
     msg!("Withdrawal requested: {} lamports", amount);
@@ -92,133 +442,740 @@ pub fn request_withdraw(ctx: Context<RequestWithdraw>, amount: u64) -> Result<()
 }
 
 /// Claim a pending withdrawal
-pub fn claim_withdraw(ctx: Context<ClaimWithdraw>) -> Result<()> {
-    let pending = &mut ctx.accounts.pending_withdraw;
+///
+/// With an empty `split_amounts` the full amount goes to the recipient signer.
+/// Otherwise each entry is paid to the matching remaining account, letting the
+/// recipient fan out into several fresh stealth addresses in one claim.
+pub fn claim_withdraw<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimWithdraw<'info>>,
+    split_amounts: Vec<u64>,
+) -> Result<()> {
+    let mut pending = ctx.accounts.pending_withdraw.load_mut()?;
     let now = Clock::get()?.unix_timestamp;
 
-    require!(!pending.claimed, ErrorCode::AlreadyClaimed);
-    require!(now >= pending.available_at, ErrorCode::WithdrawNotReady);
+    pending.check_claimable(now)?;
 
     let amount = pending.amount;
     let pool = &ctx.accounts.pool;
-    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &[pool.vault_bump]]];
+    let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
 
-    system_program::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.pool_vault.to_account_info(),
-                to: ctx.accounts.recipient.to_account_info(),
-            },
-            signer_seeds,
-        ),
-        amount,
-    )?;
+    let outputs: Vec<(AccountInfo<'info>, u64)> = if split_amounts.is_empty() {
+        vec![(ctx.accounts.recipient.to_account_info(), amount)]
+    } else {
+        validate_split(amount, &split_amounts, ctx.remaining_accounts)?;
+        ctx.remaining_accounts.iter().cloned().zip(split_amounts).collect()
+    };
 
-    pending.claimed = true;
+    for (output, output_amount) in outputs {
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.pool_vault.to_account_info(),
+                    to: output.clone(),
+                },
+                signer_seeds,
+            ),
+            output_amount,
+        )?;
 
-    let pool = &mut ctx.accounts.pool;
-    pool.total_withdrawn = pool.total_withdrawn.checked_add(amount)
-        .ok_or(ErrorCode::Overflow)?;
-    pool.withdraw_count = pool.withdraw_count.checked_add(1)
-        .ok_or(ErrorCode::Overflow)?;
+        msg!("Withdrawal claimed: {} lamports to {}", output_amount, output.key());
+    }
+
+    pending.claimed = 1;
 
-    msg!("Withdrawal claimed: {} lamports to {}", amount, ctx.accounts.recipient.key());
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.release_reservation(amount);
+    shard.record_withdrawal(&ctx.accounts.pool, amount)?;
 
     Ok(())
 }
 
-/// Get pool stats
-pub fn get_stats(ctx: Context<GetPoolStats>) -> Result<()> {
+/// Reopen an expired withdrawal with a freshly randomized delay
+pub fn reopen_withdraw(ctx: Context<ReopenWithdraw>) -> Result<()> {
+    let mut pending = ctx.accounts.pending_withdraw.load_mut()?;
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    require!(!pending.is_claimed(), ErrorCode::AlreadyClaimed);
+    require!(!pending.is_voided(), ErrorCode::WithdrawVoided);
+    require!(pending.is_expired(now), ErrorCode::WithdrawNotExpired);
+
+    let variable_delay = variable_delay(clock.slot, &pending.recipient);
+
+    pending.available_at = now + variable_delay;
+    pending.expires_at = pending.available_at + CLAIM_WINDOW_SECONDS;
+    // A queue entry the crank never released becomes an ordinary claim
+    pending.queued = 0;
+
+    msg!("Withdrawal reopened: {} lamports", pending.amount);
+    msg!("Available at: {} (variable delay: {}s)", pending.available_at, variable_delay);
+
+    Ok(())
+}
+
+/// Offer `tip` out of the amount to whoever executes the claim once it is ready
+///
+/// Bots then claim as soon as the withdrawal opens, so the recipient never
+/// has to come online at a time that could be correlated with the request.
+pub fn offer_claim_tip(ctx: Context<OfferClaimTip>, tip: u64) -> Result<()> {
+    let mut pending = ctx.accounts.pending_withdraw.load_mut()?;
+
+    require!(!pending.is_claimed(), ErrorCode::AlreadyClaimed);
+    require!(!pending.is_voided(), ErrorCode::WithdrawVoided);
+    let max_tip = (pending.amount as u128 * MAX_CLAIM_TIP_BPS as u128 / BPS_DENOMINATOR as u128) as u64;
+    require!(tip > 0 && tip <= max_tip, ErrorCode::InvalidClaimTip);
+
+    pending.claim_tip = tip;
+
+    msg!("Claim tip offered: {} lamports", tip);
+
+    Ok(())
+}
+
+/// Claim a tipped withdrawal for its recipient, keeping the tip
+pub fn execute_tipped_claim(ctx: Context<ExecuteTippedClaim>) -> Result<()> {
+    let mut pending = ctx.accounts.pending_withdraw.load_mut()?;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(pending.offers_claim_tip(), ErrorCode::ClaimTipNotOffered);
+    pending.check_claimable(now)?;
+
+    let amount = pending.amount;
+    let tip = pending.claim_tip;
     let pool = &ctx.accounts.pool;
-    let balance = ctx.accounts.pool_vault.lamports();
+    let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    for (output, output_amount) in [
+        (ctx.accounts.recipient.to_account_info(), amount - tip),
+        (ctx.accounts.executor.to_account_info(), tip),
+    ] {
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.pool_vault.to_account_info(),
+                    to: output,
+                },
+                signer_seeds,
+            ),
+            output_amount,
+        )?;
+    }
+
+    pending.claimed = 1;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.release_reservation(amount);
+    shard.record_withdrawal(&ctx.accounts.pool, amount)?;
+
+    msg!("Tipped claim executed: {} lamports to {}", amount - tip, ctx.accounts.recipient.key());
+    msg!("Executor: {} (tip {} lamports)", ctx.accounts.executor.key(), tip);
+
+    Ok(())
+}
+
+/// Void an abandoned pending withdrawal and return its reservation to its shard
+pub fn expire_pending_withdraw(ctx: Context<ExpirePendingWithdraw>) -> Result<()> {
+    let mut pending = ctx.accounts.pending_withdraw.load_mut()?;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(!pending.is_claimed(), ErrorCode::AlreadyClaimed);
+    require!(!pending.is_voided(), ErrorCode::WithdrawVoided);
+    require!(
+        pending.is_abandoned(now, PENDING_VOID_TIMEOUT_SECONDS),
+        ErrorCode::WithdrawNotAbandoned
+    );
+
+    pending.voided = 1;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.release_reservation(pending.amount);
+    shard.void_count = shard.void_count.saturating_add(1);
+
+    msg!("Pending withdrawal voided: {} lamports returned to pool", pending.amount);
+
+    Ok(())
+}
+
+/// Close a settled pending withdrawal
+///
+/// Frees the recipient's pending PDA for a new request and returns the rent
+/// to the recipient; the payer that opened it is never recorded.
+pub fn close_pending_withdraw(ctx: Context<ClosePendingWithdraw>) -> Result<()> {
+    msg!("Pending withdrawal closed for {}", ctx.accounts.recipient.key());
+
+    Ok(())
+}
+
+/// Resize a pending withdrawal created with the original Borsh layout
+///
+/// Those accounts are too short to load with the current layout. The new
+/// bytes are zeroed, so the withdrawal reads as never expiring, not voided,
+/// not queued and without a claim tip, exactly as it behaved before. The shard
+/// byte reads as shard 0, which `init_pool_shard` gives the pre-shard reservations.
+pub fn migrate_pending_withdraw(ctx: Context<MigratePendingWithdraw>) -> Result<()> {
+    let info = ctx.accounts.pending_withdraw.to_account_info();
+    let old_len = info.data_len();
+    {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.get(..PendingWithdraw::DISCRIMINATOR.len()) == Some(PendingWithdraw::DISCRIMINATOR),
+            ErrorCode::BatchInvalidPendingDiscriminator
+        );
+        require!(old_len < PendingWithdraw::SPACE, ErrorCode::PendingWithdrawCurrent);
+        require!(old_len >= PendingWithdraw::LEGACY_SPACE, ErrorCode::BatchInvalidPendingDiscriminator);
+
+        let expected_address = Pubkey::create_program_address(
+            &[b"pending", &data[8..40], &[data[PendingWithdraw::BUMP_OFFSET]]],
+            &crate::ID,
+        ).map_err(|_| ErrorCode::BatchInvalidPendingAddress)?;
+        require_keys_eq!(expected_address, info.key(), ErrorCode::BatchInvalidPendingAddress);
+    }
+
+    let shortfall = Rent::get()?.minimum_balance(PendingWithdraw::SPACE)
+        .saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    info.resize(PendingWithdraw::SPACE)?;
+    info.try_borrow_mut_data()?[old_len..].fill(0);
+
+    msg!("Pending withdrawal migrated: {} -> {} bytes", old_len, PendingWithdraw::SPACE);
+
+    Ok(())
+}
+
+/// Create the withdrawal queue for a current or future time bucket
+pub fn init_withdraw_queue(ctx: Context<InitWithdrawQueue>, bucket: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(bucket >= WithdrawQueue::bucket_at(now), ErrorCode::InvalidQueueBucket);
+
+    let queue = &mut ctx.accounts.withdraw_queue;
+    queue.bucket = bucket;
+    queue.entries = Vec::new();
+    queue.released = 0;
+    queue.shuffled = false;
+    queue.bump = ctx.bumps.withdraw_queue;
+    queue.seed_slot = 0;
+
+    msg!("Withdraw queue created for bucket {} (closes at {})", bucket, queue.closes_at());
+
+    Ok(())
+}
+
+/// Request a withdrawal released through the current bucket's shuffled queue
+///
+/// Instead of its own delay, the withdrawal becomes available when the
+/// bucket closes and is paid out by the crank in the shuffled order.
+pub fn request_withdraw_queued(ctx: Context<RequestWithdrawQueued>, amount: u64) -> Result<()> {
+    require!(
+        ALLOWED_AMOUNTS.contains(&amount),
+        ErrorCode::InvalidWithdrawAmount
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let queue = &mut ctx.accounts.withdraw_queue;
+    require!(queue.bucket == WithdrawQueue::bucket_at(now), ErrorCode::QueueBucketClosed);
+    require!(!queue.is_full(), ErrorCode::QueueFull);
+
+    let shard = &mut ctx.accounts.pool_shard;
+    let available = shard.unreserved_balance();
+    require!(amount <= available, ErrorCode::InsufficientPoolFunds);
+    shard.reserve(amount)?;
+
+    let mut pending = ctx.accounts.pending_withdraw.load_init()?;
+    pending.recipient = ctx.accounts.recipient.key();
+    pending.amount = amount;
+    pending.requested_at = now;
+    pending.available_at = queue.closes_at();
+    pending.expires_at = pending.available_at + CLAIM_WINDOW_SECONDS;
+    pending.claimed = 0;
+    pending.queued = 1;
+    pending.shard = ctx.accounts.pool_shard.index;
+    pending.bump = ctx.bumps.pending_withdraw;
+
+    queue.entries.push(ctx.accounts.pending_withdraw.key());
+
+    msg!("Queued withdrawal requested: {} lamports", amount);
+    msg!("Queue bucket {}: {} entries", queue.bucket, queue.entries.len());
+
+    Ok(())
+}
+
+/// Fix the future slot whose hash will seed a closed bucket's shuffle
+///
+/// The entries can no longer change and the seed slot lies
+/// SHUFFLE_SEED_DELAY_SLOTS ahead, so nobody knows the order when it is
+/// committed to. If the seed slot leaves the SlotHashes window unused, the
+/// shuffle can be armed again.
+pub fn arm_withdraw_queue_shuffle(ctx: Context<ShuffleWithdrawQueue>) -> Result<()> {
+    let clock = Clock::get()?;
+    let queue = &mut ctx.accounts.withdraw_queue;
+
+    require!(clock.unix_timestamp >= queue.closes_at(), ErrorCode::QueueBucketOpen);
+    require!(!queue.shuffled, ErrorCode::QueueAlreadyShuffled);
+    require!(!queue.seed_armed(clock.slot), ErrorCode::QueueShuffleArmed);
+
+    queue.seed_slot = clock.slot + SHUFFLE_SEED_DELAY_SLOTS;
+
+    msg!("Withdraw queue bucket {} shuffle seeded by slot {}", queue.bucket, queue.seed_slot);
+
+    Ok(())
+}
+
+/// Shuffle a closed queue bucket into its release order
+///
+/// The seed is the hash of the slot fixed by `arm_withdraw_queue_shuffle`,
+/// which was unknown when the crank committed to it.
+pub fn shuffle_withdraw_queue(ctx: Context<ShuffleWithdrawQueue>) -> Result<()> {
+    let clock = Clock::get()?;
+    let queue = &mut ctx.accounts.withdraw_queue;
+
+    require!(!queue.shuffled, ErrorCode::QueueAlreadyShuffled);
+    require!(queue.seed_armed(clock.slot), ErrorCode::QueueShuffleNotArmed);
+    require!(clock.slot > queue.seed_slot, ErrorCode::QueueSeedNotReady);
+    let seed_hash = PodSlotHashes::fetch()?.get(&queue.seed_slot)?
+        .ok_or(ErrorCode::QueueShuffleNotArmed)?;
+
+    let seed = hashv(&[seed_hash.as_ref(), &queue.bucket.to_le_bytes()]).to_bytes();
+    let mut state = u64::from_le_bytes(seed[..8].try_into().unwrap());
+
+    // Fisher-Yates
+    for i in (1..queue.entries.len()).rev() {
+        state = state
+            .wrapping_mul(0x5851F42D4C957F2D)
+            .wrapping_add(0x14057B7EF767814F);
+        let j = ((state >> 33) % (i as u64 + 1)) as usize;
+        queue.entries.swap(i, j);
+    }
+
+    queue.shuffled = true;
+
+    msg!("Withdraw queue bucket {} shuffled ({} entries)", queue.bucket, queue.entries.len());
+
+    Ok(())
+}
+
+/// Release the next withdrawal of a shuffled queue
+///
+/// Settled entries are skipped so they can never stall the queue, and so are
+/// entries whose PDA was closed and re-created outside this bucket since.
+pub fn release_queued_withdraw(ctx: Context<ReleaseQueuedWithdraw>) -> Result<()> {
+    let queue = &ctx.accounts.withdraw_queue;
+    require!(queue.shuffled, ErrorCode::QueueNotShuffled);
+    require!(queue.next_entry().is_some(), ErrorCode::QueueExhausted);
+
+    let mut pending = ctx.accounts.pending_withdraw.load_mut()?;
+    let amount = pending.amount;
+
+    if pending.is_settled() || !pending.is_queued() || pending.available_at != queue.closes_at() {
+        msg!("Queued withdrawal {} skipped", ctx.accounts.pending_withdraw.key());
+    } else {
+        let pool = &ctx.accounts.pool;
+        let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.pool_vault.to_account_info(),
+                    to: ctx.accounts.recipient.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        pending.claimed = 1;
+
+        let shard = &mut ctx.accounts.pool_shard;
+        shard.release_reservation(amount);
+        shard.record_withdrawal(&ctx.accounts.pool, amount)?;
+
+        msg!("Queued withdrawal released: {} lamports to {}", amount, ctx.accounts.recipient.key());
+    }
+
+    let queue = &mut ctx.accounts.withdraw_queue;
+    queue.released += 1;
+
+    Ok(())
+}
+
+/// Status of a recipient's pending withdrawal, returned as return data
+pub fn get_pending_status(ctx: Context<GetPendingStatus>, _recipient: Pubkey) -> Result<PendingStatus> {
+    let info = ctx.accounts.pending_withdraw.to_account_info();
+    if info.owner != &crate::ID {
+        return Ok(PendingStatus::missing());
+    }
+
+    let data = info.try_borrow_data()?;
+    let discriminator_len = PendingWithdraw::DISCRIMINATOR.len();
+    let body_len = std::mem::size_of::<PendingWithdraw>();
+    if data.get(..discriminator_len) != Some(PendingWithdraw::DISCRIMINATOR)
+        || data.len() < discriminator_len + body_len
+    {
+        return Ok(PendingStatus::missing());
+    }
+
+    let pending: PendingWithdraw =
+        bytemuck::pod_read_unaligned(&data[discriminator_len..discriminator_len + body_len]);
+    let status = PendingStatus::of(&pending, Clock::get()?.unix_timestamp);
+
+    msg!("Pending status: {:?}, {} lamports, {}s remaining", status.state, status.amount, status.seconds_remaining);
+
+    Ok(status)
+}
+
+/// Get pool stats: the pre-shard totals plus every shard's bookings
+pub fn get_stats<'info>(ctx: Context<'_, '_, 'info, 'info, GetPoolStats<'info>>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let shards = load_pool_shards(ctx.remaining_accounts)?;
+    let sum = |field: &dyn Fn(&PoolShard) -> u64| shards.iter().map(|shard| field(shard)).fold(0u64, u64::saturating_add);
+
+    let vault_balance = pool.vault_balance.saturating_add(sum(&|shard| shard.vault_balance));
+    let total_deposited = pool.total_deposited.saturating_add(sum(&|shard| shard.total_deposited));
+    let total_withdrawn = pool.total_withdrawn.saturating_add(sum(&|shard| shard.total_withdrawn));
 
     msg!("=== Privacy Pool Stats ===");
-    msg!("Current balance: {} lamports", balance);
-    msg!("Total deposited: {} lamports", pool.total_deposited);
-    msg!("Total withdrawn: {} lamports", pool.total_withdrawn);
-    msg!("Deposit count: {}", pool.deposit_count);
-    msg!("Withdraw count: {}", pool.withdraw_count);
+    msg!("Current balance: {} lamports ({} shards)", vault_balance, VAULT_SHARD_COUNT);
+    msg!("Total deposited: {} lamports", total_deposited);
+    msg!("Total withdrawn: {} lamports", total_withdrawn);
+    msg!("Deposit count: {}", pool.deposit_count.saturating_add(sum(&|shard| shard.deposit_count)));
+    msg!("Withdraw count: {}", pool.withdraw_count.saturating_add(sum(&|shard| shard.withdraw_count)));
+    msg!("Reserved for pending: {} lamports", pool.total_reserved.saturating_add(sum(&|shard| shard.total_reserved)));
+    msg!("Voided pending: {}", pool.void_count.saturating_add(sum(&|shard| shard.void_count)));
+    msg!("Dust swept: {} lamports", pool.total_dust_swept);
+
+    let epoch = Clock::get()?.epoch;
+    for shard in shards.iter() {
+        let outflow = if shard.outflow_epoch == epoch { shard.epoch_outflow } else { 0 };
+        msg!(
+            "Shard {}: {} lamports booked, epoch outflow {} / {} lamports",
+            shard.index,
+            shard.vault_balance,
+            outflow,
+            pool.shard_outflow_limit()
+        );
+    }
+
+    for (index, amount) in ALLOWED_AMOUNTS.iter().enumerate() {
+        let deposits = pool.denomination_deposits[index]
+            .saturating_add(sum(&|shard| shard.denomination_deposits[index]));
+        let withdrawals = pool.denomination_withdrawals[index]
+            .saturating_add(sum(&|shard| shard.denomination_withdrawals[index]));
+        msg!(
+            "Denomination {} lamports: {} deposits, {} withdrawals, anonymity set {}",
+            amount,
+            deposits,
+            withdrawals,
+            deposits.saturating_sub(withdrawals)
+        );
+    }
+
+    Ok(())
+}
+
+/// Every pool shard, passed as remaining accounts in index order
+///
+/// A shard can only be created at the PDA of its index, so its recorded
+/// index identifies it.
+fn load_pool_shards<'info>(accounts: &'info [AccountInfo<'info>]) -> Result<Vec<Account<'info, PoolShard>>> {
+    require!(accounts.len() == VAULT_SHARD_COUNT, ErrorCode::IncompleteVaultSet);
+    accounts
+        .iter()
+        .enumerate()
+        .map(|(index, info)| {
+            let shard: Account<PoolShard> = Account::try_from(info)?;
+            require!(shard.index as usize == index, ErrorCode::InvalidVaultShard);
+            Ok(shard)
+        })
+        .collect()
+}
+
+/// Create an empty snapshot account for the current or a past epoch
+pub fn init_epoch_snapshot(ctx: Context<InitEpochSnapshot>, epoch: u64) -> Result<()> {
+    require!(epoch <= Clock::get()?.epoch, ErrorCode::InvalidSnapshotEpoch);
+
+    let snapshot = &mut ctx.accounts.snapshot;
+    snapshot.epoch = epoch;
+    snapshot.stats = EpochStats::default();
+    snapshot.finalized = false;
+    snapshot.finalized_at = 0;
+    snapshot.bump = ctx.bumps.snapshot;
+
+    msg!("Epoch snapshot created for epoch {}", epoch);
+
+    Ok(())
+}
+
+/// Copy a finished epoch's activity counters from the pool and its shards
+/// into its snapshot
+///
+/// Each shard retains only the previous epoch with activity, so snapshots
+/// must be finalized before a shard's activity spans two later epochs.
+pub fn finalize_epoch_snapshot<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FinalizeEpochSnapshot<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let shards = load_pool_shards(ctx.remaining_accounts)?;
+    let snapshot = &mut ctx.accounts.snapshot;
+    require!(!snapshot.finalized, ErrorCode::SnapshotAlreadyFinalized);
+    require!(clock.epoch > snapshot.epoch, ErrorCode::EpochNotEnded);
+
+    let mut stats = ctx.accounts.pool.finished_epoch_stats(snapshot.epoch)
+        .ok_or(ErrorCode::SnapshotStatsUnavailable)?;
+    for shard in shards.iter() {
+        let shard_stats = shard.finished_epoch_stats(snapshot.epoch)
+            .ok_or(ErrorCode::SnapshotStatsUnavailable)?;
+        stats = stats.add(&shard_stats);
+    }
+
+    snapshot.stats = stats;
+    snapshot.finalized = true;
+    snapshot.finalized_at = clock.unix_timestamp;
+
+    msg!("Epoch {} snapshot finalized", snapshot.epoch);
+    msg!(
+        "Deposits: {} ({} lamports), withdrawals: {} ({} lamports), churns: {}",
+        stats.deposit_count,
+        stats.deposited,
+        stats.withdraw_count,
+        stats.withdrawn,
+        stats.churn_count
+    );
+
+    Ok(())
+}
+
+/// Compare vault balances with the pool's deposit/withdraw books
+///
+/// Every vault shard, pool shard and churn vault must be passed, so a
+/// balance or booking can never be left out of the comparison.
+pub fn reconcile<'info>(ctx: Context<'_, '_, 'info, 'info, ReconcilePool<'info>>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    require!(
+        ctx.remaining_accounts.len() == 2 * VAULT_SHARD_COUNT + CHURN_VAULT_COUNT as usize,
+        ErrorCode::IncompleteVaultSet
+    );
+    let (shards, rest) = ctx.remaining_accounts.split_at(VAULT_SHARD_COUNT);
+    let (pool_shards, churn_vaults) = rest.split_at(VAULT_SHARD_COUNT);
+    let pool_shards = load_pool_shards(pool_shards)?;
+
+    let mut vault_balance: u64 = 0;
+    for (shard, expected) in shards.iter().zip(pool.vault_shards.iter()) {
+        require_keys_eq!(shard.key(), *expected, ErrorCode::InvalidVaultShard);
+        vault_balance = vault_balance.checked_add(shard.lamports())
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    let mut churned_balance: u64 = 0;
+    for (index, churn_vault) in churn_vaults.iter().enumerate() {
+        let (expected, _) = Pubkey::find_program_address(
+            &[b"churn_vault", (index as u8).to_le_bytes().as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(churn_vault.key(), expected, ErrorCode::InvalidChurnIndex);
+
+        churned_balance = churned_balance.checked_add(churn_vault.lamports())
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    let booked = pool_shards.iter().fold(
+        pool.total_deposited as i128 - pool.total_withdrawn as i128 - pool.total_dust_swept as i128,
+        |booked, shard| booked + shard.total_deposited as i128 - shard.total_withdrawn as i128,
+    );
+    let expected_balance = u64::try_from(booked.max(0)).map_err(|_| ErrorCode::Overflow)?;
+    let held = vault_balance as i128 + churned_balance as i128;
+    let discrepancy = i64::try_from(held - expected_balance as i128)
+        .map_err(|_| ErrorCode::Overflow)?;
+
+    msg!("=== Pool Reconciliation ===");
+    msg!("Vault balance: {} lamports", vault_balance);
+    msg!("Churned balance: {} lamports", churned_balance);
+    msg!("Expected balance: {} lamports", expected_balance);
+    msg!("Discrepancy: {} lamports", discrepancy);
+
+    emit!(PoolReconciled {
+        vault_balance,
+        churned_balance,
+        expected_balance,
+        discrepancy,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
 
 /// Batch claim withdrawals
-pub fn batch_claim<'a, 'b, 'c, 'info>(
-    ctx: Context<'a, 'b, 'c, 'info, BatchClaimWithdraw<'info>>,
+pub fn batch_claim<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchClaimWithdraw<'info>>,
 ) -> Result<()> {
-    let pool = &mut ctx.accounts.pool;
-    let now = Clock::get()?.unix_timestamp;
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
 
-    let remaining = &ctx.remaining_accounts;
+    let remaining = ctx.remaining_accounts;
     require!(remaining.len() >= 2, ErrorCode::BatchTooSmall);
-    require!(remaining.len() % 2 == 0, ErrorCode::BatchInvalidPairs);
-    require!(remaining.len() <= 10, ErrorCode::BatchTooLarge);
+    require!(remaining.chunks_exact(2).remainder().is_empty(), ErrorCode::BatchInvalidPairs);
+    require!(remaining.len() <= MAX_BATCH_CLAIMS * 2, ErrorCode::BatchTooLarge);
+
+    let vault_signer = ctx.accounts.pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
 
-    let num_claims = remaining.len() / 2;
+    let mut results = Vec::with_capacity(remaining.len() / 2);
     let mut total_claimed: u64 = 0;
-    let mut success_count: u64 = 0;
+    let mut success_count: u8 = 0;
 
-    for i in 0..num_claims {
-        let recipient_info = &remaining[i * 2];
-        let pending_info = &remaining[i * 2 + 1];
+    for (i, pair) in remaining.chunks_exact(2).enumerate() {
+        let recipient_info = &pair[0];
+        let pending_info = &pair[1];
 
-        let mut pending_data = pending_info.try_borrow_mut_data()?;
+        let loader = load_batch_pending(i, pending_info)?;
+        let mut pending = loader.load_mut()?;
 
-        if pending_data.len() < 8 + 32 + 8 + 8 + 8 + 1 + 1 {
-            msg!("Skipping invalid pending account at index {}", i);
-            continue;
+        let expected_address = Pubkey::create_program_address(
+            &[b"pending", pending.recipient.as_ref(), &[pending.bump]],
+            &crate::ID,
+        ).map_err(|_| ErrorCode::BatchInvalidPendingAddress)?;
+        if expected_address != pending_info.key() {
+            msg!("Batch claim {}: pending account is not a pending PDA", i);
+            return err!(ErrorCode::BatchInvalidPendingAddress);
         }
 
-        let stored_recipient = Pubkey::try_from(&pending_data[8..40]).unwrap_or_default();
-        let amount = u64::from_le_bytes(pending_data[40..48].try_into().unwrap_or_default());
-        let available_at = i64::from_le_bytes(pending_data[56..64].try_into().unwrap_or_default());
-        let claimed = pending_data[64] != 0;
+        let status = if pending.recipient != recipient_info.key() {
+            BatchClaimStatus::RecipientMismatch
+        } else if pending.is_claimed() {
+            BatchClaimStatus::AlreadyClaimed
+        } else if pending.is_voided() {
+            BatchClaimStatus::Voided
+        } else if pending.is_queued() {
+            BatchClaimStatus::Queued
+        } else if now < pending.available_at {
+            BatchClaimStatus::NotReady
+        } else if pending.is_expired(now) {
+            BatchClaimStatus::Expired
+        } else if pending.shard != ctx.accounts.pool_shard.index {
+            BatchClaimStatus::WrongShard
+        } else if pending.amount > ctx.accounts.pool_vault.lamports() {
+            BatchClaimStatus::InsufficientFunds
+        } else if !ctx.accounts.pool_shard.outflow_allows(&ctx.accounts.pool, pending.amount, clock.epoch) {
+            BatchClaimStatus::OutflowLimited
+        } else {
+            BatchClaimStatus::Claimed
+        };
 
-        if stored_recipient != *recipient_info.key {
-            msg!("Recipient mismatch at index {}", i);
-            continue;
-        }
+        results.push(BatchClaimItem { index: i as u8, amount: pending.amount, status });
 
-        if claimed {
-            msg!("Already claimed at index {}", i);
-            continue;
-        }
-        if now < available_at {
-            msg!("Not ready yet at index {}", i);
-            continue;
-        }
-
-        let pool_balance = ctx.accounts.pool_vault.lamports();
-        if amount > pool_balance {
-            msg!("Insufficient funds for index {}", i);
+        if status != BatchClaimStatus::Claimed {
+            msg!("Batch claim {}: skipped ({:?})", i, status);
             continue;
         }
 
-        **ctx.accounts.pool_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
-        **recipient_info.try_borrow_mut_lamports()? += amount;
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.pool_vault.to_account_info(),
+                    to: recipient_info.clone(),
+                },
+                signer_seeds,
+            ),
+            pending.amount,
+        )?;
 
-        pending_data[64] = 1;
+        pending.claimed = 1;
 
-        total_claimed = total_claimed.saturating_add(amount);
+        ctx.accounts.pool_shard.release_reservation(pending.amount);
+        ctx.accounts.pool_shard.record_withdrawal(&ctx.accounts.pool, pending.amount)?;
+        total_claimed = total_claimed.saturating_add(pending.amount);
         success_count += 1;
 
-        msg!("Batch claim {}: {} lamports to {}", i, amount, recipient_info.key);
-    }
-
-    if success_count > 0 {
-        pool.total_withdrawn = pool.total_withdrawn.saturating_add(total_claimed);
-        pool.withdraw_count = pool.withdraw_count.saturating_add(success_count);
+        msg!("Batch claim {}: {} lamports to {}", i, pending.amount, recipient_info.key);
     }
 
     msg!("Batch withdrawal complete: {} claims, {} total lamports", success_count, total_claimed);
 
+    emit!(BatchClaimCompleted {
+        results,
+        success_count,
+        total_claimed,
+        timestamp: now,
+    });
+
     Ok(())
 }
 
+/// Check a claim split against the pending amount and its output accounts
+fn validate_split(amount: u64, split_amounts: &[u64], outputs: &[AccountInfo]) -> Result<()> {
+    require!(split_amounts.len() <= MAX_CLAIM_SPLITS, ErrorCode::ClaimSplitTooLarge);
+    require!(split_amounts.len() == outputs.len(), ErrorCode::ClaimSplitAccountMismatch);
+    require!(
+        split_amounts.iter().all(|&part| part >= MIN_SPLIT_OUTPUT),
+        ErrorCode::ClaimSplitOutputTooSmall
+    );
+    require!(outputs.iter().all(|output| output.is_writable), ErrorCode::ClaimSplitAccountMismatch);
+
+    let total = split_amounts.iter()
+        .try_fold(0u64, |acc, &part| acc.checked_add(part))
+        .ok_or(ErrorCode::Overflow)?;
+    require!(total == amount, ErrorCode::ClaimSplitSumMismatch);
+
+    Ok(())
+}
+
+/// Pseudo-random claim delay between MIN_DELAY_SECONDS and MAX_DELAY_SECONDS
+pub(crate) fn variable_delay(slot: u64, recipient: &Pubkey) -> i64 {
+    let recipient_bytes = recipient.to_bytes();
+    let entropy = slot
+        .wrapping_add(recipient_bytes[0] as u64)
+        .wrapping_add(recipient_bytes[31] as u64)
+        .wrapping_mul(0x5851F42D4C957F2D);
+
+    let delay_range = (MAX_DELAY_SECONDS - MIN_DELAY_SECONDS) as u64;
+    MIN_DELAY_SECONDS + ((entropy % delay_range) as i64)
+}
+
+/// Strictly validate and load a pending withdrawal passed via remaining accounts
+///
+/// Invalid accounts abort the whole batch instead of being skipped, so a
+/// crafted account can never be silently mixed into a claim.
+pub(crate) fn load_batch_pending<'info>(
+    index: usize,
+    pending_info: &'info AccountInfo<'info>,
+) -> Result<AccountLoader<'info, PendingWithdraw>> {
+    if pending_info.owner != &crate::ID {
+        msg!("Batch claim {}: pending account has wrong owner {}", index, pending_info.owner);
+        return err!(ErrorCode::BatchInvalidPendingOwner);
+    }
+
+    let has_discriminator = pending_info
+        .try_borrow_data()?
+        .get(..PendingWithdraw::DISCRIMINATOR.len())
+        == Some(PendingWithdraw::DISCRIMINATOR);
+    if !has_discriminator {
+        msg!("Batch claim {}: account is not a PendingWithdraw", index);
+        return err!(ErrorCode::BatchInvalidPendingDiscriminator);
+    }
+
+    AccountLoader::<PendingWithdraw>::try_from(pending_info)
+}
+
 /// Initialize a churn vault
 pub fn init_churn_vault(ctx: Context<InitChurnVault>, vault_index: u8) -> Result<()> {
-    require!(vault_index < 3, ErrorCode::InvalidChurnIndex);
+    require!(vault_index < CHURN_VAULT_COUNT, ErrorCode::InvalidChurnIndex);
 
     let churn_state = &mut ctx.accounts.churn_state;
     churn_state.vault_index = vault_index;
@@ -236,11 +1193,12 @@ pub fn init_churn_vault(ctx: Context<InitChurnVault>, vault_index: u8) -> Result
 pub fn churn(ctx: Context<PoolChurn>, amount: u64) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
 
-    let pool_balance = ctx.accounts.pool_vault.lamports();
-    require!(amount <= pool_balance, ErrorCode::InsufficientPoolFunds);
-
     let pool = &ctx.accounts.pool;
-    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &[pool.vault_bump]]];
+    let available = ctx.accounts.pool_shard.unreserved_balance();
+    require!(amount <= available, ErrorCode::InsufficientPoolFunds);
+
+    let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
 
     system_program::transfer(
         CpiContext::new_with_signer(
@@ -258,8 +1216,9 @@ pub fn churn(ctx: Context<PoolChurn>, amount: u64) -> Result<()> {
     churn_state.total_churned = churn_state.total_churned.saturating_add(amount);
     churn_state.churn_count = churn_state.churn_count.saturating_add(1);
 
-    let pool = &mut ctx.accounts.pool;
-    pool.churn_count = pool.churn_count.saturating_add(1);
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.debit_vault(amount)?;
+    shard.record_churn()?;
 
     msg!("Pool churn: {} lamports to vault {}", amount, churn_state.vault_index);
 
@@ -293,6 +1252,8 @@ pub fn unchurn(ctx: Context<PoolUnchurn>, amount: u64) -> Result<()> {
         amount,
     )?;
 
+    ctx.accounts.pool_shard.credit_vault(amount)?;
+
     msg!("Pool unchurn: {} lamports from vault {}", amount, churn_state.vault_index);
 
     Ok(())
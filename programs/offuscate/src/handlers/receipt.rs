@@ -3,37 +3,75 @@
 //! Business logic for anonymous receipt operations.
 
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::hash::hashv;
+use crate::constants::{
+    RECEIPT_VERSION, RECEIPT_DOMAIN, RECEIPT_LEAF_DOMAIN, RECEIPT_TIME_BUCKET_SECONDS, RECEIPT_TREE_CLASS,
+    COMMITMENT_TREE_DEPTH,
+};
 use crate::errors::ErrorCode;
-use crate::instructions::{CreateReceipt, VerifyReceipt, VerifyReceiptBlind};
+use crate::events::{ReceiptLeafInserted, ReceiptMembershipProven};
+use crate::instructions::{
+    CreateReceipt, VerifyReceipt, VerifyReceiptBlind, InitReceiptTree, ProveReceiptMembership,
+};
+use crate::state::MerkleProof;
+
+/// Create the receipt membership tree with room for 2^depth receipts
+pub fn init_tree(ctx: Context<InitReceiptTree>, depth: u8) -> Result<()> {
+    require!(
+        depth > 0 && depth as usize <= COMMITMENT_TREE_DEPTH,
+        ErrorCode::InvalidTreeParameters
+    );
+
+    let mut tree = ctx.accounts.receipt_tree.load_init()?;
+    tree.initialize(RECEIPT_TREE_CLASS, depth, ctx.bumps.receipt_tree);
+
+    msg!("Receipt tree initialized (depth {})", depth);
+
+    Ok(())
+}
 
 /// Create an anonymous receipt
-pub fn create(ctx: Context<CreateReceipt>, receipt_secret: [u8; 32]) -> Result<()> {
-    let employee = &ctx.accounts.employee;
-    let batch = &ctx.accounts.batch;
+///
+/// Also appends hash(tag || employer || day || membership_key) to the
+/// receipt tree, so the employee can later prove the receipt exists without
+/// naming this account.
+pub fn create(ctx: Context<CreateReceipt>, receipt_secret: [u8; 32], membership_key: Pubkey) -> Result<()> {
+    let employee = ctx.accounts.employee.load()?;
+    let batch_key = ctx.accounts.batch.key();
+    let employer = ctx.accounts.batch.load()?.owner;
     let now = Clock::get()?.unix_timestamp;
 
     let elapsed = now.checked_sub(employee.last_claimed_at)
         .unwrap_or(0) as u64;
     let claimed_amount = employee.salary_rate.saturating_mul(elapsed.max(1));
 
-    let mut preimage = Vec::with_capacity(32 + 32 + 8 + 8 + 32);
-    preimage.extend_from_slice(&employee.wallet.to_bytes());
-    preimage.extend_from_slice(&batch.key().to_bytes());
-    preimage.extend_from_slice(&now.to_le_bytes());
-    preimage.extend_from_slice(&claimed_amount.to_le_bytes());
-    preimage.extend_from_slice(&receipt_secret);
-
-    let commitment = hash(&preimage).to_bytes();
+    let commitment = receipt_commitment(
+        RECEIPT_VERSION,
+        &employee.wallet,
+        &batch_key,
+        now,
+        claimed_amount,
+        &receipt_secret,
+    );
 
     let receipt = &mut ctx.accounts.receipt;
     receipt.employee = employee.wallet;
-    receipt.batch = batch.key();
-    receipt.employer = batch.owner;
+    receipt.batch = batch_key;
+    receipt.employer = employer;
     receipt.commitment = commitment;
     receipt.timestamp = now;
     receipt.receipt_index = employee.total_claimed;
     receipt.bump = ctx.bumps.receipt;
+    receipt.version = RECEIPT_VERSION;
+
+    let leaf = receipt_leaf(&employer, time_bucket(now), &membership_key);
+    let mut tree = ctx.accounts.receipt_tree.load_mut()?;
+    let leaf_index = tree.insert(leaf)?;
+    emit!(ReceiptLeafInserted {
+        leaf,
+        leaf_index,
+        root: tree.current_root(),
+    });
 
     msg!("Anonymous receipt created");
     msg!("Receipt can prove payment without revealing amount");
@@ -52,14 +90,14 @@ pub fn verify(
 ) -> Result<()> {
     let receipt = &ctx.accounts.receipt;
 
-    let mut preimage = Vec::with_capacity(32 + 32 + 8 + 8 + 32);
-    preimage.extend_from_slice(&employee_wallet.to_bytes());
-    preimage.extend_from_slice(&batch_key.to_bytes());
-    preimage.extend_from_slice(&timestamp.to_le_bytes());
-    preimage.extend_from_slice(&amount.to_le_bytes());
-    preimage.extend_from_slice(&secret);
-
-    let computed_commitment = hash(&preimage).to_bytes();
+    let computed_commitment = receipt_commitment(
+        receipt.version,
+        &employee_wallet,
+        &batch_key,
+        timestamp,
+        amount,
+        &secret,
+    );
 
     require!(
         computed_commitment == receipt.commitment,
@@ -106,3 +144,73 @@ pub fn verify_blind(
 
     Ok(())
 }
+
+/// Prove membership of a receipt from `employer` dated within [range_start, range_end]
+///
+/// The holder signs with the receipt's membership key and the result is
+/// published as a `ReceiptMembershipProven` event. This is not a
+/// zero-knowledge proof: the leaf and its index are visible in the
+/// transaction, so a verifier who replays the tree can tell which receipt it
+/// is, though no receipt account or amount is named. Leaves only carry the
+/// payment day, so the whole day must lie inside the range.
+pub fn prove_membership(
+    ctx: Context<ProveReceiptMembership>,
+    employer: Pubkey,
+    day: i64,
+    range_start: i64,
+    range_end: i64,
+    proof: MerkleProof,
+) -> Result<()> {
+    require!(
+        day == time_bucket(day)
+            && day >= range_start
+            && day.saturating_add(RECEIPT_TIME_BUCKET_SECONDS - 1) <= range_end,
+        ErrorCode::ReceiptTimestampMismatch
+    );
+
+    let leaf = receipt_leaf(&employer, day, &ctx.accounts.holder.key());
+    ctx.accounts.receipt_tree.load()?.verify(leaf, &proof)?;
+
+    emit!(ReceiptMembershipProven {
+        holder: ctx.accounts.holder.key(),
+        employer,
+        range_start,
+        range_end,
+        root: proof.root,
+    });
+
+    msg!("Receipt membership verified");
+
+    Ok(())
+}
+
+/// Start of the receipt time bucket containing `timestamp`
+fn time_bucket(timestamp: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(RECEIPT_TIME_BUCKET_SECONDS)
+}
+
+/// Receipt tree leaf: hash(tag || employer || day || membership key)
+fn receipt_leaf(employer: &Pubkey, day: i64, membership_key: &Pubkey) -> [u8; 32] {
+    hashv(&[RECEIPT_LEAF_DOMAIN, employer.as_ref(), &day.to_le_bytes(), membership_key.as_ref()]).to_bytes()
+}
+
+/// hash(tag || employee || batch || timestamp || amount || secret); version 0 has no tag
+fn receipt_commitment(
+    version: u8,
+    employee: &Pubkey,
+    batch: &Pubkey,
+    timestamp: i64,
+    amount: u64,
+    secret: &[u8; 32],
+) -> [u8; 32] {
+    let domain: &[u8] = if version >= 2 { RECEIPT_DOMAIN } else { &[] };
+    hashv(&[
+        domain,
+        employee.as_ref(),
+        batch.as_ref(),
+        &timestamp.to_le_bytes(),
+        &amount.to_le_bytes(),
+        secret,
+    ])
+    .to_bytes()
+}
@@ -5,132 +5,1198 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
 use anchor_lang::solana_program::ed25519_program;
-use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
-use anchor_lang::solana_program::hash::hash;
-use crate::constants::ALLOWED_AMOUNTS;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::secp256k1_program;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use anchor_lang::solana_program::instruction::Instruction;
 use crate::errors::ErrorCode;
-use crate::instructions::{ClaimWithdrawRelayed, PrivateWithdrawRelayed};
+use crate::handlers::commitment::{append_note, spend_note};
+use crate::instructions::{
+    ClaimWithdrawRelayed, PrivateWithdrawRelayed, OpenDepositEscrow, CloseDepositEscrow, PrivateDepositRelayed, InitRelayNonce,
+    QuoteRelayFee, DonateRelayed, WithdrawRelayed, SetRelaySigner, ClaimWithdrawRelayedSession, RegisterSessionKey,
+    CloseSessionKey, InitRelayerAllowlist, UpdateRelayerAllowlist, RegisterRelayer,
+    BatchClaimRelayed, OpenRentEscrow, CloseRentEscrow, RequestWithdrawRelayed,
+};
+use crate::constants::{
+    MAX_SESSION_SECONDS, SESSION_ALLOW_CLAIM, SESSION_ALLOWED_MASK, MAX_ALLOWED_RELAYERS, MAX_BATCH_CLAIMS,
+    MAX_AUTHORIZATION_SECONDS, MAX_DURABLE_AUTHORIZATION_SECONDS, ALLOWED_AMOUNTS, CLAIM_WINDOW_SECONDS,
+};
+use crate::events::{
+    BatchClaimCompleted, BatchClaimItem, BatchClaimStatus, RelayJobCompleted, RelayJobKind, RelayJobOutcome,
+};
+use crate::handlers::privacy_pool::{load_batch_pending, variable_delay};
+use crate::state::{Campaign, CampaignStatus, DepositEscrow, MerkleProof, NoteHeader, RelayNonce, RelayFeeQuote, RentEscrow, Secp256k1NoteOwner, SessionKey};
 
 /// Claim a pending withdrawal via relayer (gasless)
-pub fn claim_withdraw_relayed(ctx: Context<ClaimWithdrawRelayed>) -> Result<()> {
-    let now = Clock::get()?.unix_timestamp;
+pub fn claim_withdraw_relayed(ctx: Context<ClaimWithdrawRelayed>, valid_until: i64) -> Result<()> {
+    check_authorization_window(valid_until)?;
+
+    // The recipient must have signed "claim:<pending_pda>:<nonce>:<valid_until>"
+    // (base58 PDA), otherwise any valid ed25519 signature would authorize the claim
+    let message = RelayNonce::claim_message(&ctx.accounts.pending_withdraw.key(), ctx.accounts.relay_nonce.nonce, valid_until);
+    require_ed25519_signature(
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.recipient.key(),
+        &message,
+        ErrorCode::InvalidClaimMessage,
+    )?;
+
+    settle_relayed_claim(ctx.accounts, relay_job_id(&message))
+}
+
+/// Claim a pending withdrawal via relayer with an authorization pre-signed offline
+///
+/// The recipient signs `RelayNonce::durable_claim_message`, naming a durable
+/// nonce account; the claim transaction must advance that account first. The
+/// authorization may then be valid for up to MAX_DURABLE_AUTHORIZATION_SECONDS.
+pub fn claim_withdraw_relayed_durable(
+    ctx: Context<ClaimWithdrawRelayed>,
+    valid_until: i64,
+    durable_nonce: Pubkey,
+) -> Result<()> {
+    check_durable_authorization(&ctx.accounts.instructions_sysvar, &durable_nonce, valid_until)?;
+
+    let message = RelayNonce::durable_claim_message(
+        &ctx.accounts.pending_withdraw.key(),
+        ctx.accounts.relay_nonce.nonce,
+        valid_until,
+        &durable_nonce,
+    );
+    require_ed25519_signature(
+        &ctx.accounts.instructions_sysvar,
+        &ctx.accounts.recipient.key(),
+        &message,
+        ErrorCode::InvalidClaimMessage,
+    )?;
+
+    settle_relayed_claim(ctx.accounts, relay_job_id(&message))
+}
+
+/// Settle many relayed claims in one transaction
+///
+/// Each recipient signs its own nonce-bound claim message; entries without
+/// a matching signature, or not claimable yet, are skipped and reported in
+/// `BatchClaimCompleted` like `batch_claim_withdraw` does. `valid_until`
+/// holds each recipient's authorization expiry, in triple order.
+pub fn batch_claim_relayed<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchClaimRelayed<'info>>,
+    valid_until: Vec<i64>,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    accounts.pool.check_relayer(&accounts.relayer.key(), accounts.relayer_allowlist.as_deref())?;
+
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    let remaining = ctx.remaining_accounts;
+    require!(remaining.len() >= 3, ErrorCode::BatchTooSmall);
+    require!(remaining.chunks_exact(3).remainder().is_empty(), ErrorCode::BatchInvalidTriples);
+    require!(remaining.len() <= MAX_BATCH_CLAIMS * 3, ErrorCode::BatchTooLarge);
+    require!(valid_until.len() == remaining.len() / 3, ErrorCode::BatchInvalidTriples);
+
+    let signatures = ed25519_signatures(&accounts.instructions_sysvar)?;
+
+    let vault_signer = accounts.pool.vault_signer(&accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    let mut results = Vec::with_capacity(remaining.len() / 3);
+    let mut total_claimed: u64 = 0;
+    let mut success_count: u8 = 0;
 
-    let pending_recipient = ctx.accounts.pending_withdraw.recipient;
-    let pending_claimed = ctx.accounts.pending_withdraw.claimed;
-    let pending_available_at = ctx.accounts.pending_withdraw.available_at;
-    let pending_amount = ctx.accounts.pending_withdraw.amount;
+    for (i, triple) in remaining.chunks_exact(3).enumerate() {
+        let recipient_info = &triple[0];
+        let pending_info = &triple[1];
+        let nonce_info = &triple[2];
 
-    require!(!pending_claimed, ErrorCode::AlreadyClaimed);
-    require!(now >= pending_available_at, ErrorCode::WithdrawNotReady);
+        let loader = load_batch_pending(i, pending_info)?;
+        let mut pending = loader.load_mut()?;
 
+        let expected_address = Pubkey::create_program_address(
+            &[b"pending", pending.recipient.as_ref(), &[pending.bump]],
+            &crate::ID,
+        ).map_err(|_| ErrorCode::BatchInvalidPendingAddress)?;
+        require!(expected_address == pending_info.key(), ErrorCode::BatchInvalidPendingAddress);
+
+        let mut relay_nonce: Account<RelayNonce> = Account::try_from(nonce_info)?;
+        let expected_nonce = Pubkey::create_program_address(
+            &[b"relay_nonce", pending.recipient.as_ref(), &[relay_nonce.bump]],
+            &crate::ID,
+        ).map_err(|_| ErrorCode::BatchInvalidRelayNonce)?;
+        require!(expected_nonce == nonce_info.key(), ErrorCode::BatchInvalidRelayNonce);
+
+        let message = RelayNonce::claim_message(&pending_info.key(), relay_nonce.nonce, valid_until[i]);
+        let signed = check_authorization_window(valid_until[i]).is_ok()
+            && signatures.iter().any(|(signer, signed)| *signer == pending.recipient && *signed == message);
+
+        let status = if pending.recipient != recipient_info.key() {
+            BatchClaimStatus::RecipientMismatch
+        } else if !signed {
+            BatchClaimStatus::SignatureMissing
+        } else if pending.is_claimed() {
+            BatchClaimStatus::AlreadyClaimed
+        } else if pending.is_voided() {
+            BatchClaimStatus::Voided
+        } else if pending.is_queued() {
+            BatchClaimStatus::Queued
+        } else if now < pending.available_at {
+            BatchClaimStatus::NotReady
+        } else if pending.is_expired(now) {
+            BatchClaimStatus::Expired
+        } else if pending.shard != accounts.pool_shard.index {
+            BatchClaimStatus::WrongShard
+        } else if pending.amount > accounts.pool_vault.lamports() {
+            BatchClaimStatus::InsufficientFunds
+        } else if !accounts.pool_shard.outflow_allows(&accounts.pool, pending.amount, clock.epoch) {
+            BatchClaimStatus::OutflowLimited
+        } else {
+            BatchClaimStatus::Claimed
+        };
+
+        results.push(BatchClaimItem { index: i as u8, amount: pending.amount, status });
+
+        let job_id = relay_job_id(&message);
+        if status != BatchClaimStatus::Claimed {
+            msg!("Relayed batch claim {}: skipped ({:?})", i, status);
+            accounts.relayer_info.record_failure();
+            emit_relay_job(job_id, accounts.relayer.key(), RelayJobKind::Claim, 0, RelayJobOutcome::Skipped)?;
+            continue;
+        }
+
+        accounts.relayer_info.record_claim(accounts.pool.max_relayer_claims_per_epoch, 0)?;
+        relay_nonce.consume()?;
+        relay_nonce.exit(&crate::ID)?;
+
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: accounts.pool_vault.to_account_info(),
+                    to: recipient_info.clone(),
+                },
+                signer_seeds,
+            ),
+            pending.amount,
+        )?;
+
+        pending.claimed = 1;
+
+        accounts.pool_shard.release_reservation(pending.amount);
+        accounts.pool_shard.record_withdrawal(&accounts.pool, pending.amount)?;
+        total_claimed = total_claimed.saturating_add(pending.amount);
+        success_count += 1;
+
+        emit_relay_job(job_id, accounts.relayer.key(), RelayJobKind::Claim, 0, RelayJobOutcome::Settled)?;
+    }
+
+    msg!("Relayed batch complete: {} claims, {} total lamports", success_count, total_claimed);
+    msg!("Relayer: {} (paid gas)", accounts.relayer.key());
+
+    emit!(BatchClaimCompleted {
+        results,
+        success_count,
+        total_claimed,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Claim a pending withdrawal via relayer, authorized by the secp256k1 key
+/// the recipient registered on its relay nonce
+pub fn claim_withdraw_relayed_secp256k1(ctx: Context<ClaimWithdrawRelayed>, valid_until: i64) -> Result<()> {
+    check_authorization_window(valid_until)?;
+
+    let relay_nonce = &ctx.accounts.relay_nonce;
+    require!(relay_nonce.has_secp256k1_signer(), ErrorCode::NoSecp256k1Signer);
+    let message = RelayNonce::claim_message(&ctx.accounts.pending_withdraw.key(), relay_nonce.nonce, valid_until);
+    require_secp256k1_signature(
+        &ctx.accounts.instructions_sysvar,
+        &relay_nonce.secp256k1_signer,
+        &message,
+        ErrorCode::InvalidClaimMessage,
+    )?;
+
+    settle_relayed_claim(ctx.accounts, relay_job_id(&message))
+}
+
+/// Claim a pending withdrawal via relayer, authorized by a session key of the recipient
+///
+/// The session key signs the same nonce-bound claim message the stealth key
+/// would, so each authorization is still valid exactly once.
+pub fn claim_withdraw_relayed_session(ctx: Context<ClaimWithdrawRelayedSession>, valid_until: i64) -> Result<()> {
+    check_authorization_window(valid_until)?;
+
+    let session = &ctx.accounts.session;
+    session.check_allows(SESSION_ALLOW_CLAIM, Clock::get()?.unix_timestamp)?;
+
+    let claim = &mut ctx.accounts.claim;
+    let message = RelayNonce::claim_message(&claim.pending_withdraw.key(), claim.relay_nonce.nonce, valid_until);
+    require_ed25519_signature(
+        &claim.instructions_sysvar,
+        &session.session_key,
+        &message,
+        ErrorCode::InvalidClaimMessage,
+    )?;
+
+    settle_relayed_claim(claim, relay_job_id(&message))
+}
+
+/// Register a session key for `owner`, authorized once by the owner's ed25519
+/// signature over `SessionKey::register_message`
+///
+/// The relayer pays the rent and gets it back when the session is closed.
+pub fn register_session_key(
+    ctx: Context<RegisterSessionKey>,
+    session_key: Pubkey,
+    expires_at: i64,
+    allowed: u8,
+    valid_until: i64,
+) -> Result<()> {
+    check_authorization_window(valid_until)?;
+
+    let now = Clock::get()?.unix_timestamp;
     require!(
-        ctx.accounts.recipient.key() == pending_recipient,
-        ErrorCode::SignerMismatch
+        expires_at > now && expires_at <= now.saturating_add(MAX_SESSION_SECONDS),
+        ErrorCode::InvalidSessionExpiry
+    );
+    require!(allowed != 0 && allowed & !SESSION_ALLOWED_MASK == 0, ErrorCode::InvalidSessionPermissions);
+    ctx.accounts.pool.check_relayer(&ctx.accounts.relayer.key(), ctx.accounts.relayer_allowlist.as_deref())?;
+
+    let owner = ctx.accounts.owner.key();
+    let message = SessionKey::register_message(
+        &owner,
+        &session_key,
+        expires_at,
+        allowed,
+        ctx.accounts.relay_nonce.nonce,
+        valid_until,
     );
+    require_ed25519_signature(&ctx.accounts.instructions_sysvar, &owner, &message, ErrorCode::SignedMessageMismatch)?;
+    ctx.accounts.relay_nonce.consume()?;
 
-    let ix_sysvar = &ctx.accounts.instructions_sysvar;
-    let ed25519_ix = load_instruction_at_checked(0, ix_sysvar)?;
+    let session = &mut ctx.accounts.session;
+    session.owner = owner;
+    session.session_key = session_key;
+    session.expires_at = expires_at;
+    session.allowed = allowed;
+    session.rent_payer = ctx.accounts.relayer.key();
+    session.bump = ctx.bumps.session;
 
+    msg!("Session key registered for {} until {}", owner, expires_at);
+
+    emit_relay_job(
+        relay_job_id(&message),
+        ctx.accounts.relayer.key(),
+        RelayJobKind::SessionKey,
+        0,
+        RelayJobOutcome::Settled,
+    )
+}
+
+/// Close a session key, refunding its rent payer
+///
+/// The owner may close it at any time, e.g. to revoke a leaked key; anyone
+/// else only once it has expired.
+pub fn close_session_key(ctx: Context<CloseSessionKey>) -> Result<()> {
+    let session = &ctx.accounts.session;
+    let by_owner = ctx.accounts.owner.as_ref().is_some_and(|owner| owner.key() == session.owner);
     require!(
-        ed25519_ix.program_id == ed25519_program::ID,
-        ErrorCode::InvalidSignatureInstruction
+        by_owner || Clock::get()?.unix_timestamp > session.expires_at,
+        ErrorCode::SessionStillActive
     );
 
+    msg!("Session key closed for {}", ctx.accounts.session.owner);
+
+    Ok(())
+}
+
+/// Register (or clear, with all zeroes) the Ethereum address allowed to
+/// authorize relayed claims for the signer
+pub fn set_relay_secp256k1_signer(ctx: Context<SetRelaySigner>, eth_address: [u8; 20]) -> Result<()> {
+    ctx.accounts.relay_nonce.secp256k1_signer = eth_address;
+
+    msg!("Relay secp256k1 signer updated for {}", ctx.accounts.signer.key());
+
+    Ok(())
+}
+
+/// Pay out an authorized relayed claim and consume its nonce
+fn settle_relayed_claim(accounts: &mut ClaimWithdrawRelayed, job_id: [u8; 32]) -> Result<()> {
+    accounts.pool.check_relayer(&accounts.relayer.key(), accounts.relayer_allowlist.as_deref())?;
+    accounts.relayer_info.record_claim(accounts.pool.max_relayer_claims_per_epoch, 0)?;
+    let now = Clock::get()?.unix_timestamp;
+
+    let pending = accounts.pending_withdraw.load()?;
+    pending.check_claimable(now)?;
+    let pending_amount = pending.amount;
+    drop(pending);
+
+    accounts.relay_nonce.consume()?;
+
     let amount = pending_amount;
-    let vault_bump = ctx.accounts.pool.vault_bump;
-    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &[vault_bump]]];
+    let vault_signer = accounts.pool.vault_signer(&accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
 
     system_program::transfer(
         CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
+            accounts.system_program.to_account_info(),
             system_program::Transfer {
-                from: ctx.accounts.pool_vault.to_account_info(),
-                to: ctx.accounts.recipient.to_account_info(),
+                from: accounts.pool_vault.to_account_info(),
+                to: accounts.recipient.to_account_info(),
             },
             signer_seeds,
         ),
         amount,
     )?;
 
-    ctx.accounts.pending_withdraw.claimed = true;
+    accounts.pending_withdraw.load_mut()?.claimed = 1;
 
-    let pool = &mut ctx.accounts.pool;
-    pool.total_withdrawn = pool.total_withdrawn.checked_add(amount)
-        .ok_or(ErrorCode::Overflow)?;
-    pool.withdraw_count = pool.withdraw_count.checked_add(1)
-        .ok_or(ErrorCode::Overflow)?;
+    let shard = &mut accounts.pool_shard;
+    shard.release_reservation(amount);
+    shard.record_withdrawal(&accounts.pool, amount)?;
 
-    msg!("RELAYED withdrawal claimed: {} lamports to {}", amount, ctx.accounts.recipient.key());
-    msg!("Relayer: {} (paid gas)", ctx.accounts.relayer.key());
+    msg!("RELAYED withdrawal claimed: {} lamports to {}", amount, accounts.recipient.key());
+    msg!("Relayer: {} (paid gas)", accounts.relayer.key());
 
-    Ok(())
+    emit_relay_job(job_id, accounts.relayer.key(), RelayJobKind::Claim, 0, RelayJobOutcome::Settled)
 }
 
 /// Private withdraw via relayer (gasless)
+///
+/// Revealing a plain `secret_hash` would let the relayer resubmit the spend
+/// to its own recipient, so relayed notes are owned by an ed25519 key:
+/// `secret_hash = hash("ed25519-owner" || owner || salt)`, committed under
+/// OWNED_COMMITMENT_DOMAIN so `private_withdraw` cannot spend them. An ed25519
+/// instruction earlier in the transaction must carry the owner's signature over
+/// `withdraw_message(nullifier, recipient, amount, fee, valid_until)`, which makes the
+/// spend valid for that recipient and relayer fee only. Use a fresh owner
+/// key per note; the signature is public and would link notes sharing a key.
 pub fn private_withdraw_relayed(
+    ctx: Context<PrivateWithdrawRelayed>,
+    nullifier: [u8; 32],
+    owner_salt: [u8; 32],
+    amount: u64,
+    fee: u64,
+    valid_until: i64,
+    proof: MerkleProof,
+) -> Result<()> {
+    check_authorization_window(valid_until)?;
+
+    let owner = ed25519_signer_of(
+        &ctx.accounts.instructions_sysvar,
+        &withdraw_message(&nullifier, &ctx.accounts.recipient.key(), amount, fee, valid_until),
+    )?;
+
+    let secret_hash = hashv(&[b"ed25519-owner", owner.as_ref(), &owner_salt]).to_bytes();
+
+    msg!("RELAYED private withdrawal: {} lamports", amount);
+    settle_relayed_withdraw(ctx, nullifier, secret_hash, amount, fee, valid_until, &proof)
+}
+
+/// Private withdraw via relayer, authorized by a secp256k1 (Ethereum-style) key
+///
+/// Notes spendable this way are owned notes with `secret_hash =
+/// hash("secp256k1-owner" || eth_address || salt)`. Besides the note secrets, a secp256k1 precompile
+/// instruction earlier in the transaction must carry a signature by `owner.eth_address` over
+/// `withdraw_message(nullifier, recipient, amount, fee, valid_until)`, so recipient and
+/// fee are fixed by the key holder.
+pub fn private_withdraw_secp256k1(
+    ctx: Context<PrivateWithdrawRelayed>,
+    nullifier: [u8; 32],
+    owner: Secp256k1NoteOwner,
+    amount: u64,
+    fee: u64,
+    valid_until: i64,
+    proof: MerkleProof,
+) -> Result<()> {
+    check_authorization_window(valid_until)?;
+
+    require_secp256k1_signature(
+        &ctx.accounts.instructions_sysvar,
+        &owner.eth_address,
+        &withdraw_message(&nullifier, &ctx.accounts.recipient.key(), amount, fee, valid_until),
+        ErrorCode::SignedMessageMismatch,
+    )?;
+
+    let secret_hash = hashv(&[b"secp256k1-owner", &owner.eth_address, &owner.salt]).to_bytes();
+
+    msg!("RELAYED secp256k1-authorized withdrawal: {} lamports", amount);
+    settle_relayed_withdraw(ctx, nullifier, secret_hash, amount, fee, valid_until, &proof)
+}
+
+/// Spend an authorized note, paying `fee` to the relayer and the rest to
+/// the recipient in the same instruction
+fn settle_relayed_withdraw(
     ctx: Context<PrivateWithdrawRelayed>,
     nullifier: [u8; 32],
     secret_hash: [u8; 32],
     amount: u64,
+    fee: u64,
+    valid_until: i64,
+    proof: &MerkleProof,
+) -> Result<()> {
+    ctx.accounts.pool.check_relayer(&ctx.accounts.relayer.key(), ctx.accounts.relayer_allowlist.as_deref())?;
+    ctx.accounts.relayer_info.record_claim(ctx.accounts.pool.max_relayer_claims_per_epoch, fee)?;
+    ctx.accounts.pool.check_relay_fee(amount, fee)?;
+
+    let header = NoteHeader::current(amount)?;
+    let tree = ctx.accounts.commitment_tree.load()?;
+    let mut bitmap = ctx.accounts.spent_bitmap.load_mut()?;
+    spend_note(&tree, &mut bitmap, nullifier, secret_hash, &header, proof, true)?;
+    let used_at = tree.coarse_timestamp()?;
+    drop(tree);
+    drop(bitmap);
+
+    // When the escrow reimburses the relayer below, the escrow paid the rent
+    // and gets it back when the nullifier is closed
+    let relayer = ctx.accounts.relayer.key();
+    let rent_payer = ctx.accounts.rent_escrow.as_ref().map_or(relayer, |escrow| escrow.key());
+    ctx.accounts.nullifier_pda.record(nullifier, proof.leaf_index, rent_payer, used_at, ctx.bumps.nullifier_pda);
+
+    let pool = &ctx.accounts.pool;
+    let vault_signer = pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.recipient.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount - fee,
+    )?;
+
+    if fee > 0 {
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.pool_vault.to_account_info(),
+                    to: ctx.accounts.relayer.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            fee,
+        )?;
+    }
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_withdrawal(&ctx.accounts.pool, amount)?;
+
+    if let Some(rent_escrow) = ctx.accounts.rent_escrow.as_mut() {
+        draw_rent(
+            rent_escrow,
+            &ctx.accounts.nullifier_pda.to_account_info(),
+            &ctx.accounts.relayer,
+            &ctx.accounts.instructions_sysvar,
+            valid_until,
+        )?;
+    }
+
+    msg!("Relayer: {} (fee {} lamports)", relayer, fee);
+
+    let job_id = relay_job_id(&withdraw_message(&nullifier, &ctx.accounts.recipient.key(), amount, fee, valid_until));
+    emit_relay_job(job_id, relayer, RelayJobKind::Withdraw, fee, RelayJobOutcome::Settled)
+}
+
+/// Register the signing relayer's activity account
+pub fn register_relayer(ctx: Context<RegisterRelayer>) -> Result<()> {
+    let info = &mut ctx.accounts.relayer_info;
+    info.relayer = ctx.accounts.relayer.key();
+    info.epoch = Clock::get()?.epoch;
+    info.epoch_claims = 0;
+    info.successful_relays = 0;
+    info.failed_relays = 0;
+    info.fees_earned = 0;
+    info.bump = ctx.bumps.relayer_info;
+
+    msg!("Relayer registered: {}", info.relayer);
+
+    Ok(())
+}
+
+/// Create the empty relayer allowlist (pool authority only)
+pub fn init_relayer_allowlist(ctx: Context<InitRelayerAllowlist>) -> Result<()> {
+    let allowlist = &mut ctx.accounts.relayer_allowlist;
+    allowlist.relayers = [Pubkey::default(); MAX_ALLOWED_RELAYERS];
+    allowlist.bump = ctx.bumps.relayer_allowlist;
+
+    msg!("Relayer allowlist initialized");
+
+    Ok(())
+}
+
+/// Set or clear (with the default pubkey) one relayer allowlist slot
+pub fn set_allowed_relayer(ctx: Context<UpdateRelayerAllowlist>, index: u8, relayer: Pubkey) -> Result<()> {
+    require!((index as usize) < MAX_ALLOWED_RELAYERS, ErrorCode::InvalidRelayerSlot);
+
+    ctx.accounts.relayer_allowlist.relayers[index as usize] = relayer;
+
+    msg!("Relayer allowlist slot {} set to {}", index, relayer);
+
+    Ok(())
+}
+
+/// Relayer fee for a relayed withdrawal of `amount`, returned as return data
+///
+/// Wallets sign `fee` into the withdraw message; it stays valid until the
+/// fee schedule changes.
+pub fn quote_relay_fee(ctx: Context<QuoteRelayFee>, amount: u64) -> Result<RelayFeeQuote> {
+    let pool = &ctx.accounts.pool;
+    let quote = RelayFeeQuote {
+        fee: pool.required_relay_fee(),
+        max_fee: pool.max_relay_fee(amount),
+        base_fee: pool.relay_base_fee,
+        congestion_bps: pool.relay_congestion_bps,
+    };
+
+    msg!("Relayer fee quote: {} lamports (max {})", quote.fee, quote.max_fee);
+
+    Ok(quote)
+}
+
+/// Create the relay nonce account of `signer`; anyone may pay for it
+pub fn init_relay_nonce(ctx: Context<InitRelayNonce>, signer: Pubkey) -> Result<()> {
+    let relay_nonce = &mut ctx.accounts.relay_nonce;
+    relay_nonce.signer = signer;
+    relay_nonce.nonce = 0;
+    relay_nonce.secp256k1_signer = [0u8; 20];
+    relay_nonce.bump = ctx.bumps.relay_nonce;
+
+    msg!("Relay nonce opened for {}", signer);
+
+    Ok(())
+}
+
+/// Open a deposit escrow controlled by a one-time ed25519 key
+pub fn open_deposit_escrow(ctx: Context<OpenDepositEscrow>, escrow_authority: Pubkey) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.authority = escrow_authority;
+    escrow.nonce = 0;
+    escrow.bump = ctx.bumps.escrow;
+    escrow.owner = ctx.accounts.payer.key();
+    escrow.last_used_at = Clock::get()?.unix_timestamp;
+
+    msg!("Deposit escrow opened: {}", escrow.key());
+
+    Ok(())
+}
+
+/// Close a deposit escrow that has been idle for the refund delay
+///
+/// Its whole balance, including anything topped up since, goes to the owner.
+pub fn close_deposit_escrow(ctx: Context<CloseDepositEscrow>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+    require!(escrow.refundable(Clock::get()?.unix_timestamp), ErrorCode::EscrowStillActive);
+
+    msg!("Deposit escrow closed: {} lamports to {}", escrow.to_account_info().lamports(), escrow.owner);
+
+    Ok(())
+}
+
+/// Open a rent escrow controlled by a one-time ed25519 key
+pub fn open_rent_escrow(ctx: Context<OpenRentEscrow>, escrow_authority: Pubkey) -> Result<()> {
+    let escrow = &mut ctx.accounts.rent_escrow;
+    escrow.authority = escrow_authority;
+    escrow.nonce = 0;
+    escrow.bump = ctx.bumps.rent_escrow;
+    escrow.owner = ctx.accounts.payer.key();
+    escrow.last_used_at = Clock::get()?.unix_timestamp;
+
+    msg!("Rent escrow opened: {}", escrow.key());
+
+    Ok(())
+}
+
+/// Close a rent escrow that has been idle for the refund delay
+///
+/// Its whole balance, including anything topped up since, goes to the owner.
+pub fn close_rent_escrow(ctx: Context<CloseRentEscrow>) -> Result<()> {
+    let escrow = &ctx.accounts.rent_escrow;
+    require!(escrow.refundable(Clock::get()?.unix_timestamp), ErrorCode::EscrowStillActive);
+
+    msg!("Rent escrow closed: {} lamports to {}", escrow.to_account_info().lamports(), escrow.owner);
+
+    Ok(())
+}
+
+/// Request a withdrawal via relayer, for a recipient that never signs a transaction
+///
+/// The recipient's ed25519 signature over `RelayNonce::request_message` stands
+/// in for its transaction signature. The relayer fronts the pending account's
+/// rent and draws it back from the rent escrow in the same instruction.
+pub fn request_withdraw_relayed(ctx: Context<RequestWithdrawRelayed>, amount: u64, valid_until: i64) -> Result<()> {
+    check_authorization_window(valid_until)?;
+    require!(ALLOWED_AMOUNTS.contains(&amount), ErrorCode::InvalidWithdrawAmount);
+
+    let accounts = &mut *ctx.accounts;
+    accounts.pool.check_relayer(&accounts.relayer.key(), accounts.relayer_allowlist.as_deref())?;
+
+    let recipient = accounts.recipient.key();
+    let message = RelayNonce::request_message(&accounts.pending_withdraw.key(), amount, accounts.relay_nonce.nonce, valid_until);
+    require_ed25519_signature(&accounts.instructions_sysvar, &recipient, &message, ErrorCode::SignedMessageMismatch)?;
+    accounts.relay_nonce.consume()?;
+
+    let shard = &mut accounts.pool_shard;
+    let available = shard.unreserved_balance();
+    require!(amount <= available, ErrorCode::InsufficientPoolFunds);
+    shard.reserve(amount)?;
+
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    let variable_delay = variable_delay(clock.slot, &recipient);
+
+    let mut pending = accounts.pending_withdraw.load_init()?;
+    pending.recipient = recipient;
+    pending.amount = amount;
+    pending.requested_at = now;
+    pending.available_at = now + variable_delay;
+    pending.expires_at = pending.available_at + CLAIM_WINDOW_SECONDS;
+    pending.claimed = 0;
+    pending.shard = accounts.pool_shard.index;
+    pending.bump = ctx.bumps.pending_withdraw;
+    let available_at = pending.available_at;
+    drop(pending);
+
+    draw_rent(
+        &mut accounts.rent_escrow,
+        &accounts.pending_withdraw.to_account_info(),
+        &accounts.relayer,
+        &accounts.instructions_sysvar,
+        valid_until,
+    )?;
+    accounts.relayer_info.record_relay(0);
+
+    msg!("RELAYED withdrawal requested: {} lamports", amount);
+    msg!("Available at: {} (variable delay: {}s)", available_at, variable_delay);
+
+    emit_relay_job(relay_job_id(&message), accounts.relayer.key(), RelayJobKind::Request, 0, RelayJobOutcome::Settled)
+}
+
+/// Reimburse the relayer the rent of `account`, a freshly created account
+///
+/// The escrow authority must have signed `RentEscrow::rent_message` for
+/// exactly this account. The escrow itself stays rent-exempt.
+fn draw_rent(
+    escrow: &mut Account<RentEscrow>,
+    account: &AccountInfo,
+    relayer: &AccountInfo,
+    instructions_sysvar: &AccountInfo,
+    valid_until: i64,
 ) -> Result<()> {
+    require_ed25519_signature(
+        instructions_sysvar,
+        &escrow.authority,
+        &RentEscrow::rent_message(&escrow.key(), escrow.nonce, account.key, valid_until),
+        ErrorCode::SignedMessageMismatch,
+    )?;
+
+    // The escrow is program-owned, so lamports move without a system CPI
+    let rent = Rent::get()?;
+    let amount = rent.minimum_balance(account.data_len());
+    let escrow_info = escrow.to_account_info();
     require!(
-        ALLOWED_AMOUNTS.contains(&amount),
-        ErrorCode::InvalidWithdrawAmount
+        escrow_info.lamports().saturating_sub(rent.minimum_balance(escrow_info.data_len())) >= amount,
+        ErrorCode::InsufficientEscrowFunds
     );
+    **escrow_info.try_borrow_mut_lamports()? -= amount;
+    **relayer.try_borrow_mut_lamports()? += amount;
+
+    escrow.nonce = escrow.nonce.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    escrow.last_used_at = Clock::get()?.unix_timestamp;
+
+    msg!("Rent of {} lamports drawn from escrow {}", amount, escrow.key());
+
+    Ok(())
+}
 
-    let ix_sysvar = &ctx.accounts.instructions_sysvar;
-    let ed25519_ix = load_instruction_at_checked(0, ix_sysvar)?;
+/// Private deposit via relayer, funded from a pre-funded escrow
+pub fn private_deposit_relayed(
+    ctx: Context<PrivateDepositRelayed>,
+    commitment: [u8; 32],
+    amount: u64,
+    valid_until: i64,
+) -> Result<()> {
+    check_authorization_window(valid_until)?;
+
+    let header = NoteHeader::current(amount)?;
+    ctx.accounts.pool.check_relayer(&ctx.accounts.relayer.key(), ctx.accounts.relayer_allowlist.as_deref())?;
+
+    let escrow = &ctx.accounts.escrow;
+    let message = DepositEscrow::deposit_message(&escrow.key(), escrow.nonce, &commitment, amount, valid_until);
+    require_ed25519_signature(&ctx.accounts.instructions_sysvar, &escrow.authority, &message, ErrorCode::SignedMessageMismatch)?;
+
+    // The escrow is program-owned, so lamports move without a system CPI
+    let escrow_info = ctx.accounts.escrow.to_account_info();
+    let rent_minimum = Rent::get()?.minimum_balance(escrow_info.data_len());
     require!(
-        ed25519_ix.program_id == ed25519_program::ID,
-        ErrorCode::InvalidSignatureInstruction
+        escrow_info.lamports().saturating_sub(rent_minimum) >= amount,
+        ErrorCode::InsufficientEscrowFunds
     );
+    **escrow_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.pool_vault.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.nonce = escrow.nonce.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    escrow.last_used_at = Clock::get()?.unix_timestamp;
+
+    let mut tree = ctx.accounts.commitment_tree.load_mut()?;
+    let leaf_index = append_note(&mut tree, &header, &commitment)?;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_deposit(&ctx.accounts.pool, amount)?;
 
-    let mut preimage = Vec::with_capacity(72);
-    preimage.extend_from_slice(&secret_hash);
-    preimage.extend_from_slice(&nullifier);
-    preimage.extend_from_slice(&amount.to_le_bytes());
+    ctx.accounts.relayer_info.record_relay(0);
 
-    let computed_commitment = hash(&preimage).to_bytes();
+    msg!("RELAYED private deposit: {} lamports (leaf {})", amount, leaf_index);
+    msg!("Relayer: {}", ctx.accounts.relayer.key());
+
+    emit_relay_job(relay_job_id(&message), ctx.accounts.relayer.key(), RelayJobKind::Deposit, 0, RelayJobOutcome::Settled)
+}
+
+/// Campaign donation via relayer, funded from the donor's deposit escrow
+pub fn donate_relayed(ctx: Context<DonateRelayed>, amount: u64, valid_until: i64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    check_authorization_window(valid_until)?;
+
+    ctx.accounts.pool.check_relayer(&ctx.accounts.relayer.key(), ctx.accounts.relayer_allowlist.as_deref())?;
 
-    let commitment_pda = &mut ctx.accounts.commitment_pda;
+    let campaign = &ctx.accounts.campaign;
+    require!(campaign.status == CampaignStatus::Active, ErrorCode::CampaignNotActive);
+    require!(Clock::get()?.unix_timestamp < campaign.deadline, ErrorCode::CampaignEnded);
+
+    let escrow = &ctx.accounts.escrow;
+    let message = DepositEscrow::donate_message(&escrow.key(), escrow.nonce, &campaign.key(), amount, valid_until);
+    require_ed25519_signature(&ctx.accounts.instructions_sysvar, &escrow.authority, &message, ErrorCode::SignedMessageMismatch)?;
+
+    // The escrow is program-owned, so lamports move without a system CPI
+    let escrow_info = ctx.accounts.escrow.to_account_info();
+    let rent_minimum = Rent::get()?.minimum_balance(escrow_info.data_len());
     require!(
-        computed_commitment == commitment_pda.commitment,
-        ErrorCode::InvalidCommitmentProof
+        escrow_info.lamports().saturating_sub(rent_minimum) >= amount,
+        ErrorCode::InsufficientEscrowFunds
     );
-    require!(!commitment_pda.spent, ErrorCode::NullifierAlreadyUsed);
-    require!(commitment_pda.amount == amount, ErrorCode::InvalidAmount);
+    **escrow_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.nonce = escrow.nonce.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    escrow.last_used_at = Clock::get()?.unix_timestamp;
+
+    let campaign = &mut ctx.accounts.campaign;
+    campaign.total_raised = campaign.total_raised.checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    campaign.donor_count = campaign.donor_count.checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
 
-    commitment_pda.spent = true;
+    ctx.accounts.relayer_info.record_relay(0);
 
-    let nullifier_pda = &mut ctx.accounts.nullifier_pda;
-    nullifier_pda.nullifier = nullifier;
-    nullifier_pda.used_at = Clock::get()?.unix_timestamp;
-    nullifier_pda.bump = ctx.bumps.nullifier_pda;
+    msg!("RELAYED donation received: {} lamports", amount);
+    msg!("Relayer: {}", ctx.accounts.relayer.key());
+
+    emit_relay_job(relay_job_id(&message), ctx.accounts.relayer.key(), RelayJobKind::Donation, 0, RelayJobOutcome::Settled)
+}
+
+/// Campaign withdrawal via relayer, signed off-chain by the campaign owner
+///
+/// The owner signs `Campaign::withdraw_message`, fixing recipient, amount and
+/// fee; `amount - fee` goes to the recipient and `fee` to the relayer.
+pub fn withdraw_relayed(ctx: Context<WithdrawRelayed>, amount: u64, fee: u64, valid_until: i64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    check_authorization_window(valid_until)?;
 
     let pool = &ctx.accounts.pool;
-    let vault_bump = pool.vault_bump;
-    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &[vault_bump]]];
+    pool.check_relayer(&ctx.accounts.relayer.key(), ctx.accounts.relayer_allowlist.as_deref())?;
+    pool.check_relay_fee(amount, fee)?;
+
+    let campaign = &ctx.accounts.campaign;
+    let message = Campaign::withdraw_message(
+        &campaign.key(),
+        campaign.withdraw_nonce,
+        &ctx.accounts.recipient.key(),
+        amount,
+        fee,
+        valid_until,
+    );
+    require_ed25519_signature(&ctx.accounts.instructions_sysvar, &campaign.owner, &message, ErrorCode::SignedMessageMismatch)?;
+    require!(amount <= ctx.accounts.vault.lamports(), ErrorCode::InsufficientFunds);
 
+    let campaign_id = campaign.campaign_id.as_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[b"vault", campaign_id, &[campaign.vault_bump]]];
     system_program::transfer(
         CpiContext::new_with_signer(
             ctx.accounts.system_program.to_account_info(),
             system_program::Transfer {
-                from: ctx.accounts.pool_vault.to_account_info(),
+                from: ctx.accounts.vault.to_account_info(),
                 to: ctx.accounts.recipient.to_account_info(),
             },
             signer_seeds,
         ),
-        amount,
+        amount - fee,
     )?;
+    if fee > 0 {
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.relayer.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            fee,
+        )?;
+    }
 
-    let pool = &mut ctx.accounts.pool;
-    pool.total_withdrawn = pool.total_withdrawn.checked_add(amount)
-        .ok_or(ErrorCode::Overflow)?;
-    pool.withdraw_count = pool.withdraw_count.checked_add(1)
-        .ok_or(ErrorCode::Overflow)?;
+    let campaign = &mut ctx.accounts.campaign;
+    campaign.withdraw_nonce = campaign.withdraw_nonce.checked_add(1).ok_or(ErrorCode::Overflow)?;
 
-    msg!("RELAYED private withdrawal: {} lamports", amount);
+    ctx.accounts.relayer_info.record_relay(fee);
+
+    msg!("RELAYED campaign withdrawal: {} lamports (fee {} lamports)", amount, fee);
     msg!("Relayer: {}", ctx.accounts.relayer.key());
 
+    emit_relay_job(relay_job_id(&message), ctx.accounts.relayer.key(), RelayJobKind::CampaignWithdraw, fee, RelayJobOutcome::Settled)
+}
+
+/// Every (signer, message) pair verified by ed25519 instructions earlier in this transaction
+///
+/// Wallets may put compute-budget or other instructions first, and one
+/// verify instruction may carry several signatures, so all preceding
+/// instructions are scanned.
+pub(crate) fn ed25519_signatures(ix_sysvar: &AccountInfo) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    let current = load_current_index_checked(ix_sysvar)?;
+    let mut signatures = Vec::new();
+    for index in 0..current {
+        let ix = load_instruction_at_checked(index as usize, ix_sysvar)?;
+        if ix.program_id == ed25519_program::ID {
+            signatures.extend(ed25519_entries(&ix, index)?);
+        }
+    }
+    require!(!signatures.is_empty(), ErrorCode::InvalidSignatureInstruction);
+    Ok(signatures)
+}
+
+/// Require an ed25519 signature by `signer` over exactly `message`
+///
+/// Fails with `SignerMismatch` if `signer` signed nothing, and with
+/// `mismatch` if it signed something else.
+pub(crate) fn require_ed25519_signature(
+    ix_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+    mismatch: ErrorCode,
+) -> Result<()> {
+    let signatures = ed25519_signatures(ix_sysvar)?;
+    let mut signed = signatures.iter().filter(|(key, _)| key == signer).peekable();
+    require!(signed.peek().is_some(), ErrorCode::SignerMismatch);
+    if !signed.any(|(_, signed)| signed.as_slice() == message) {
+        return Err(mismatch.into());
+    }
+    Ok(())
+}
+
+/// Key that signed `message` in an ed25519 instruction of this transaction
+pub(crate) fn ed25519_signer_of(ix_sysvar: &AccountInfo, message: &[u8]) -> Result<Pubkey> {
+    ed25519_signatures(ix_sysvar)?
+        .into_iter()
+        .find(|(_, signed)| signed.as_slice() == message)
+        .map(|(signer, _)| signer)
+        .ok_or(error!(ErrorCode::SignedMessageMismatch))
+}
+
+/// Extract the signer and message of each signature of the ed25519 verify
+/// instruction at `own_index`
+///
+/// All offsets must point into the verify instruction itself, so the
+/// checked bytes are exactly the ones the precompile verified.
+fn ed25519_entries(ix: &Instruction, own_index: u16) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    const OFFSETS_START: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+
+    let data = &ix.data;
+    let count = *data.first().ok_or(ErrorCode::InvalidSignatureInstruction)? as usize;
+    require!(
+        count > 0 && data.len() >= OFFSETS_START + count * OFFSETS_LEN,
+        ErrorCode::InvalidSignatureInstruction
+    );
+
+    let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+    let is_own = |ix_index: u16| ix_index == u16::MAX || ix_index == own_index;
+
+    (0..count)
+        .map(|entry| {
+            let base = OFFSETS_START + entry * OFFSETS_LEN;
+            let signature_ix_index = read_u16(base + 2);
+            let public_key_offset = read_u16(base + 4) as usize;
+            let public_key_ix_index = read_u16(base + 6);
+            let message_offset = read_u16(base + 8) as usize;
+            let message_size = read_u16(base + 10) as usize;
+            let message_ix_index = read_u16(base + 12);
+
+            require!(
+                is_own(signature_ix_index) && is_own(public_key_ix_index) && is_own(message_ix_index),
+                ErrorCode::InvalidSignatureInstruction
+            );
+
+            let public_key = data.get(public_key_offset..public_key_offset + 32)
+                .ok_or(ErrorCode::InvalidSignatureInstruction)?;
+            let message = data.get(message_offset..message_offset + message_size)
+                .ok_or(ErrorCode::InvalidSignatureInstruction)?;
+
+            let public_key = Pubkey::try_from(public_key).map_err(|_| ErrorCode::InvalidSignatureInstruction)?;
+            Ok((public_key, message.to_vec()))
+        })
+        .collect()
+}
+
+/// Reject an authorization past its `valid_until`, or valid for longer
+/// than MAX_AUTHORIZATION_SECONDS from now
+pub(crate) fn check_authorization_window(valid_until: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now <= valid_until && valid_until <= now.saturating_add(MAX_AUTHORIZATION_SECONDS),
+        ErrorCode::AuthorizationExpired
+    );
+    Ok(())
+}
+
+/// Check a durable-nonce authorization: its longer validity window, and
+/// that this transaction's first instruction advances `durable_nonce`
+fn check_durable_authorization(instructions_sysvar: &AccountInfo, durable_nonce: &Pubkey, valid_until: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now <= valid_until && valid_until <= now.saturating_add(MAX_DURABLE_AUTHORIZATION_SECONDS),
+        ErrorCode::AuthorizationExpired
+    );
+
+    // SystemInstruction::AdvanceNonceAccount is variant 4; its first account is the nonce
+    let advance = load_instruction_at_checked(0, instructions_sysvar)?;
+    require!(
+        advance.program_id == system_program::ID
+            && advance.data.get(..4) == Some(&4u32.to_le_bytes()[..])
+            && advance.accounts.first().is_some_and(|meta| meta.pubkey == *durable_nonce),
+        ErrorCode::DurableNonceNotAdvanced
+    );
+    Ok(())
+}
+
+/// Id of the relay job authorized by `message`: its sha256
+fn relay_job_id(message: &[u8]) -> [u8; 32] {
+    hashv(&[message]).to_bytes()
+}
+
+fn emit_relay_job(
+    job_id: [u8; 32],
+    relayer: Pubkey,
+    kind: RelayJobKind,
+    fee: u64,
+    outcome: RelayJobOutcome,
+) -> Result<()> {
+    emit!(RelayJobCompleted {
+        job_id,
+        relayer,
+        kind,
+        fee,
+        outcome,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
+
+/// Message a note owner signs to authorize one withdrawal
+///
+/// `fee` is the part of `amount` paid to the relayer that submits it.
+pub(crate) fn withdraw_message(nullifier: &[u8; 32], recipient: &Pubkey, amount: u64, fee: u64, valid_until: i64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(9 + 32 + 32 + 8 + 8 + 8);
+    message.extend_from_slice(b"withdraw:");
+    message.extend_from_slice(nullifier);
+    message.extend_from_slice(recipient.as_ref());
+    message.extend_from_slice(&amount.to_le_bytes());
+    message.extend_from_slice(&fee.to_le_bytes());
+    message.extend_from_slice(&valid_until.to_le_bytes());
+    message
+}
+
+/// Message an owned note's key signs to rescue it to `recipient`
+pub(crate) fn rescue_message(nullifier: &[u8; 32], recipient: &Pubkey, amount: u64, valid_until: i64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(7 + 32 + 32 + 8 + 8);
+    message.extend_from_slice(b"rescue:");
+    message.extend_from_slice(nullifier);
+    message.extend_from_slice(recipient.as_ref());
+    message.extend_from_slice(&amount.to_le_bytes());
+    message.extend_from_slice(&valid_until.to_le_bytes());
+    message
+}
+
+/// Every (Ethereum address, message) pair verified by secp256k1 instructions
+/// earlier in this transaction
+pub(crate) fn secp256k1_signatures(ix_sysvar: &AccountInfo) -> Result<Vec<([u8; 20], Vec<u8>)>> {
+    let current = load_current_index_checked(ix_sysvar)?;
+    let mut signatures = Vec::new();
+    for index in 0..current.min(u8::MAX as u16 + 1) {
+        let ix = load_instruction_at_checked(index as usize, ix_sysvar)?;
+        if ix.program_id == secp256k1_program::ID {
+            signatures.extend(secp256k1_entries(&ix, index as u8)?);
+        }
+    }
+    require!(!signatures.is_empty(), ErrorCode::InvalidSecp256k1Instruction);
+    Ok(signatures)
+}
+
+/// Require a secp256k1 signature by `eth_address` over exactly `message`
+pub(crate) fn require_secp256k1_signature(
+    ix_sysvar: &AccountInfo,
+    eth_address: &[u8; 20],
+    message: &[u8],
+    mismatch: ErrorCode,
+) -> Result<()> {
+    let signatures = secp256k1_signatures(ix_sysvar)?;
+    let mut signed = signatures.iter().filter(|(address, _)| address == eth_address).peekable();
+    require!(signed.peek().is_some(), ErrorCode::SignerMismatch);
+    if !signed.any(|(_, signed)| signed.as_slice() == message) {
+        return Err(mismatch.into());
+    }
+    Ok(())
+}
+
+/// Extract the Ethereum address and message of each signature of the
+/// secp256k1 verify instruction located at `own_index`
+///
+/// As with ed25519, every offset must point into the verify instruction
+/// itself. The secp256k1 precompile names instructions by absolute index.
+fn secp256k1_entries(ix: &Instruction, own_index: u8) -> Result<Vec<([u8; 20], Vec<u8>)>> {
+    const OFFSETS_START: usize = 1;
+    const OFFSETS_LEN: usize = 11;
+
+    let data = &ix.data;
+    let count = *data.first().ok_or(ErrorCode::InvalidSecp256k1Instruction)? as usize;
+    require!(
+        count > 0 && data.len() >= OFFSETS_START + count * OFFSETS_LEN,
+        ErrorCode::InvalidSecp256k1Instruction
+    );
+
+    let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]) as usize;
+
+    (0..count)
+        .map(|entry| {
+            let base = OFFSETS_START + entry * OFFSETS_LEN;
+            let signature_ix_index = data[base + 2];
+            let eth_address_offset = read_u16(base + 3);
+            let eth_address_ix_index = data[base + 5];
+            let message_offset = read_u16(base + 6);
+            let message_size = read_u16(base + 8);
+            let message_ix_index = data[base + 10];
+
+            require!(
+                signature_ix_index == own_index && eth_address_ix_index == own_index && message_ix_index == own_index,
+                ErrorCode::InvalidSecp256k1Instruction
+            );
+
+            let eth_address: [u8; 20] = data.get(eth_address_offset..eth_address_offset + 20)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or(ErrorCode::InvalidSecp256k1Instruction)?;
+            let message = data.get(message_offset..message_offset + message_size)
+                .ok_or(ErrorCode::InvalidSecp256k1Instruction)?;
+
+            Ok((eth_address, message.to_vec()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ed25519 verify instruction with one signature by `signer` over
+    /// `message`, all offsets naming instruction `ix_index`
+    fn ed25519_ix(signer: &Pubkey, message: &[u8], ix_index: u16) -> Instruction {
+        const DATA_START: u16 = 2 + 14;
+        let (public_key_offset, signature_offset) = (DATA_START, DATA_START + 32);
+        let message_offset = signature_offset + 64;
+
+        let mut data = vec![1, 0];
+        for field in [
+            signature_offset, ix_index, public_key_offset, ix_index,
+            message_offset, message.len() as u16, ix_index,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[7u8; 64]);
+        data.extend_from_slice(message);
+        Instruction { program_id: ed25519_program::ID, accounts: vec![], data }
+    }
+
+    /// Secp256k1 verify instruction with one signature by `eth_address` over
+    /// `message`, all offsets naming instruction `ix_index`
+    fn secp256k1_ix(eth_address: &[u8; 20], message: &[u8], ix_index: u8) -> Instruction {
+        const DATA_START: u16 = 1 + 11;
+        let (eth_address_offset, signature_offset) = (DATA_START, DATA_START + 20);
+        let message_offset = signature_offset + 65;
+
+        let mut data = vec![1];
+        data.extend_from_slice(&signature_offset.to_le_bytes());
+        data.push(ix_index);
+        data.extend_from_slice(&eth_address_offset.to_le_bytes());
+        data.push(ix_index);
+        data.extend_from_slice(&message_offset.to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.push(ix_index);
+        data.extend_from_slice(eth_address);
+        data.extend_from_slice(&[7u8; 65]);
+        data.extend_from_slice(message);
+        Instruction { program_id: secp256k1_program::ID, accounts: vec![], data }
+    }
+
+    #[test]
+    fn parses_ed25519_entries_of_own_instruction() {
+        let signer = Pubkey::new_unique();
+        let message = withdraw_message(&[1; 32], &Pubkey::new_unique(), 100_000_000, 5_000, 1_700_000_000);
+
+        for ix_index in [u16::MAX, 3] {
+            let entries = ed25519_entries(&ed25519_ix(&signer, &message, ix_index), 3).unwrap();
+            assert_eq!(entries, vec![(signer, message.clone())]);
+        }
+    }
+
+    #[test]
+    fn rejects_ed25519_offsets_outside_own_instruction() {
+        let signer = Pubkey::new_unique();
+        assert!(ed25519_entries(&ed25519_ix(&signer, b"withdraw:", 2), 3).is_err());
+
+        let mut truncated = ed25519_ix(&signer, b"withdraw:", 3);
+        truncated.data.pop();
+        assert!(ed25519_entries(&truncated, 3).is_err());
+
+        let mut empty = ed25519_ix(&signer, b"withdraw:", 3);
+        empty.data[0] = 0;
+        assert!(ed25519_entries(&empty, 3).is_err());
+    }
+
+    #[test]
+    fn parses_secp256k1_entries_of_own_instruction() {
+        let eth_address = [9u8; 20];
+        let message = rescue_message(&[1; 32], &Pubkey::new_unique(), 100_000_000, 1_700_000_000);
+
+        let entries = secp256k1_entries(&secp256k1_ix(&eth_address, &message, 1), 1).unwrap();
+        assert_eq!(entries, vec![(eth_address, message)]);
+    }
+
+    #[test]
+    fn rejects_secp256k1_offsets_outside_own_instruction() {
+        let eth_address = [9u8; 20];
+        assert!(secp256k1_entries(&secp256k1_ix(&eth_address, b"rescue:", 0), 1).is_err());
+
+        let mut truncated = secp256k1_ix(&eth_address, b"rescue:", 1);
+        truncated.data.pop();
+        assert!(secp256k1_entries(&truncated, 1).is_err());
+
+        let mut overlong = secp256k1_ix(&eth_address, b"rescue:", 1);
+        overlong.data[0] = 2;
+        assert!(secp256k1_entries(&overlong, 1).is_err());
+    }
+}
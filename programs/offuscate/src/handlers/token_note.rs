@@ -0,0 +1,130 @@
+//! Shielded SPL Token Note Handlers
+//!
+//! Business logic for token notes. A note commits to (mint, amount) on top
+//! of the usual secret and nullifier, and lives in its mint's own tree.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_spl::token;
+use crate::constants::{COMMITMENT_TREE_DEPTH, TOKEN_COMMITMENT_DOMAIN, TOKEN_NOTE_DOMAIN, TOKEN_TREE_CLASS};
+use crate::errors::ErrorCode;
+use crate::events::TokenCommitmentInserted;
+use crate::instructions::{InitTokenPool, TokenDeposit, TokenWithdraw};
+use crate::state::{CommitmentTree, MerkleProof, NoteHeader, SpendIntent};
+
+/// Create the note tree of `mint` with room for 2^depth notes
+pub fn init_pool(ctx: Context<InitTokenPool>, depth: u8) -> Result<()> {
+    require!(
+        depth > 0 && depth as usize <= COMMITMENT_TREE_DEPTH,
+        ErrorCode::InvalidTreeParameters
+    );
+
+    let mut tree = ctx.accounts.token_tree.load_init()?;
+    tree.initialize(TOKEN_TREE_CLASS, depth, ctx.bumps.token_tree);
+
+    msg!("Token note pool for mint {} initialized (depth {})", ctx.accounts.mint.key(), depth);
+
+    Ok(())
+}
+
+/// Deposit `amount` tokens behind `commitment` = hash(tag || secret_hash || nullifier)
+pub fn deposit(ctx: Context<TokenDeposit>, commitment: [u8; 32], amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    ctx.accounts.pool.require_not_paused()?;
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.depositor_token.to_account_info(),
+                to: ctx.accounts.token_vault.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let mint = ctx.accounts.mint.key();
+    let header = NoteHeader::token();
+    let mut tree = ctx.accounts.token_tree.load_mut()?;
+    tree.require_class(&header)?;
+    tree.require_deposits_enabled(&header)?;
+    let leaf = CommitmentTree::leaf(&header, &token_commitment(&mint, amount, &commitment));
+    let leaf_index = tree.insert(leaf)?;
+
+    emit!(TokenCommitmentInserted {
+        mint,
+        leaf,
+        leaf_index,
+        root: tree.current_root(),
+        timestamp: tree.coarse_timestamp()?,
+    });
+
+    msg!("Token note deposit (leaf {})", leaf_index);
+
+    Ok(())
+}
+
+/// Spend a token note by revealing its secret hash, paying `amount` to the recipient
+///
+/// The spend intent must name `recipient_token` and the amount.
+pub fn withdraw(
+    ctx: Context<TokenWithdraw>,
+    nullifier: [u8; 32],
+    secret_hash: [u8; 32],
+    amount: u64,
+    proof: MerkleProof,
+) -> Result<()> {
+    ctx.accounts.pool.require_not_paused()?;
+    let statement = SpendIntent::statement(
+        b"token-withdraw",
+        &[nullifier, secret_hash],
+        ctx.accounts.recipient_token.key().as_ref(),
+        amount,
+    );
+    ctx.accounts.spend_intent.require_matured(&statement, Clock::get()?.slot)?;
+
+    let header = NoteHeader::token();
+    let inner = token_note_commitment(&secret_hash, &nullifier);
+    let leaf = CommitmentTree::leaf(&header, &token_commitment(&ctx.accounts.mint.key(), amount, &inner));
+
+    let tree = ctx.accounts.token_tree.load()?;
+    tree.require_class(&header)?;
+    tree.verify(leaf, &proof)?;
+    tree.require_anonymity_set(proof.leaf_index)?;
+    let used_at = tree.coarse_timestamp()?;
+    drop(tree);
+
+    let payer = ctx.accounts.payer.key();
+    ctx.accounts.nullifier_pda.record(nullifier, proof.leaf_index, payer, used_at, ctx.bumps.nullifier_pda);
+
+    let pool_bump = ctx.accounts.pool.bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"privacy_pool", &[pool_bump]]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.token_vault.to_account_info(),
+                to: ctx.accounts.recipient_token.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    msg!("Token note withdrawal: {} tokens", amount);
+
+    Ok(())
+}
+
+/// Inner commitment of a token note: hash(tag || secret_hash || nullifier)
+fn token_note_commitment(secret_hash: &[u8; 32], nullifier: &[u8; 32]) -> [u8; 32] {
+    hashv(&[TOKEN_NOTE_DOMAIN, secret_hash, nullifier]).to_bytes()
+}
+
+/// Tree commitment of a token note: hash(tag || mint || amount || inner commitment)
+fn token_commitment(mint: &Pubkey, amount: u64, commitment: &[u8; 32]) -> [u8; 32] {
+    hashv(&[TOKEN_COMMITMENT_DOMAIN, mint.as_ref(), &amount.to_le_bytes(), commitment]).to_bytes()
+}
@@ -0,0 +1,183 @@
+//! Wrapped SOL Handlers
+//!
+//! Business logic for wSOL deposits and claims. Lamports always move through
+//! the regular pool vault, so wSOL users share the same anonymity set.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::system_program;
+use anchor_spl::token;
+use crate::constants::MAX_SWAP_PROGRAMS;
+use crate::errors::ErrorCode;
+use crate::instructions::{PoolDepositWsol, ClaimWithdrawWsol, ClaimWithdrawSwap, UpdatePoolConfig};
+
+/// Deposit wSOL into the privacy pool
+pub fn deposit(ctx: Context<PoolDepositWsol>, amount: u64) -> Result<()> {
+    ctx.accounts.pool.check_deposit_amount(amount)?;
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.depositor_token.to_account_info(),
+                to: ctx.accounts.unwrap_account.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    // Closing the native account releases its lamports (amount + rent)
+    // to the depositor, who then forwards exactly `amount` to the vault
+    let pool_seeds: &[&[&[u8]]] = &[&[b"privacy_pool", &[ctx.accounts.pool.bump]]];
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token::CloseAccount {
+            account: ctx.accounts.unwrap_account.to_account_info(),
+            destination: ctx.accounts.depositor.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        },
+        pool_seeds,
+    ))?;
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.depositor.to_account_info(),
+                to: ctx.accounts.pool_vault.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.record_deposit(&ctx.accounts.pool, amount)?;
+
+    msg!("Pool wSOL deposit: {} lamports", amount);
+    msg!("Shard total: {} lamports", shard.total_deposited);
+
+    Ok(())
+}
+
+/// Claim a pending withdrawal into a wSOL token account
+pub fn claim_withdraw(ctx: Context<ClaimWithdrawWsol>) -> Result<()> {
+    let mut pending = ctx.accounts.pending_withdraw.load_mut()?;
+    let now = Clock::get()?.unix_timestamp;
+
+    pending.check_claimable(now)?;
+
+    let amount = pending.amount;
+    let vault_signer = ctx.accounts.pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.recipient_token.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    token::sync_native(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        token::SyncNative {
+            account: ctx.accounts.recipient_token.to_account_info(),
+        },
+    ))?;
+
+    pending.claimed = 1;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.release_reservation(amount);
+    shard.record_withdrawal(&ctx.accounts.pool, amount)?;
+
+    msg!("Withdrawal claimed as wSOL: {} lamports to {}", amount, ctx.accounts.recipient_token.key());
+
+    Ok(())
+}
+
+/// Claim a pending withdrawal as wSOL, then swap it into the output token
+///
+/// The pending withdrawal is marked claimed before the swap CPI, and the
+/// output account must grow by at least `min_amount_out`.
+pub fn claim_withdraw_swap<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimWithdrawSwap<'info>>,
+    swap_data: Vec<u8>,
+    min_amount_out: u64,
+) -> Result<()> {
+    let mut pending = ctx.accounts.pending_withdraw.load_mut()?;
+    let now = Clock::get()?.unix_timestamp;
+
+    pending.check_claimable(now)?;
+
+    let amount = pending.amount;
+    pending.claimed = 1;
+    drop(pending);
+
+    let vault_signer = ctx.accounts.pool.vault_signer(&ctx.accounts.pool_vault.key())?;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"pool_vault", &vault_signer[0], &vault_signer[1]]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.recipient_token.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    token::sync_native(CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        token::SyncNative {
+            account: ctx.accounts.recipient_token.to_account_info(),
+        },
+    ))?;
+
+    let shard = &mut ctx.accounts.pool_shard;
+    shard.release_reservation(amount);
+    shard.record_withdrawal(&ctx.accounts.pool, amount)?;
+
+    let output_before = ctx.accounts.output_token.amount;
+
+    let swap_ix = Instruction {
+        program_id: ctx.accounts.swap_program.key(),
+        accounts: ctx.remaining_accounts.iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: swap_data,
+    };
+    invoke(&swap_ix, ctx.remaining_accounts)?;
+
+    ctx.accounts.output_token.reload()?;
+    let received = ctx.accounts.output_token.amount.saturating_sub(output_before);
+    require!(received >= min_amount_out, ErrorCode::SwapOutputTooLow);
+
+    msg!("Withdrawal claimed via swap: {} lamports in, {} out to {}", amount, received, ctx.accounts.output_token.key());
+
+    Ok(())
+}
+
+/// Set or clear (with the default pubkey) a whitelisted swap program slot
+pub fn set_swap_program(ctx: Context<UpdatePoolConfig>, index: u8, program_id: Pubkey) -> Result<()> {
+    require!((index as usize) < MAX_SWAP_PROGRAMS, ErrorCode::InvalidSwapProgramIndex);
+
+    let pool = &mut ctx.accounts.pool;
+    pool.swap_programs[index as usize] = program_id;
+
+    msg!("Swap program slot {} set to {}", index, program_id);
+
+    Ok(())
+}
@@ -3,7 +3,7 @@
 //! Campaign CRUD and stealth payment operations
 
 use anchor_lang::prelude::*;
-use crate::state::Campaign;
+use crate::state::{Campaign, CampaignRegistry, CampaignRegistryPage};
 use crate::errors::ErrorCode;
 
 #[derive(Accounts)]
@@ -29,6 +29,72 @@ pub struct CreateCampaign<'info> {
     )]
     pub vault: SystemAccount<'info>,
 
+    /// Registry and current page; without both, list the campaign later with `register_campaign`
+    #[account(
+        mut,
+        seeds = [b"campaign_registry"],
+        bump = registry.bump
+    )]
+    pub registry: Option<Account<'info, CampaignRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"campaign_registry_page",
+            CampaignRegistryPage::page_of(registry.as_ref().map_or(0, |r| r.campaign_count)).to_le_bytes().as_ref(),
+        ],
+        bump = registry_page.load()?.bump
+    )]
+    pub registry_page: Option<AccountLoader<'info, CampaignRegistryPage>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create a campaign owned by `owner`, which need not sign or pay
+///
+/// Lets a beneficiary use a fresh stealth key as owner while a relayer
+/// (or anyone) pays the rent; the owner then withdraws with `withdraw_relayed`.
+#[derive(Accounts)]
+#[instruction(campaign_id: String)]
+pub struct CreateStealthCampaign<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Campaign::SPACE,
+        seeds = [b"campaign", campaign_id.as_bytes()],
+        bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    /// CHECK: Vault PDA - just holds SOL, no data
+    #[account(
+        mut,
+        seeds = [b"vault", campaign_id.as_bytes()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Registry and current page; without both, list the campaign later with `register_campaign`
+    #[account(
+        mut,
+        seeds = [b"campaign_registry"],
+        bump = registry.bump
+    )]
+    pub registry: Option<Account<'info, CampaignRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"campaign_registry_page",
+            CampaignRegistryPage::page_of(registry.as_ref().map_or(0, |r| r.campaign_count)).to_le_bytes().as_ref(),
+        ],
+        bump = registry_page.load()?.bump
+    )]
+    pub registry_page: Option<AccountLoader<'info, CampaignRegistryPage>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -93,3 +159,139 @@ pub struct CloseCampaign<'info> {
 
     pub system_program: Program<'info, System>,
 }
+
+#[derive(Accounts)]
+pub struct UpdateCampaign<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.campaign_id.as_bytes()],
+        bump = campaign.campaign_bump,
+        has_one = owner @ ErrorCode::Unauthorized
+    )]
+    pub campaign: Account<'info, Campaign>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeCampaign<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.campaign_id.as_bytes()],
+        bump = campaign.campaign_bump,
+        has_one = owner @ ErrorCode::Unauthorized
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    /// CHECK: Vault PDA
+    #[account(
+        mut,
+        seeds = [b"vault", campaign.campaign_id.as_bytes()],
+        bump = campaign.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCampaignAccount<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.campaign_id.as_bytes()],
+        bump = campaign.campaign_bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    /// CHECK: Vault PDA
+    #[account(
+        mut,
+        seeds = [b"vault", campaign.campaign_id.as_bytes()],
+        bump = campaign.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// Registry page listing the campaign; required if it was registered
+    #[account(
+        mut,
+        seeds = [
+            b"campaign_registry_page",
+            CampaignRegistryPage::page_of(campaign.registry_index).to_le_bytes().as_ref(),
+        ],
+        bump = registry_page.load()?.bump
+    )]
+    pub registry_page: Option<AccountLoader<'info, CampaignRegistryPage>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitCampaignRegistry<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = CampaignRegistry::SPACE,
+        seeds = [b"campaign_registry"],
+        bump
+    )]
+    pub registry: Account<'info, CampaignRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct InitCampaignRegistryPage<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = CampaignRegistryPage::SPACE,
+        seeds = [b"campaign_registry_page", page.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub registry_page: AccountLoader<'info, CampaignRegistryPage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// List a campaign created before the registry existed, or without it (permissionless)
+#[derive(Accounts)]
+pub struct RegisterCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign_registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, CampaignRegistry>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.campaign_id.as_bytes()],
+        bump = campaign.campaign_bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"campaign_registry_page",
+            CampaignRegistryPage::page_of(registry.campaign_count).to_le_bytes().as_ref(),
+        ],
+        bump = registry_page.load()?.bump
+    )]
+    pub registry_page: AccountLoader<'info, CampaignRegistryPage>,
+}
@@ -1,64 +1,246 @@
 //! Commitment-based Privacy Account Contexts
 //!
 //! ZK-like privacy operations using commitment schemes:
+//! - InitCommitmentTree: Create the commitment Merkle tree of one note class
+//! - UpdateCommitmentConfig: Tree settings (pool authority only)
 //! - PrivateDeposit: Deposit with commitment hash
+//! - PostSpendIntent / CancelSpendIntent: Commit to a bare-secret spend ahead of it
 //! - PrivateWithdraw: Withdraw by revealing secret (proves knowledge)
+//! - PrivateWithdrawDelegated: Withdraw a delegated note, by owner or delegate
+//! - RescueOwnedNote: Self-rescue of an owned note, signed by its key
+//! - PrivateWithdrawBatch: Spend several notes to one recipient at once
+//! - WithdrawCommitmentPda: Spend a deposit made before the commitment tree
+//! - MigrateNote: Re-issue an older-version note in the current format
+//! - TransferNote: Hand a note to a new owner inside the shielded set
+//! - PrivateDonate: Spend a note straight into a campaign vault
+//! - FundBatchPrivate: Spend a note straight into a payroll batch vault
+//! - PrivateDepositRefundable: Deposit with a timelocked refund clause
+//! - RefundNote: Reclaim an unspent refundable deposit
+//! - InitSpentBitmap: Create a chunk of a tree's spent-leaf bitmap
+//! - GetShieldedStats: Read commitment counts and roots
+//! - CloseNullifier: Reclaim rent from an old nullifier PDA
+//! - RegisterDisclosure / CloseDisclosure: Opt-in auditor disclosures
 
 use anchor_lang::prelude::*;
-use crate::state::{PrivacyPool, CommitmentPDA, NullifierPDA};
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use crate::state::{
+    PrivacyPool, PoolShard, CommitmentTree, NullifierPDA, SpentBitmap, RefundTicket, Disclosure, Campaign,
+    MasterVault, PayrollBatch, CommitmentPDA, SpendIntent,
+};
+use crate::constants::NULLIFIER_CLOSE_DELAY_SECONDS;
+use crate::errors::ErrorCode;
+
+/// Create the commitment tree for one note class
+#[derive(Accounts)]
+#[instruction(class: u8)]
+pub struct InitCommitmentTree<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = CommitmentTree::SPACE,
+        seeds = [b"commitment_tree", class.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Update commitment tree settings (pool authority only)
+#[derive(Accounts)]
+pub struct UpdateCommitmentConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+}
 
 /// Private deposit with commitment scheme
 ///
 /// The depositor creates: commitment = hash(secret || nullifier || amount)
 /// Only the depositor knows secret and nullifier.
-/// To withdraw: reveal (nullifier, secret_hash, amount) such that
-/// hash(secret_hash || nullifier || amount) matches stored commitment.
+/// The program appends hash(note header || commitment) to the commitment tree.
+/// To withdraw: reveal (nullifier, secret_hash, amount) and prove the
+/// resulting leaf is in the tree under a recent root.
 #[derive(Accounts)]
-#[instruction(commitment: [u8; 32])]
 pub struct PrivateDeposit<'info> {
     #[account(mut)]
     pub depositor: Signer<'info>,
 
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
     #[account(
         mut,
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Private deposit that also records a refund clause
+///
+/// Same as `PrivateDeposit`, plus a ticket that lets the refund key reclaim
+/// the note once the refund timelock expires, if it is still unspent.
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct PrivateDepositRefundable<'info> {
+    pub deposit: PrivateDeposit<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = RefundTicket::SPACE,
+        seeds = [b"refund_ticket", commitment.as_ref()],
+        bump
+    )]
+    pub refund_ticket: Account<'info, RefundTicket>,
+
+    /// Pays for the ticket; normally the depositor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Reclaim a refundable deposit after its timelock
+///
+/// Marks the leaf spent so the note can no longer be withdrawn. If the note
+/// was already spent the ticket is just closed.
+#[derive(Accounts)]
+pub struct RefundNote<'info> {
+    #[account(mut)]
+    pub refund_authority: Signer<'info>,
+
+    #[account(
         seeds = [b"privacy_pool"],
         bump = pool.bump
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA
     #[account(
         mut,
-        seeds = [b"pool_vault"],
-        bump = pool.vault_bump
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
     )]
     pub pool_vault: SystemAccount<'info>,
 
-    /// Commitment PDA - stores the commitment hash
-    /// Derived from the commitment bytes so only one deposit per commitment
+    #[account(
+        mut,
+        close = refund_authority,
+        seeds = [b"refund_ticket", refund_ticket.commitment.as_ref()],
+        bump = refund_ticket.bump,
+        has_one = refund_authority
+    )]
+    pub refund_ticket: Account<'info, RefundTicket>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"spent_bitmap",
+            spent_bitmap.load()?.class.to_le_bytes().as_ref(),
+            spent_bitmap.load()?.chunk.to_le_bytes().as_ref(),
+        ],
+        bump = spent_bitmap.load()?.bump
+    )]
+    pub spent_bitmap: AccountLoader<'info, SpentBitmap>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Post the intent a bare-secret spend will be checked against
+#[derive(Accounts)]
+#[instruction(statement: [u8; 32])]
+pub struct PostSpendIntent<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     #[account(
         init,
-        payer = depositor,
-        space = CommitmentPDA::SPACE,
-        seeds = [b"commitment", commitment.as_ref()],
+        payer = payer,
+        space = SpendIntent::SPACE,
+        seeds = [b"spend_intent", statement.as_ref()],
         bump
     )]
-    pub commitment_pda: Account<'info, CommitmentPDA>,
+    pub spend_intent: Account<'info, SpendIntent>,
 
     pub system_program: Program<'info, System>,
 }
 
+/// Drop an unused spend intent and refund its rent to whoever posted it
+#[derive(Accounts)]
+pub struct CancelSpendIntent<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"spend_intent", spend_intent.statement.as_ref()],
+        bump = spend_intent.bump,
+        has_one = payer
+    )]
+    pub spend_intent: Account<'info, SpendIntent>,
+}
+
 /// Private withdrawal by revealing secret
 ///
 /// To withdraw, the user must provide:
 /// - nullifier: unique identifier, used once
 /// - secret_hash: hash of secret
 /// - amount: the amount to withdraw
+/// - proof: Merkle path of the commitment leaf to a recent root
+///
+/// The instruction recomputes the leaf from hash(secret_hash || nullifier || amount),
+/// checks its membership, creates the nullifier PDA (prevents reuse) and sends funds.
+/// A matured `SpendIntent` must name the recipient, so the revealed secret
+/// cannot be copied into a spend to another address.
 ///
-/// The instruction verifies: hash(secret_hash || nullifier || amount) == stored_commitment
-/// If valid, creates nullifier PDA (prevents reuse) and sends funds.
+/// The withdrawal transaction names no deposit-side account, but it is not
+/// unlinkable: the revealed preimage determines the leaf and `leaf_index` is
+/// passed in the clear, so anyone replaying the tree finds the deposit.
 #[derive(Accounts)]
-#[instruction(nullifier: [u8; 32], secret_hash: [u8; 32], amount: u64)]
+#[instruction(nullifier: [u8; 32])]
 pub struct PrivateWithdraw<'info> {
     /// Payer for the transaction (can be anyone)
     #[account(mut)]
@@ -68,32 +250,409 @@ pub struct PrivateWithdraw<'info> {
     #[account(mut)]
     pub recipient: AccountInfo<'info>,
 
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
     #[account(
         mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"spent_bitmap",
+            spent_bitmap.load()?.class.to_le_bytes().as_ref(),
+            spent_bitmap.load()?.chunk.to_le_bytes().as_ref(),
+        ],
+        bump = spent_bitmap.load()?.bump
+    )]
+    pub spent_bitmap: AccountLoader<'info, SpentBitmap>,
+
+    /// Nullifier PDA - created to mark this nullifier as used
+    /// If this account already exists, the withdrawal will fail (double-spend prevention)
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierPDA::SPACE,
+        seeds = [b"nullifier", nullifier.as_ref()],
+        bump
+    )]
+    pub nullifier_pda: Account<'info, NullifierPDA>,
+
+    /// Intent committing to this spend's destination, needed for bare-secret
+    /// notes; closed on use
+    #[account(mut, close = payer)]
+    pub spend_intent: Option<Account<'info, SpendIntent>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw a delegated note, authorized by its owner or its delegate
+#[derive(Accounts)]
+pub struct PrivateWithdrawDelegated<'info> {
+    pub withdraw: PrivateWithdraw<'info>,
+
+    /// CHECK: Instructions sysvar for the owner's or delegate's ed25519 signature
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+/// Self-rescue an owned note, authorized by its owner key
+#[derive(Accounts)]
+pub struct RescueOwnedNote<'info> {
+    pub withdraw: PrivateWithdraw<'info>,
+
+    /// CHECK: Instructions sysvar for the owner's ed25519 or secp256k1 signature
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+/// Spend a deposit made with the original per-commitment PDA
+///
+/// The PDA is closed on spend, which is what prevents a second withdrawal:
+/// no instruction creates accounts at that address any more.
+#[derive(Accounts)]
+pub struct WithdrawCommitmentPda<'info> {
+    /// Payer for the transaction (can be anyone)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: The recipient (any address, typically stealth); also gets the PDA's rent
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
         seeds = [b"privacy_pool"],
         bump = pool.bump
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA
     #[account(
         mut,
-        seeds = [b"pool_vault"],
-        bump = pool.vault_bump
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
     )]
     pub pool_vault: SystemAccount<'info>,
 
-    /// Commitment PDA - verify it exists and hasn't been spent
-    /// The commitment is recomputed from secret_hash || nullifier || amount
     #[account(
         mut,
         seeds = [b"commitment", commitment_pda.commitment.as_ref()],
-        bump = commitment_pda.bump
+        bump = commitment_pda.bump,
+        close = recipient
     )]
     pub commitment_pda: Account<'info, CommitmentPDA>,
 
-    /// Nullifier PDA - created to mark this nullifier as used
-    /// If this account already exists, the withdrawal will fail (double-spend prevention)
+    /// Intent committing to this spend's destination; closed on use
+    #[account(mut, close = payer)]
+    pub spend_intent: Account<'info, SpendIntent>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Spend several notes in one instruction, paying the total to one recipient
+///
+/// The notes share a Merkle multiproof against one root and must lie in
+/// the same spent-bitmap chunk. This is the transparent stand-in for a
+/// recursive proof, which would need an on-chain ZK verifier; it fits in a
+/// transaction when the notes sit close together in the tree.
+///
+/// Remaining accounts: one nullifier PDA per note, in the same order as the
+/// notes; the handler creates them.
+#[derive(Accounts)]
+pub struct PrivateWithdrawBatch<'info> {
+    /// Payer for the transaction and the nullifier PDAs (can be anyone)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: The recipient (any address, typically stealth)
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"spent_bitmap",
+            spent_bitmap.load()?.class.to_le_bytes().as_ref(),
+            spent_bitmap.load()?.chunk.to_le_bytes().as_ref(),
+        ],
+        bump = spent_bitmap.load()?.bump
+    )]
+    pub spent_bitmap: AccountLoader<'info, SpentBitmap>,
+
+    /// Intent committing to this spend's destination; closed on use
+    #[account(mut, close = payer)]
+    pub spend_intent: Account<'info, SpendIntent>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Re-issue an older-version note as a current-version note
+///
+/// The old note is spent (its nullifier recorded) and `new_commitment` is
+/// appended in the current format. No funds move.
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct MigrateNote<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"spent_bitmap",
+            spent_bitmap.load()?.class.to_le_bytes().as_ref(),
+            spent_bitmap.load()?.chunk.to_le_bytes().as_ref(),
+        ],
+        bump = spent_bitmap.load()?.bump
+    )]
+    pub spent_bitmap: AccountLoader<'info, SpentBitmap>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierPDA::SPACE,
+        seeds = [b"nullifier", nullifier.as_ref()],
+        bump
+    )]
+    pub nullifier_pda: Account<'info, NullifierPDA>,
+
+    /// Intent committing to this spend's destination; closed on use
+    #[account(mut, close = payer)]
+    pub spend_intent: Account<'info, SpendIntent>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Spend a note and re-commit its amount to a new owner's commitment
+///
+/// The new owner computes `new_commitment` from their own secrets; the
+/// program never sees them. No funds leave the pool.
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct TransferNote<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"spent_bitmap",
+            spent_bitmap.load()?.class.to_le_bytes().as_ref(),
+            spent_bitmap.load()?.chunk.to_le_bytes().as_ref(),
+        ],
+        bump = spent_bitmap.load()?.bump
+    )]
+    pub spent_bitmap: AccountLoader<'info, SpentBitmap>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierPDA::SPACE,
+        seeds = [b"nullifier", nullifier.as_ref()],
+        bump
+    )]
+    pub nullifier_pda: Account<'info, NullifierPDA>,
+
+    /// Intent committing to this spend's destination; closed on use
+    #[account(mut, close = payer)]
+    pub spend_intent: Account<'info, SpendIntent>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Spend a note as a donation to a campaign
+///
+/// Funds go from the pool vault to the campaign vault, so the campaign only
+/// sees its aggregate stealth total, never the donor.
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct PrivateDonate<'info> {
+    /// Payer for the transaction (can be anyone)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"spent_bitmap",
+            spent_bitmap.load()?.class.to_le_bytes().as_ref(),
+            spent_bitmap.load()?.chunk.to_le_bytes().as_ref(),
+        ],
+        bump = spent_bitmap.load()?.bump
+    )]
+    pub spent_bitmap: AccountLoader<'info, SpentBitmap>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierPDA::SPACE,
+        seeds = [b"nullifier", nullifier.as_ref()],
+        bump
+    )]
+    pub nullifier_pda: Account<'info, NullifierPDA>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.campaign_id.as_bytes()],
+        bump = campaign.campaign_bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    /// CHECK: Campaign vault PDA
+    #[account(
+        mut,
+        seeds = [b"vault", campaign.campaign_id.as_bytes()],
+        bump = campaign.vault_bump
+    )]
+    pub campaign_vault: SystemAccount<'info>,
+
+    /// Intent committing to this spend's destination; closed on use
+    #[account(mut, close = payer)]
+    pub spend_intent: Account<'info, SpendIntent>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Spend a note to fund a payroll batch
+///
+/// Funds go from the pool vault to the batch vault, so neither employees
+/// nor observers can trace the batch back to the employer's wallet.
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct FundBatchPrivate<'info> {
+    /// Payer for the transaction (can be anyone)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"spent_bitmap",
+            spent_bitmap.load()?.class.to_le_bytes().as_ref(),
+            spent_bitmap.load()?.chunk.to_le_bytes().as_ref(),
+        ],
+        bump = spent_bitmap.load()?.bump
+    )]
+    pub spent_bitmap: AccountLoader<'info, SpentBitmap>,
+
     #[account(
         init,
         payer = payer,
@@ -103,5 +662,126 @@ pub struct PrivateWithdraw<'info> {
     )]
     pub nullifier_pda: Account<'info, NullifierPDA>,
 
+    #[account(
+        mut,
+        seeds = [b"master_vault"],
+        bump = master_vault.bump
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.master_vault == master_vault.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA
+    #[account(
+        mut,
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    /// Intent committing to this spend's destination; closed on use
+    #[account(mut, close = payer)]
+    pub spend_intent: Account<'info, SpendIntent>,
+
     pub system_program: Program<'info, System>,
 }
+
+/// Create one chunk of the spent-leaf bitmap (permissionless)
+#[derive(Accounts)]
+#[instruction(class: u8, chunk: u64)]
+pub struct InitSpentBitmap<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = SpentBitmap::SPACE,
+        seeds = [b"spent_bitmap", class.to_le_bytes().as_ref(), chunk.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub spent_bitmap: AccountLoader<'info, SpentBitmap>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Read-only view of the shielded set
+///
+/// Pass the note class trees to report on as remaining accounts.
+#[derive(Accounts)]
+pub struct GetShieldedStats {}
+
+/// Close a nullifier PDA and refund its rent to whoever paid for it
+///
+/// Permissionless once the close delay has passed (immediately if spend
+/// times are omitted). The spent leaf stays marked in the bitmap, so the
+/// note cannot be spent again afterwards.
+#[derive(Accounts)]
+pub struct CloseNullifier<'info> {
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"nullifier", nullifier_pda.nullifier.as_ref()],
+        bump = nullifier_pda.bump,
+        has_one = rent_payer,
+        constraint = Clock::get()?.unix_timestamp
+            >= nullifier_pda.used_at.saturating_add(NULLIFIER_CLOSE_DELAY_SECONDS)
+            @ ErrorCode::NullifierCloseTooEarly
+    )]
+    pub nullifier_pda: Account<'info, NullifierPDA>,
+
+    #[account(
+        seeds = [
+            b"spent_bitmap",
+            spent_bitmap.load()?.class.to_le_bytes().as_ref(),
+            spent_bitmap.load()?.chunk.to_le_bytes().as_ref(),
+        ],
+        bump = spent_bitmap.load()?.bump
+    )]
+    pub spent_bitmap: AccountLoader<'info, SpentBitmap>,
+
+    /// CHECK: Original payer of the nullifier PDA, receives the rent
+    #[account(mut)]
+    pub rent_payer: AccountInfo<'info>,
+}
+
+/// Register an auditor-readable disclosure for a commitment
+///
+/// `owner` must be the ed25519 key owning the note; the handler checks the opening.
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct RegisterDisclosure<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Disclosure::SPACE,
+        seeds = [b"disclosure", commitment.as_ref()],
+        bump
+    )]
+    pub disclosure: Account<'info, Disclosure>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Remove a disclosure and refund its rent to the owner
+#[derive(Accounts)]
+pub struct CloseDisclosure<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"disclosure", disclosure.commitment.as_ref()],
+        bump = disclosure.bump,
+        has_one = owner
+    )]
+    pub disclosure: Account<'info, Disclosure>,
+}
@@ -0,0 +1,153 @@
+//! Confidential Pool Account Contexts
+//!
+//! Token-2022 confidential transfer pool operations:
+//! - InitConfidentialPool: Create and configure the confidential vault
+//! - ConfidentialPoolApplyPending: Fold incoming deposits into the available balance
+//! - RequestConfidentialWithdraw: Destination owner consents to one withdrawal
+//! - ConfidentialPoolWithdraw: Confidential transfer out of the vault
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::state::{ConfidentialPool, ConfidentialWithdrawRequest, PrivacyPool};
+use crate::errors::ErrorCode;
+
+/// Create a confidential pool (privacy pool authority only)
+#[derive(Accounts)]
+pub struct InitConfidentialPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = privacy_pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub privacy_pool: Account<'info, PrivacyPool>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ConfidentialPool::SPACE,
+        seeds = [b"confidential_pool", mint.key().as_ref()],
+        bump
+    )]
+    pub confidential_pool: Account<'info, ConfidentialPool>,
+
+    /// CHECK: Vault PDA - created by the handler with room for the confidential
+    /// transfer extension, then initialized by the token program
+    #[account(
+        mut,
+        seeds = [b"confidential_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: Pubkey validity proof context state - verified by the token program
+    pub pubkey_validity_proof: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfidentialPoolApplyPending<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"confidential_pool", confidential_pool.mint.as_ref()],
+        bump = confidential_pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = vault @ ErrorCode::Unauthorized
+    )]
+    pub confidential_pool: Account<'info, ConfidentialPool>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+/// Open a withdraw request for a destination token account (its owner only)
+#[derive(Accounts)]
+pub struct RequestConfidentialWithdraw<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"confidential_pool", confidential_pool.mint.as_ref()],
+        bump = confidential_pool.bump
+    )]
+    pub confidential_pool: Account<'info, ConfidentialPool>,
+
+    #[account(
+        token::mint = confidential_pool.mint,
+        token::authority = owner
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ConfidentialWithdrawRequest::SPACE,
+        seeds = [b"confidential_withdraw", confidential_pool.key().as_ref(), destination.key().as_ref()],
+        bump
+    )]
+    pub request: Account<'info, ConfidentialWithdrawRequest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfidentialPoolWithdraw<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"confidential_pool", mint.key().as_ref()],
+        bump = confidential_pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = mint @ ErrorCode::Unauthorized,
+        has_one = vault @ ErrorCode::Unauthorized
+    )]
+    pub confidential_pool: Account<'info, ConfidentialPool>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Recipient's confidential token account (typically owned by a stealth key)
+    #[account(mut, token::mint = mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// The destination owner's request, consumed by this withdrawal
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"confidential_withdraw", confidential_pool.key().as_ref(), destination.key().as_ref()],
+        bump = request.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        constraint = Clock::get()?.unix_timestamp >= request.available_at @ ErrorCode::ConfidentialWithdrawTooEarly
+    )]
+    pub request: Account<'info, ConfidentialWithdrawRequest>,
+
+    /// CHECK: Request owner, receives its rent back
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Ciphertext-commitment equality proof context - verified by the token program
+    pub equality_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Ciphertext validity proof context - verified by the token program
+    pub ciphertext_validity_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Range proof context - verified by the token program
+    pub range_proof: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+}
@@ -0,0 +1,121 @@
+//! Hidden-Amount Note Account Contexts
+//!
+//! Pedersen-commitment notes whose amount is not part of the revealed leaf:
+//! - PrivateDepositHidden: Deposit into a hidden-amount note
+//! - PrivateWithdrawHidden: Withdraw part of a note, re-committing the change
+
+use anchor_lang::prelude::*;
+use crate::state::{PrivacyPool, PoolShard, CommitmentTree, NullifierPDA, SpentBitmap};
+use crate::errors::ErrorCode;
+
+/// Deposit into a hidden-amount note
+///
+/// The lamports moved are public, so the depositor proves the Pedersen
+/// commitment opens to exactly that amount. Later spends reveal the
+/// commitment but never the amount it holds.
+#[derive(Accounts)]
+pub struct PrivateDepositHidden<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw a public amount from a hidden-amount note
+///
+/// The note's remaining value is re-committed as a change note. A balance
+/// proof shows input = withdrawn + change and is bound to the recipient and
+/// the change note, and a range proof (pre-verified into a context state
+/// account by the ZK ElGamal proof program) shows the change is non-negative,
+/// so neither the note nor the change amount is revealed. With a 20-level Merkle path the transaction needs an address
+/// lookup table to fit.
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct PrivateWithdrawHidden<'info> {
+    /// Payer for the transaction (can be anyone)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: The recipient (any address, typically stealth)
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"spent_bitmap",
+            spent_bitmap.load()?.class.to_le_bytes().as_ref(),
+            spent_bitmap.load()?.chunk.to_le_bytes().as_ref(),
+        ],
+        bump = spent_bitmap.load()?.bump
+    )]
+    pub spent_bitmap: AccountLoader<'info, SpentBitmap>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierPDA::SPACE,
+        seeds = [b"nullifier", nullifier.as_ref()],
+        bump
+    )]
+    pub nullifier_pda: Account<'info, NullifierPDA>,
+
+    /// CHECK: 64-bit range proof context for the change commitment - checked in the handler
+    pub range_proof: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
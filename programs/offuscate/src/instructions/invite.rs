@@ -3,7 +3,7 @@
 //! Employee onboarding via invite codes
 
 use anchor_lang::prelude::*;
-use crate::state::{Campaign, Invite, PayrollBatch, Employee, MasterVault};
+use crate::state::{BatchStatus, Campaign, Invite, PayrollBatch, Employee, MasterVault, RosterPage};
 use crate::errors::ErrorCode;
 
 #[derive(Accounts)]
@@ -38,11 +38,11 @@ pub struct CreateBatchInvite<'info> {
     pub owner: Signer<'info>,
 
     #[account(
-        seeds = [b"batch", batch.master_vault.as_ref(), &batch.index.to_le_bytes()],
-        bump = batch.batch_bump,
+        seeds = [b"batch", batch.load()?.master_vault.as_ref(), &batch.load()?.index.to_le_bytes()],
+        bump = batch.load()?.batch_bump,
         has_one = owner @ ErrorCode::Unauthorized
     )]
-    pub batch: Account<'info, PayrollBatch>,
+    pub batch: AccountLoader<'info, PayrollBatch>,
 
     #[account(
         init,
@@ -116,19 +116,49 @@ pub struct AcceptInviteStreaming<'info> {
     /// The batch this invite belongs to
     #[account(
         mut,
-        constraint = batch.owner == invite.creator @ ErrorCode::Unauthorized
+        constraint = batch.load()?.owner == invite.creator @ ErrorCode::Unauthorized,
+        constraint = batch.load()?.status()? == BatchStatus::Active @ ErrorCode::BatchNotActive
     )]
-    pub batch: Account<'info, PayrollBatch>,
+    pub batch: AccountLoader<'info, PayrollBatch>,
 
     /// The new employee account (created with stealth pubkey)
     #[account(
         init,
         payer = payer,
         space = Employee::SPACE,
-        seeds = [b"employee", batch.key().as_ref(), &batch.employee_count.to_le_bytes()],
+        seeds = [b"employee", batch.key().as_ref(), &batch.load()?.next_employee_index().to_le_bytes()],
         bump
     )]
-    pub employee: Account<'info, Employee>,
+    pub employee: AccountLoader<'info, Employee>,
+
+    #[account(
+        mut,
+        seeds = [b"roster", batch.key().as_ref(), &RosterPage::page_of(batch.load()?.next_employee_index()).to_le_bytes()],
+        bump = roster.load()?.bump
+    )]
+    pub roster: AccountLoader<'info, RosterPage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Expire a batch's stale invites (permissionless, tipped from the batch vault)
+///
+/// The invites go in remaining accounts.
+#[derive(Accounts)]
+pub struct CrankExpireInvites<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(mut)]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA
+    #[account(
+        mut,
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
 
     pub system_program: Program<'info, System>,
 }
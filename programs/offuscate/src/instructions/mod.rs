@@ -11,7 +11,11 @@
 //! - payroll: Streaming payroll operations
 //! - receipt: Anonymous receipt operations
 //! - commitment: Commitment-based privacy operations
+//! - hidden_note: Hidden-amount (Pedersen) notes
+//! - token_note: Shielded SPL token notes
 //! - relayer: Relayer-assisted operations
+//! - wsol: Wrapped SOL deposits and claims, swap claims
+//! - confidential_pool: Token-2022 confidential transfer pool
 
 pub mod privacy_pool;
 pub mod campaign;
@@ -20,7 +24,11 @@ pub mod invite;
 pub mod payroll;
 pub mod receipt;
 pub mod commitment;
+pub mod hidden_note;
+pub mod token_note;
 pub mod relayer;
+pub mod wsol;
+pub mod confidential_pool;
 
 // Re-export all contexts
 pub use privacy_pool::*;
@@ -30,4 +38,8 @@ pub use invite::*;
 pub use payroll::*;
 pub use receipt::*;
 pub use commitment::*;
+pub use hidden_note::*;
+pub use token_note::*;
 pub use relayer::*;
+pub use wsol::*;
+pub use confidential_pool::*;
@@ -3,7 +3,9 @@
 //! Streaming payroll operations
 
 use anchor_lang::prelude::*;
-use crate::state::{MasterVault, PayrollBatch, Employee, BatchStatus};
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::state::{MasterVault, PayrollBatch, BatchRole, EarningSnapshot, Employee, RosterPage, BatchStatus, PrivacyPool, PoolShard, CommitmentTree};
 use crate::errors::ErrorCode;
 
 #[derive(Accounts)]
@@ -23,6 +25,34 @@ pub struct InitMasterVault<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Update global payroll settings (payroll authority only)
+#[derive(Accounts)]
+pub struct UpdatePayrollConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"master_vault"],
+        bump = master_vault.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+}
+
+/// Accept a proposed payroll authority transfer (proposed authority only)
+#[derive(Accounts)]
+pub struct AcceptPayrollAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"master_vault"],
+        bump = master_vault.bump,
+        constraint = master_vault.pending_authority == new_authority.key() @ ErrorCode::NotPendingPayrollAuthority
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+}
+
 #[derive(Accounts)]
 #[instruction(title: String)]
 pub struct CreateBatch<'info> {
@@ -43,7 +73,7 @@ pub struct CreateBatch<'info> {
         seeds = [b"batch", master_vault.key().as_ref(), &master_vault.batch_count.to_le_bytes()],
         bump
     )]
-    pub batch: Account<'info, PayrollBatch>,
+    pub batch: AccountLoader<'info, PayrollBatch>,
 
     /// CHECK: Batch vault PDA
     #[account(
@@ -59,18 +89,23 @@ pub struct CreateBatch<'info> {
 #[derive(Accounts)]
 #[instruction(stealth_address: String, salary_rate: u64)]
 pub struct AddEmployee<'info> {
+    /// Batch owner, or an admin with the ADD_EMPLOYEES role
     #[account(mut)]
     pub owner: Signer<'info>,
 
     #[account(mut)]
     pub master_vault: Account<'info, MasterVault>,
 
+    pub role: Option<Account<'info, BatchRole>>,
+
     #[account(
         mut,
-        constraint = batch.owner == owner.key() @ ErrorCode::Unauthorized,
-        constraint = batch.status == BatchStatus::Active @ ErrorCode::CampaignNotActive
+        constraint = batch.load()?.owner == owner.key()
+            || role.as_deref().is_some_and(|role| role.allows(&batch.key(), &owner.key(), BatchRole::ADD_EMPLOYEES))
+            @ ErrorCode::Unauthorized,
+        constraint = batch.load()?.status()? == BatchStatus::Active @ ErrorCode::CampaignNotActive
     )]
-    pub batch: Account<'info, PayrollBatch>,
+    pub batch: AccountLoader<'info, PayrollBatch>,
 
     /// CHECK: Employee wallet to be added
     pub employee_wallet: UncheckedAccount<'info>,
@@ -79,10 +114,17 @@ pub struct AddEmployee<'info> {
         init,
         payer = owner,
         space = Employee::SPACE,
-        seeds = [b"employee", batch.key().as_ref(), &batch.employee_count.to_le_bytes()],
+        seeds = [b"employee", batch.key().as_ref(), &batch.load()?.next_employee_index().to_le_bytes()],
         bump
     )]
-    pub employee: Account<'info, Employee>,
+    pub employee: AccountLoader<'info, Employee>,
+
+    #[account(
+        mut,
+        seeds = [b"roster", batch.key().as_ref(), &RosterPage::page_of(batch.load()?.next_employee_index()).to_le_bytes()],
+        bump = roster.load()?.bump
+    )]
+    pub roster: AccountLoader<'info, RosterPage>,
 
     pub system_program: Program<'info, System>,
 }
@@ -96,19 +138,222 @@ pub struct FundBatch<'info> {
     pub master_vault: Account<'info, MasterVault>,
 
     #[account(mut)]
-    pub batch: Account<'info, PayrollBatch>,
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA
+    #[account(
+        mut,
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit into the owner's payroll treasury
+#[derive(Accounts)]
+pub struct FundTreasury<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"master_vault"],
+        bump = master_vault.bump
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    /// CHECK: Treasury PDA, one per owner
+    #[account(
+        mut,
+        seeds = [b"payroll_treasury", master_vault.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Move treasury funds into one of the owner's batch vaults
+#[derive(Accounts)]
+pub struct AllocateToBatch<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"master_vault"],
+        bump = master_vault.bump
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    /// CHECK: Treasury PDA, one per owner
+    #[account(
+        mut,
+        seeds = [b"payroll_treasury", master_vault.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = batch.load()?.master_vault == master_vault.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
 
     /// CHECK: Batch vault PDA
     #[account(
         mut,
         seeds = [b"batch_vault", batch.key().as_ref()],
-        bump = batch.vault_bump
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Open a batch token vault for `mint` (batch owner only)
+#[derive(Accounts)]
+pub struct InitBatchTokenVault<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA, authority of the batch's token vaults
+    #[account(
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
     )]
     pub batch_vault: SystemAccount<'info>,
 
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = owner,
+        token::mint = mint,
+        token::authority = batch_vault,
+        seeds = [b"batch_token_vault", batch.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct FundBatchToken<'info> {
+    pub funder: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = funder,
+    )]
+    pub funder_token: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"master_vault"],
+        bump = master_vault.bump
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        constraint = batch.load()?.master_vault == master_vault.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        mut,
+        seeds = [b"batch_token_vault", batch.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Claim salary paid in tokens, to any token account of the pay mint
+#[derive(Accounts)]
+pub struct ClaimSalaryToken<'info> {
+    pub recipient: Signer<'info>,
+
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA, authority of the batch's token vaults
+    #[account(
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.wallet == recipient.key() @ ErrorCode::Unauthorized,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized,
+        constraint = employee.load()?.pay_mint == token_vault.mint @ ErrorCode::InvalidPayMint
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+
+    #[account(
+        mut,
+        seeds = [b"batch_token_vault", batch.key().as_ref(), token_vault.mint.as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = token_vault.mint,
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Close a batch token vault, returning its balance (batch owner only)
+#[derive(Accounts)]
+pub struct CloseBatchTokenVault<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA, authority of the batch's token vaults
+    #[account(
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"batch_token_vault", batch.key().as_ref(), token_vault.mint.as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = token_vault.mint,
+    )]
+    pub owner_token: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
 #[derive(Accounts)]
 pub struct ClaimSalary<'info> {
     #[account(mut)]
@@ -118,56 +363,882 @@ pub struct ClaimSalary<'info> {
     pub master_vault: Account<'info, MasterVault>,
 
     #[account(mut)]
-    pub batch: Account<'info, PayrollBatch>,
+    pub batch: AccountLoader<'info, PayrollBatch>,
 
     /// CHECK: Batch vault PDA
     #[account(
         mut,
         seeds = [b"batch_vault", batch.key().as_ref()],
-        bump = batch.vault_bump
+        bump = batch.load()?.vault_bump
     )]
     pub batch_vault: SystemAccount<'info>,
 
     #[account(
         mut,
-        constraint = employee.wallet == recipient.key() @ ErrorCode::Unauthorized,
-        constraint = employee.batch == batch.key() @ ErrorCode::Unauthorized
+        constraint = employee.load()?.wallet == recipient.key() @ ErrorCode::Unauthorized,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
     )]
-    pub employee: Account<'info, Employee>,
+    pub employee: AccountLoader<'info, Employee>,
+
+    /// CHECK: Pyth SOL/USD price update, required by USD-denominated batches;
+    /// owner, layout and feed id are checked in the handler
+    pub price_update: Option<UncheckedAccount<'info>>,
 
     pub system_program: Program<'info, System>,
 }
 
+/// Claim accrued salary to a fresh stealth recipient
+///
+/// Authorized by the employee wallet's ed25519 signature naming `recipient`,
+/// so the submitter can be anyone and the wallet never receives the payout.
 #[derive(Accounts)]
-pub struct UpdateSalaryRate<'info> {
+pub struct ClaimSalaryStealth<'info> {
+    /// Pays gas; typically a relayer
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub payer: Signer<'info>,
+
+    /// CHECK: Fresh stealth address receiving the salary
+    #[account(
+        mut,
+        constraint = recipient.key() != employee.load()?.wallet @ ErrorCode::StealthRecipientReused,
+        constraint = recipient.lamports() == 0 && recipient.data_is_empty() @ ErrorCode::StealthRecipientReused
+    )]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub master_vault: Account<'info, MasterVault>,
 
+    #[account(mut)]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA
     #[account(
-        constraint = batch.owner == owner.key() @ ErrorCode::Unauthorized
+        mut,
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
     )]
-    pub batch: Account<'info, PayrollBatch>,
+    pub batch_vault: SystemAccount<'info>,
 
     #[account(
         mut,
-        constraint = employee.batch == batch.key() @ ErrorCode::Unauthorized
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
     )]
-    pub employee: Account<'info, Employee>,
+    pub employee: AccountLoader<'info, Employee>,
+
+    /// CHECK: Instructions sysvar for ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
+/// Register the employee's payout address for pushed salary
 #[derive(Accounts)]
-pub struct SetEmployeeStatus<'info> {
+pub struct SetPayoutAddress<'info> {
+    pub employee_wallet: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.wallet == employee_wallet.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+}
+
+/// Rotate the employee's stealth meta-address
+#[derive(Accounts)]
+pub struct UpdateEmployeeStealth<'info> {
+    pub employee_wallet: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.wallet == employee_wallet.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+}
+
+/// Opt in or out of owner-pushed salary payments
+#[derive(Accounts)]
+pub struct SetPushPayments<'info> {
+    pub employee_wallet: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.wallet == employee_wallet.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+}
+
+/// Push accrued salary to an opted-in employee's wallet (batch owner only)
+#[derive(Accounts)]
+pub struct PushSalary<'info> {
+    pub owner: Signer<'info>,
+
     #[account(mut)]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA
+    #[account(
+        mut,
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+
+    #[account(
+        mut,
+        constraint = wallet.key() == employee.load()?.wallet @ ErrorCode::Unauthorized
+    )]
+    pub wallet: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Push salary to many employees at once (batch owner only)
+///
+/// Employees and their payout addresses go in remaining accounts.
+#[derive(Accounts)]
+pub struct RunPayday<'info> {
     pub owner: Signer<'info>,
 
+    #[account(mut)]
+    pub master_vault: Account<'info, MasterVault>,
+
     #[account(
-        constraint = batch.owner == owner.key() @ ErrorCode::Unauthorized
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
     )]
-    pub batch: Account<'info, PayrollBatch>,
+    pub batch: AccountLoader<'info, PayrollBatch>,
 
+    /// CHECK: Batch vault PDA
     #[account(
         mut,
-        constraint = employee.batch == batch.key() @ ErrorCode::Unauthorized
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
     )]
-    pub employee: Account<'info, Employee>,
+    pub batch_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless `run_payday` for keeper bots, tipped from the batch vault
+///
+/// Employees and their payout addresses go in remaining accounts.
+#[derive(Accounts)]
+pub struct CrankClaimReadySalaries<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(mut)]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(mut)]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA
+    #[account(
+        mut,
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCrankTip<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinClaimInterval<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+}
+
+/// Claim accrued salary straight into a privacy pool commitment
+///
+/// The payout moves from the batch vault to the pool vault; no address the
+/// employee controls ever receives it. The amount must be a standard
+/// denomination, so any remainder keeps accruing.
+#[derive(Accounts)]
+pub struct ClaimSalaryToCommitment<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    #[account(mut)]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(mut)]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA
+    #[account(
+        mut,
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.wallet == recipient.key() @ ErrorCode::Unauthorized,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    /// CHECK: Pyth SOL/USD price update, required by USD-denominated batches;
+    /// owner, layout and feed id are checked in the handler
+    pub price_update: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim accrued salary into a privacy pool commitment, fees paid by `payer`
+///
+/// The employee wallet only signs; no address it controls receives the
+/// salary, and no withdrawal address appears in the transaction.
+#[derive(Accounts)]
+pub struct ClaimSalaryToPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub employee_wallet: Signer<'info>,
+
+    #[account(mut)]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(mut)]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA
+    #[account(
+        mut,
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.wallet == employee_wallet.key() @ ErrorCode::Unauthorized,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    /// CHECK: Pyth SOL/USD price update, required by USD-denominated batches;
+    /// owner, layout and feed id are checked in the handler
+    pub price_update: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSalaryRate<'info> {
+    /// Batch owner, or an admin with the UPDATE_RATES role
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub role: Option<Account<'info, BatchRole>>,
+
+    #[account(
+        constraint = batch.load()?.owner == owner.key()
+            || role.as_deref().is_some_and(|role| role.allows(&batch.key(), &owner.key(), BatchRole::UPDATE_RATES))
+            @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+}
+
+/// Switch which vault an employee is paid from
+#[derive(Accounts)]
+pub struct SetEmployeePayMint<'info> {
+    /// Batch owner, or an admin with the UPDATE_RATES role
+    pub owner: Signer<'info>,
+
+    pub role: Option<Account<'info, BatchRole>>,
+
+    #[account(
+        constraint = batch.load()?.owner == owner.key()
+            || role.as_deref().is_some_and(|role| role.allows(&batch.key(), &owner.key(), BatchRole::UPDATE_RATES))
+            @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+
+    /// Token vault of the new pay mint; omitted to pay in SOL
+    #[account(
+        seeds = [b"batch_token_vault", batch.key().as_ref(), token_vault.mint.as_ref()],
+        bump
+    )]
+    pub token_vault: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimCap<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+}
+
+#[derive(Accounts)]
+pub struct ProposePayoutSplits<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+}
+
+/// Acknowledge proposed payout splits (employee wallet only)
+#[derive(Accounts)]
+pub struct AcceptPayoutSplits<'info> {
+    pub employee_wallet: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.wallet == employee_wallet.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+}
+
+#[derive(Accounts)]
+pub struct AllocateEmployeeBudget<'info> {
+    /// Batch owner, or an admin with the FUND role
+    pub owner: Signer<'info>,
+
+    pub role: Option<Account<'info, BatchRole>>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key()
+            || role.as_deref().is_some_and(|role| role.allows(&batch.key(), &owner.key(), BatchRole::FUND))
+            @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA
+    #[account(
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+}
+
+#[derive(Accounts)]
+pub struct SetSalaryCurrency<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaySchedule<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+}
+
+#[derive(Accounts)]
+pub struct SetEmployeeStatus<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+
+    #[account(
+        mut,
+        seeds = [b"roster", batch.key().as_ref(), &RosterPage::page_of(employee.load()?.index).to_le_bytes()],
+        bump = roster.load()?.bump
+    )]
+    pub roster: AccountLoader<'info, RosterPage>,
+}
+
+/// Terminate an employee with optional severance (batch owner only)
+#[derive(Accounts)]
+pub struct TerminateEmployee<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA
+    #[account(
+        mut,
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+
+    #[account(
+        mut,
+        constraint = wallet.key() == employee.load()?.wallet @ ErrorCode::Unauthorized
+    )]
+    pub wallet: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"roster", batch.key().as_ref(), &RosterPage::page_of(employee.load()?.index).to_le_bytes()],
+        bump = roster.load()?.bump
+    )]
+    pub roster: AccountLoader<'info, RosterPage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBatchStatus<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+}
+
+/// Grant a secondary admin scoped permissions on a batch (batch owner only)
+#[derive(Accounts)]
+#[instruction(admin: Pubkey)]
+pub struct GrantBatchRole<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = BatchRole::SPACE,
+        seeds = [b"batch_role", batch.key().as_ref(), admin.as_ref()],
+        bump
+    )]
+    pub role: Account<'info, BatchRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Revoke a secondary admin's role (batch owner only)
+#[derive(Accounts)]
+pub struct RevokeBatchRole<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        mut,
+        close = owner,
+        constraint = role.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub role: Account<'info, BatchRole>,
+}
+
+/// Propose a new batch owner (batch owner only)
+#[derive(Accounts)]
+pub struct TransferBatchOwnership<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+}
+
+/// Accept a proposed batch ownership transfer (proposed owner only)
+#[derive(Accounts)]
+pub struct AcceptBatchOwnership<'info> {
+    pub new_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.pending_owner == new_owner.key() @ ErrorCode::NotPendingBatchOwner
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+}
+
+/// Create one page of a batch's employee roster (permissionless)
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct InitRosterPage<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = RosterPage::SPACE,
+        seeds = [b"roster", batch.key().as_ref(), &page.to_le_bytes()],
+        bump
+    )]
+    pub roster: AccountLoader<'info, RosterPage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Snapshot an employee's earnings for the current month (permissionless)
+#[derive(Accounts)]
+pub struct SnapshotEmployeePeriod<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = EarningSnapshot::SPACE,
+        seeds = [
+            b"earning_snapshot",
+            employee.key().as_ref(),
+            &EarningSnapshot::period_of(Clock::get()?.unix_timestamp).to_le_bytes(),
+        ],
+        bump
+    )]
+    pub snapshot: Account<'info, EarningSnapshot>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Batch runway view; employees are passed as remaining accounts
+#[derive(Accounts)]
+pub struct GetBatchRunway<'info> {
+    pub batch: AccountLoader<'info, PayrollBatch>,
+}
+
+/// Close a terminated, fully settled employee (batch owner only)
+#[derive(Accounts)]
+pub struct CloseEmployee<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"master_vault"],
+        bump = master_vault.bump
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+
+    /// CHECK: Paid the employee account's rent at creation; receives it back
+    #[account(
+        mut,
+        constraint = rent_payer.key() == employee.load()?.rent_refund_to(&owner.key()) @ ErrorCode::Unauthorized
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"roster", batch.key().as_ref(), &RosterPage::page_of(employee.load()?.index).to_le_bytes()],
+        bump = roster.load()?.bump
+    )]
+    pub roster: AccountLoader<'info, RosterPage>,
+}
+
+/// Move an employee into another batch of the same owner (batch owner only)
+#[derive(Accounts)]
+pub struct TransferEmployee<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"master_vault"],
+        bump = master_vault.bump
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = batch.load()?.status()? == BatchStatus::Active @ ErrorCode::BatchNotActive
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA of the old batch
+    #[account(
+        mut,
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        constraint = employee.load()?.batch == batch.key() @ ErrorCode::Unauthorized
+    )]
+    pub employee: AccountLoader<'info, Employee>,
+
+    /// CHECK: Paid the employee account's rent at creation; receives it back
+    #[account(
+        mut,
+        constraint = rent_payer.key() == employee.load()?.rent_refund_to(&owner.key()) @ ErrorCode::Unauthorized
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"roster", batch.key().as_ref(), &RosterPage::page_of(employee.load()?.index).to_le_bytes()],
+        bump = roster.load()?.bump
+    )]
+    pub roster: AccountLoader<'info, RosterPage>,
+
+    /// Employee wallet, receives the settled salary
+    #[account(
+        mut,
+        constraint = wallet.key() == employee.load()?.wallet @ ErrorCode::Unauthorized
+    )]
+    pub wallet: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = new_batch.key() != batch.key() @ ErrorCode::Unauthorized,
+        constraint = new_batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = new_batch.load()?.master_vault == batch.load()?.master_vault @ ErrorCode::Unauthorized,
+        constraint = new_batch.load()?.salary_currency == batch.load()?.salary_currency @ ErrorCode::Unauthorized,
+        constraint = new_batch.load()?.status()? == BatchStatus::Active @ ErrorCode::BatchNotActive
+    )]
+    pub new_batch: AccountLoader<'info, PayrollBatch>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Employee::SPACE,
+        seeds = [b"employee", new_batch.key().as_ref(), &new_batch.load()?.next_employee_index().to_le_bytes()],
+        bump
+    )]
+    pub new_employee: AccountLoader<'info, Employee>,
+
+    #[account(
+        mut,
+        seeds = [b"roster", new_batch.key().as_ref(), &RosterPage::page_of(new_batch.load()?.next_employee_index()).to_le_bytes()],
+        bump = new_roster.load()?.bump
+    )]
+    pub new_roster: AccountLoader<'info, RosterPage>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBatch<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"master_vault"],
+        bump = master_vault.bump
+    )]
+    pub master_vault: Account<'info, MasterVault>,
+
+    #[account(
+        mut,
+        close = owner,
+        constraint = batch.load()?.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = batch.load()?.master_vault == master_vault.key() @ ErrorCode::Unauthorized
+    )]
+    pub batch: AccountLoader<'info, PayrollBatch>,
+
+    /// CHECK: Batch vault PDA
+    #[account(
+        mut,
+        seeds = [b"batch_vault", batch.key().as_ref()],
+        bump = batch.load()?.vault_bump
+    )]
+    pub batch_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Convert a batch created with the Borsh layout to the zero-copy one (permissionless)
+#[derive(Accounts)]
+pub struct MigratePayrollBatch<'info> {
+    /// Pays the rent for any extra space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Not loadable yet; the handler checks discriminator and PDA
+    #[account(mut, owner = crate::ID)]
+    pub batch: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Convert an employee created with the Borsh layout to the zero-copy one (permissionless)
+#[derive(Accounts)]
+pub struct MigrateEmployee<'info> {
+    /// Pays the rent for any extra space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Not loadable yet; the handler checks discriminator and PDA
+    #[account(mut, owner = crate::ID)]
+    pub employee: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
@@ -1,17 +1,56 @@
 //! Privacy Pool Account Contexts
 //!
 //! Accounts for privacy pool operations:
-//! - InitPrivacyPool, PoolDeposit, RequestWithdraw, ClaimWithdraw, etc.
+//! - MigratePrivacyPool, InitPrivacyPool, InitPoolShard, DrainLegacyVault, PoolDeposit, RequestWithdraw, ClaimWithdraw,
+//!   ReopenWithdraw, OfferClaimTip, ExecuteTippedClaim, ExpirePendingWithdraw, etc.
+//! - InitWithdrawQueue, RequestWithdrawQueued, ShuffleWithdrawQueue, ReleaseQueuedWithdraw
+//! - InitEpochSnapshot, FinalizeEpochSnapshot
 
 use anchor_lang::prelude::*;
-use crate::state::{PrivacyPool, PendingWithdraw, ChurnVaultState};
+use crate::state::{PrivacyPool, PoolShard, PendingWithdraw, ChurnVaultState, WithdrawQueue, EpochSnapshot};
+use crate::constants::VAULT_SHARD_COUNT;
 use crate::errors::ErrorCode;
 
+/// Grow the pool account created with the original layout (program upgrade
+/// authority only)
+#[derive(Accounts)]
+pub struct MigratePrivacyPool<'info> {
+    /// Pays the rent for the extra space and becomes the pool authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::Unauthorized)]
+    pub program: Program<'info, crate::program::Offuscate>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// CHECK: Too small to load yet; the handler checks the discriminator
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"privacy_pool"],
+        bump
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the privacy pool (program upgrade authority only)
+///
+/// The signer becomes the pool authority, so only the deployer may claim it.
 #[derive(Accounts)]
 pub struct InitPrivacyPool<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::Unauthorized)]
+    pub program: Program<'info, crate::program::Offuscate>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
     #[account(
         init,
         payer = authority,
@@ -21,34 +60,162 @@ pub struct InitPrivacyPool<'info> {
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA - just holds SOL, no data
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the booking account of one vault shard (permissionless)
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct InitPoolShard<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"privacy_pool"],
+        bump = pool.bump,
+        constraint = (index as usize) < VAULT_SHARD_COUNT @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = PoolShard::SPACE,
+        seeds = [b"pool_shard", index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of the new shard (for its booked balance)
+    #[account(address = pool.shard_vault(index) @ ErrorCode::InvalidVaultShard)]
+    pub pool_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Move the pre-shard vault's lamports into a vault shard (permissionless)
+#[derive(Accounts)]
+pub struct DrainLegacyVault<'info> {
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard`
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    /// CHECK: Vault PDA used before sharding - just holds SOL
     #[account(
         mut,
         seeds = [b"pool_vault"],
         bump
     )]
+    pub legacy_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Update pool configuration (pool authority only)
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"privacy_pool"],
+        bump = pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+}
+
+/// Sweep non-denominated dust into the treasury (pool authority only)
+#[derive(Accounts)]
+pub struct SweepDust<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"privacy_pool"],
+        bump = pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
     pub pool_vault: SystemAccount<'info>,
 
+    /// CHECK: Treasury PDA - just holds SOL
+    #[account(
+        mut,
+        seeds = [b"pool_treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
 
+/// Accept a proposed pool authority transfer (proposed authority only)
+#[derive(Accounts)]
+pub struct AcceptPoolAuthority<'info> {
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"privacy_pool"],
+        bump = pool.bump,
+        constraint = pool.pending_authority == new_authority.key() @ ErrorCode::NotPendingAuthority
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+}
+
 #[derive(Accounts)]
 pub struct PoolDeposit<'info> {
     #[account(mut)]
     pub depositor: Signer<'info>,
 
     #[account(
-        mut,
         seeds = [b"privacy_pool"],
         bump = pool.bump
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA
     #[account(
         mut,
-        seeds = [b"pool_vault"],
-        bump = pool.vault_bump
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
     )]
     pub pool_vault: SystemAccount<'info>,
 
@@ -70,12 +237,13 @@ pub struct RequestWithdraw<'info> {
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA (for balance check)
+    /// Shard the withdrawal is reserved on and paid from
     #[account(
-        seeds = [b"pool_vault"],
-        bump = pool.vault_bump
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
     )]
-    pub pool_vault: SystemAccount<'info>,
+    pub pool_shard: Account<'info, PoolShard>,
 
     #[account(
         init,
@@ -84,7 +252,7 @@ pub struct RequestWithdraw<'info> {
         seeds = [b"pending", recipient.key().as_ref()],
         bump
     )]
-    pub pending_withdraw: Account<'info, PendingWithdraw>,
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
 
     pub system_program: Program<'info, System>,
 }
@@ -94,62 +262,385 @@ pub struct ClaimWithdraw<'info> {
     #[account(mut)]
     pub recipient: Signer<'info>,
 
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
     #[account(
         mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pending", recipient.key().as_ref()],
+        bump = pending_withdraw.load()?.bump,
+        constraint = pending_withdraw.load()?.shard == pool_shard.index @ ErrorCode::WrongPoolShard,
+        constraint = pending_withdraw.load()?.recipient == recipient.key() @ ErrorCode::Unauthorized
+    )]
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReopenWithdraw<'info> {
+    pub recipient: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pending", recipient.key().as_ref()],
+        bump = pending_withdraw.load()?.bump,
+        constraint = pending_withdraw.load()?.recipient == recipient.key() @ ErrorCode::Unauthorized
+    )]
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
+}
+
+/// Offer a tip for permissionless execution of the recipient's claim
+#[derive(Accounts)]
+pub struct OfferClaimTip<'info> {
+    pub recipient: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pending", recipient.key().as_ref()],
+        bump = pending_withdraw.load()?.bump,
+        constraint = pending_withdraw.load()?.recipient == recipient.key() @ ErrorCode::Unauthorized
+    )]
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
+}
+
+/// Execute a tipped claim on the recipient's behalf (permissionless)
+#[derive(Accounts)]
+pub struct ExecuteTippedClaim<'info> {
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    /// CHECK: Receives the claim; must be the pending withdrawal's recipient
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
         seeds = [b"privacy_pool"],
         bump = pool.bump
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA
     #[account(
         mut,
-        seeds = [b"pool_vault"],
-        bump = pool.vault_bump
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
     )]
     pub pool_vault: SystemAccount<'info>,
 
     #[account(
         mut,
         seeds = [b"pending", recipient.key().as_ref()],
-        bump = pending_withdraw.bump,
-        constraint = pending_withdraw.recipient == recipient.key() @ ErrorCode::Unauthorized
+        bump = pending_withdraw.load()?.bump,
+        constraint = pending_withdraw.load()?.shard == pool_shard.index @ ErrorCode::WrongPoolShard,
+        constraint = pending_withdraw.load()?.recipient == recipient.key() @ ErrorCode::Unauthorized
     )]
-    pub pending_withdraw: Account<'info, PendingWithdraw>,
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
 
     pub system_program: Program<'info, System>,
 }
 
+/// Void an abandoned pending withdrawal (permissionless)
 #[derive(Accounts)]
-pub struct GetPoolStats<'info> {
+pub struct ExpirePendingWithdraw<'info> {
     #[account(
         seeds = [b"privacy_pool"],
         bump = pool.bump
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA
+    /// Shard the withdrawal is reserved on and paid from
     #[account(
-        seeds = [b"pool_vault"],
-        bump = pool.vault_bump
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    #[account(
+        mut,
+        seeds = [b"pending", pending_withdraw.load()?.recipient.as_ref()],
+        bump = pending_withdraw.load()?.bump,
+        constraint = pending_withdraw.load()?.shard == pool_shard.index @ ErrorCode::WrongPoolShard
+    )]
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
+}
+
+/// Close a claimed or voided pending withdrawal, refunding its rent to the recipient
+#[derive(Accounts)]
+pub struct ClosePendingWithdraw<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pending", recipient.key().as_ref()],
+        bump = pending_withdraw.load()?.bump,
+        constraint = pending_withdraw.load()?.recipient == recipient.key() @ ErrorCode::Unauthorized,
+        constraint = pending_withdraw.load()?.is_settled() @ ErrorCode::WithdrawNotSettled,
+        close = recipient
+    )]
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
+}
+
+/// Grow a pending withdrawal created with the original layout (permissionless)
+#[derive(Accounts)]
+pub struct MigratePendingWithdraw<'info> {
+    /// Pays the rent for the extra space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Too small to load yet; the handler checks owner, discriminator and PDA
+    #[account(mut, owner = crate::ID)]
+    pub pending_withdraw: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the shuffled withdrawal queue for a time bucket (permissionless)
+#[derive(Accounts)]
+#[instruction(bucket: i64)]
+pub struct InitWithdrawQueue<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = WithdrawQueue::SPACE,
+        seeds = [b"withdraw_queue", bucket.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub withdraw_queue: Account<'info, WithdrawQueue>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestWithdrawQueued<'info> {
+    /// Payer for account rent (the connected wallet)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The recipient (stealth address keypair - signs to prove ownership)
+    pub recipient: Signer<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    /// Shard the withdrawal is reserved on and paid from
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    #[account(
+        mut,
+        seeds = [b"withdraw_queue", withdraw_queue.bucket.to_le_bytes().as_ref()],
+        bump = withdraw_queue.bump
+    )]
+    pub withdraw_queue: Account<'info, WithdrawQueue>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = PendingWithdraw::SPACE,
+        seeds = [b"pending", recipient.key().as_ref()],
+        bump
+    )]
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Arm or run the shuffle of a closed queue bucket (crank, permissionless)
+#[derive(Accounts)]
+pub struct ShuffleWithdrawQueue<'info> {
+    #[account(
+        mut,
+        seeds = [b"withdraw_queue", withdraw_queue.bucket.to_le_bytes().as_ref()],
+        bump = withdraw_queue.bump
+    )]
+    pub withdraw_queue: Account<'info, WithdrawQueue>,
+}
+
+/// Pay out the next withdrawal of a shuffled queue (crank, permissionless)
+#[derive(Accounts)]
+pub struct ReleaseQueuedWithdraw<'info> {
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
     )]
     pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"withdraw_queue", withdraw_queue.bucket.to_le_bytes().as_ref()],
+        bump = withdraw_queue.bump
+    )]
+    pub withdraw_queue: Account<'info, WithdrawQueue>,
+
+    #[account(
+        mut,
+        seeds = [b"pending", pending_withdraw.load()?.recipient.as_ref()],
+        bump = pending_withdraw.load()?.bump,
+        constraint = pending_withdraw.load()?.shard == pool_shard.index @ ErrorCode::WrongPoolShard,
+        constraint = withdraw_queue.next_entry() == Some(pending_withdraw.key()) @ ErrorCode::QueueEntryMismatch
+    )]
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
+
+    /// The stealth address recorded in the pending withdrawal
+    #[account(
+        mut,
+        address = pending_withdraw.load()?.recipient @ ErrorCode::Unauthorized
+    )]
+    pub recipient: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
+/// Read the status of a recipient's pending withdrawal (view)
 #[derive(Accounts)]
-pub struct BatchClaimWithdraw<'info> {
+#[instruction(recipient: Pubkey)]
+pub struct GetPendingStatus<'info> {
+    /// CHECK: May not exist; the handler checks owner and discriminator
+    #[account(
+        seeds = [b"pending", recipient.as_ref()],
+        bump
+    )]
+    pub pending_withdraw: UncheckedAccount<'info>,
+}
+
+/// Read pool statistics (view)
+///
+/// Remaining accounts are every pool shard in index order.
+#[derive(Accounts)]
+pub struct GetPoolStats<'info> {
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+}
+
+/// Create the statistics snapshot for an epoch (permissionless)
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct InitEpochSnapshot<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = EpochSnapshot::SPACE,
+        seeds = [b"epoch_snapshot", epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub snapshot: Account<'info, EpochSnapshot>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Persist a finished epoch's statistics (permissionless crank)
+///
+/// Remaining accounts are every pool shard in index order.
+#[derive(Accounts)]
+pub struct FinalizeEpochSnapshot<'info> {
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
     #[account(
         mut,
+        seeds = [b"epoch_snapshot", snapshot.epoch.to_le_bytes().as_ref()],
+        bump = snapshot.bump
+    )]
+    pub snapshot: Account<'info, EpochSnapshot>,
+}
+
+/// Reconcile pool accounting against vault balances (view)
+///
+/// Remaining accounts are every vault shard in index order, then every pool
+/// shard in index order, then every churn vault in index order (0, 1, 2);
+/// nothing else is accepted.
+#[derive(Accounts)]
+pub struct ReconcilePool<'info> {
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+}
+
+/// Batch claim of ready withdrawals
+///
+/// Remaining accounts are (recipient, pending_withdraw) pairs, up to
+/// MAX_BATCH_CLAIMS pairs. Relayers are expected to reference them through
+/// an address lookup table so a full batch fits in one v0 transaction.
+#[derive(Accounts)]
+pub struct BatchClaimWithdraw<'info> {
+    #[account(
         seeds = [b"privacy_pool"],
         bump = pool.bump
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA
     #[account(
         mut,
-        seeds = [b"pool_vault"],
-        bump = pool.vault_bump
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
     )]
     pub pool_vault: SystemAccount<'info>,
 
@@ -195,17 +686,22 @@ pub struct PoolChurn<'info> {
     pub authority: Signer<'info>,
 
     #[account(
-        mut,
         seeds = [b"privacy_pool"],
         bump = pool.bump
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA
     #[account(
         mut,
-        seeds = [b"pool_vault"],
-        bump = pool.vault_bump
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
     )]
     pub pool_vault: SystemAccount<'info>,
 
@@ -234,17 +730,22 @@ pub struct PoolUnchurn<'info> {
     pub authority: Signer<'info>,
 
     #[account(
-        mut,
         seeds = [b"privacy_pool"],
         bump = pool.bump
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA
     #[account(
         mut,
-        seeds = [b"pool_vault"],
-        bump = pool.vault_bump
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
     )]
     pub pool_vault: SystemAccount<'info>,
 
@@ -4,9 +4,11 @@
 //! - CreateReceipt: Employee creates a receipt after claiming salary
 //! - VerifyReceipt: Anyone can verify a receipt (public verification)
 //! - VerifyReceiptBlind: Blind verification (proves existence, not amount)
+//! - InitReceiptTree: Create the Merkle tree of receipt leaves
+//! - ProveReceiptMembership: Prove a receipt from an employer in a time range
 
 use anchor_lang::prelude::*;
-use crate::state::{PayrollBatch, Employee, PaymentReceipt};
+use crate::state::{PayrollBatch, Employee, PaymentReceipt, CommitmentTree};
 use crate::errors::ErrorCode;
 
 /// Create an anonymous receipt after claiming salary
@@ -18,20 +20,21 @@ use crate::errors::ErrorCode;
 /// - Prove employment without revealing salary
 /// - Prove payment was received at a specific time
 /// - Optionally reveal secret for full amount verification
+/// - Prove membership in the receipt tree without naming the receipt
 #[derive(Accounts)]
 pub struct CreateReceipt<'info> {
     #[account(mut)]
     pub employee_signer: Signer<'info>,
 
     #[account(
-        constraint = employee.wallet == employee_signer.key() @ ErrorCode::Unauthorized
+        constraint = employee.load()?.wallet == employee_signer.key() @ ErrorCode::Unauthorized
     )]
-    pub employee: Account<'info, Employee>,
+    pub employee: AccountLoader<'info, Employee>,
 
     #[account(
-        constraint = batch.key() == employee.batch @ ErrorCode::Unauthorized
+        constraint = batch.key() == employee.load()?.batch @ ErrorCode::Unauthorized
     )]
-    pub batch: Account<'info, PayrollBatch>,
+    pub batch: AccountLoader<'info, PayrollBatch>,
 
     #[account(
         init,
@@ -39,14 +42,21 @@ pub struct CreateReceipt<'info> {
         space = PaymentReceipt::SPACE,
         seeds = [
             b"receipt",
-            employee.wallet.as_ref(),
+            employee.load()?.wallet.as_ref(),
             batch.key().as_ref(),
-            &employee.total_claimed.to_le_bytes()
+            &employee.load()?.total_claimed.to_le_bytes()
         ],
         bump
     )]
     pub receipt: Account<'info, PaymentReceipt>,
 
+    #[account(
+        mut,
+        seeds = [b"receipt_tree"],
+        bump = receipt_tree.load()?.bump
+    )]
+    pub receipt_tree: AccountLoader<'info, CommitmentTree>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -77,3 +87,39 @@ pub struct VerifyReceiptBlind<'info> {
     /// The receipt to verify (blind)
     pub receipt: Account<'info, PaymentReceipt>,
 }
+
+/// Create the receipt membership tree
+///
+/// Uses the commitment tree layout with a class no note can have, so the
+/// two kinds of leaves can never be mixed.
+#[derive(Accounts)]
+pub struct InitReceiptTree<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = CommitmentTree::SPACE,
+        seeds = [b"receipt_tree"],
+        bump
+    )]
+    pub receipt_tree: AccountLoader<'info, CommitmentTree>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Prove "I hold a receipt from this employer in this time range"
+///
+/// The holder signs with the membership key chosen when the receipt was
+/// created; no receipt account is referenced.
+#[derive(Accounts)]
+pub struct ProveReceiptMembership<'info> {
+    pub holder: Signer<'info>,
+
+    #[account(
+        seeds = [b"receipt_tree"],
+        bump = receipt_tree.load()?.bump
+    )]
+    pub receipt_tree: AccountLoader<'info, CommitmentTree>,
+}
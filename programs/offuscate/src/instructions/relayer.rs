@@ -4,7 +4,10 @@
 
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
-use crate::state::{PrivacyPool, PendingWithdraw, CommitmentPDA, NullifierPDA};
+use crate::state::{
+    Campaign, PrivacyPool, PoolShard, PendingWithdraw, CommitmentTree, NullifierPDA, DepositEscrow, SpentBitmap, RelayNonce,
+    SessionKey, RelayerAllowlist, RelayerInfo, RentEscrow,
+};
 use crate::errors::ErrorCode;
 
 /// Claim withdrawal via relayer (gasless for recipient)
@@ -16,32 +19,234 @@ pub struct ClaimWithdrawRelayed<'info> {
     pub relayer: Signer<'info>,
 
     /// CHECK: The recipient (stealth address) - NOT a signer
-    /// Ownership is proven via ed25519 signature in previous instruction
+    /// Ownership is proven by its ed25519 signature over "claim:<pending_pda>:<nonce>",
+    /// or the same message signed by the secp256k1 key registered on its
+    /// relay nonce, in a verify instruction earlier in the transaction
     #[account(mut)]
     pub recipient: AccountInfo<'info>,
 
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pending", recipient.key().as_ref()],
+        bump = pending_withdraw.load()?.bump,
+        constraint = pending_withdraw.load()?.shard == pool_shard.index @ ErrorCode::WrongPoolShard,
+        constraint = pending_withdraw.load()?.recipient == recipient.key() @ ErrorCode::Unauthorized
+    )]
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
+
+    /// The recipient's relay nonce; the signed claim carries its current value
+    #[account(
+        mut,
+        seeds = [b"relay_nonce", recipient.key().as_ref()],
+        bump = relay_nonce.bump
+    )]
+    pub relay_nonce: Account<'info, RelayNonce>,
+
     #[account(
         mut,
+        seeds = [b"relayer_info", relayer.key().as_ref()],
+        bump = relayer_info.bump
+    )]
+    pub relayer_info: Account<'info, RelayerInfo>,
+
+    /// Required only while the pool's relayer allowlist mode is on
+    #[account(seeds = [b"relayer_allowlist"], bump = relayer_allowlist.bump)]
+    pub relayer_allowlist: Option<Account<'info, RelayerAllowlist>>,
+
+    /// CHECK: Instructions sysvar for ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Batch claim of relayed withdrawals, one ed25519 signature per recipient
+///
+/// Remaining accounts are (recipient, pending_withdraw, relay_nonce)
+/// triples, up to MAX_BATCH_CLAIMS. The recipients' claim signatures may
+/// all sit in one ed25519 verify instruction.
+#[derive(Accounts)]
+pub struct BatchClaimRelayed<'info> {
+    /// Relayer pays gas
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    #[account(
         seeds = [b"privacy_pool"],
         bump = pool.bump
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA
     #[account(
         mut,
-        seeds = [b"pool_vault"],
-        bump = pool.vault_bump
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
     )]
     pub pool_vault: SystemAccount<'info>,
 
     #[account(
         mut,
-        seeds = [b"pending", pending_withdraw.recipient.as_ref()],
-        bump = pending_withdraw.bump,
-        constraint = pending_withdraw.recipient == recipient.key() @ ErrorCode::Unauthorized
+        seeds = [b"relayer_info", relayer.key().as_ref()],
+        bump = relayer_info.bump
+    )]
+    pub relayer_info: Account<'info, RelayerInfo>,
+
+    /// Required only while the pool's relayer allowlist mode is on
+    #[account(seeds = [b"relayer_allowlist"], bump = relayer_allowlist.bump)]
+    pub relayer_allowlist: Option<Account<'info, RelayerAllowlist>>,
+
+    /// CHECK: Instructions sysvar for ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Register a relayer's activity account; relayers register themselves
+#[derive(Accounts)]
+pub struct RegisterRelayer<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = relayer,
+        space = RelayerInfo::SPACE,
+        seeds = [b"relayer_info", relayer.key().as_ref()],
+        bump
+    )]
+    pub relayer_info: Account<'info, RelayerInfo>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the relayer allowlist (pool authority only)
+#[derive(Accounts)]
+pub struct InitRelayerAllowlist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = RelayerAllowlist::SPACE,
+        seeds = [b"relayer_allowlist"],
+        bump
+    )]
+    pub relayer_allowlist: Account<'info, RelayerAllowlist>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Edit the relayer allowlist (pool authority only)
+#[derive(Accounts)]
+pub struct UpdateRelayerAllowlist<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(mut, seeds = [b"relayer_allowlist"], bump = relayer_allowlist.bump)]
+    pub relayer_allowlist: Account<'info, RelayerAllowlist>,
+}
+
+/// Read the relayer fee a relayed withdrawal currently requires (view)
+#[derive(Accounts)]
+pub struct QuoteRelayFee<'info> {
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+}
+
+/// Claim withdrawal via relayer, authorized by a session key of the recipient
+#[derive(Accounts)]
+pub struct ClaimWithdrawRelayedSession<'info> {
+    pub claim: ClaimWithdrawRelayed<'info>,
+
+    #[account(
+        seeds = [b"session_key", claim.recipient.key().as_ref(), session.session_key.as_ref()],
+        bump = session.bump,
+        constraint = session.owner == claim.recipient.key() @ ErrorCode::Unauthorized
+    )]
+    pub session: Account<'info, SessionKey>,
+}
+
+/// Register a session key, authorized by the owner's ed25519 signature
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct RegisterSessionKey<'info> {
+    /// Relayer pays gas and the session rent
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: The owner (stealth address) - NOT a signer; proven by its ed25519 signature
+    pub owner: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"relay_nonce", owner.key().as_ref()],
+        bump = relay_nonce.bump
+    )]
+    pub relay_nonce: Account<'info, RelayNonce>,
+
+    #[account(
+        init,
+        payer = relayer,
+        space = SessionKey::SPACE,
+        seeds = [b"session_key", owner.key().as_ref(), session_key.as_ref()],
+        bump
     )]
-    pub pending_withdraw: Account<'info, PendingWithdraw>,
+    pub session: Account<'info, SessionKey>,
+
+    /// Required only while the pool's relayer allowlist mode is on
+    #[account(seeds = [b"relayer_allowlist"], bump = relayer_allowlist.bump)]
+    pub relayer_allowlist: Option<Account<'info, RelayerAllowlist>>,
 
     /// CHECK: Instructions sysvar for ed25519 verification
     #[account(address = instructions_sysvar::ID)]
@@ -50,9 +255,65 @@ pub struct ClaimWithdrawRelayed<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Close a session key, by its owner at any time or by anyone once expired
+/// (rent goes to its payer)
+#[derive(Accounts)]
+pub struct CloseSessionKey<'info> {
+    /// The session owner, when revoking the key before it expires
+    pub owner: Option<Signer<'info>>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"session_key", session.owner.as_ref(), session.session_key.as_ref()],
+        bump = session.bump
+    )]
+    pub session: Account<'info, SessionKey>,
+
+    /// CHECK: Original rent payer, checked against the session
+    #[account(mut, address = session.rent_payer @ ErrorCode::Unauthorized)]
+    pub rent_payer: AccountInfo<'info>,
+}
+
+/// Update the relay nonce settings of a key (the key itself signs)
+#[derive(Accounts)]
+pub struct SetRelaySigner<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"relay_nonce", signer.key().as_ref()],
+        bump = relay_nonce.bump
+    )]
+    pub relay_nonce: Account<'info, RelayNonce>,
+}
+
+/// Create the relay nonce account of a signing key
+#[derive(Accounts)]
+#[instruction(signer: Pubkey)]
+pub struct InitRelayNonce<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = RelayNonce::SPACE,
+        seeds = [b"relay_nonce", signer.as_ref()],
+        bump
+    )]
+    pub relay_nonce: Account<'info, RelayNonce>,
+
+    pub system_program: Program<'info, System>,
+}
+
 /// Private withdrawal via relayer
+///
+/// Like `PrivateWithdraw`, only the tree, the new nullifier PDA and the vault
+/// are referenced; no deposit-side account is part of the spend. The
+/// recipient is fixed by the note owner's signature, not by the relayer.
 #[derive(Accounts)]
-#[instruction(nullifier: [u8; 32], secret_hash: [u8; 32], amount: u64)]
+#[instruction(nullifier: [u8; 32])]
 pub struct PrivateWithdrawRelayed<'info> {
     /// Relayer pays gas
     #[account(mut)]
@@ -63,27 +324,41 @@ pub struct PrivateWithdrawRelayed<'info> {
     pub recipient: AccountInfo<'info>,
 
     #[account(
-        mut,
         seeds = [b"privacy_pool"],
         bump = pool.bump
     )]
     pub pool: Account<'info, PrivacyPool>,
 
-    /// CHECK: Pool vault PDA
     #[account(
         mut,
-        seeds = [b"pool_vault"],
-        bump = pool.vault_bump
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
     )]
     pub pool_vault: SystemAccount<'info>,
 
-    /// Commitment PDA
+    #[account(
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
     #[account(
         mut,
-        seeds = [b"commitment", commitment_pda.commitment.as_ref()],
-        bump = commitment_pda.bump
+        seeds = [
+            b"spent_bitmap",
+            spent_bitmap.load()?.class.to_le_bytes().as_ref(),
+            spent_bitmap.load()?.chunk.to_le_bytes().as_ref(),
+        ],
+        bump = spent_bitmap.load()?.bump
     )]
-    pub commitment_pda: Account<'info, CommitmentPDA>,
+    pub spent_bitmap: AccountLoader<'info, SpentBitmap>,
 
     /// Nullifier PDA - created to mark this nullifier as used
     #[account(
@@ -95,6 +370,331 @@ pub struct PrivateWithdrawRelayed<'info> {
     )]
     pub nullifier_pda: Account<'info, NullifierPDA>,
 
+    /// Reimburses the nullifier PDA's rent to the relayer, if the note owner funds one
+    #[account(
+        mut,
+        seeds = [b"rent_escrow", rent_escrow.authority.as_ref()],
+        bump = rent_escrow.bump
+    )]
+    pub rent_escrow: Option<Account<'info, RentEscrow>>,
+
+    #[account(
+        mut,
+        seeds = [b"relayer_info", relayer.key().as_ref()],
+        bump = relayer_info.bump
+    )]
+    pub relayer_info: Account<'info, RelayerInfo>,
+
+    /// Required only while the pool's relayer allowlist mode is on
+    #[account(seeds = [b"relayer_allowlist"], bump = relayer_allowlist.bump)]
+    pub relayer_allowlist: Option<Account<'info, RelayerAllowlist>>,
+
+    /// CHECK: Instructions sysvar for ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Open an escrow for the rent of relayer-created accounts
+#[derive(Accounts)]
+#[instruction(escrow_authority: Pubkey)]
+pub struct OpenRentEscrow<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = RentEscrow::SPACE,
+        seeds = [b"rent_escrow", escrow_authority.as_ref()],
+        bump
+    )]
+    pub rent_escrow: Account<'info, RentEscrow>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Close an idle rent escrow, refunding its balance to the owner
+#[derive(Accounts)]
+pub struct CloseRentEscrow<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_escrow", rent_escrow.authority.as_ref()],
+        bump = rent_escrow.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub rent_escrow: Account<'info, RentEscrow>,
+}
+
+/// Open a pending withdrawal via relayer, with rent drawn from a rent escrow
+///
+/// The recipient and the escrow authority both sign off-chain; nothing
+/// the user controls signs or pays for the transaction.
+#[derive(Accounts)]
+pub struct RequestWithdrawRelayed<'info> {
+    /// Relayer pays gas and fronts the rent
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// CHECK: The recipient (stealth address); authorized by its ed25519 signature
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    /// Shard the withdrawal is reserved on and paid from
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    #[account(
+        init,
+        payer = relayer,
+        space = PendingWithdraw::SPACE,
+        seeds = [b"pending", recipient.key().as_ref()],
+        bump
+    )]
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
+
+    /// The recipient's relay nonce; the signed request carries its current value
+    #[account(
+        mut,
+        seeds = [b"relay_nonce", recipient.key().as_ref()],
+        bump = relay_nonce.bump
+    )]
+    pub relay_nonce: Account<'info, RelayNonce>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_escrow", rent_escrow.authority.as_ref()],
+        bump = rent_escrow.bump
+    )]
+    pub rent_escrow: Account<'info, RentEscrow>,
+
+    #[account(
+        mut,
+        seeds = [b"relayer_info", relayer.key().as_ref()],
+        bump = relayer_info.bump
+    )]
+    pub relayer_info: Account<'info, RelayerInfo>,
+
+    /// Required only while the pool's relayer allowlist mode is on
+    #[account(seeds = [b"relayer_allowlist"], bump = relayer_allowlist.bump)]
+    pub relayer_allowlist: Option<Account<'info, RelayerAllowlist>>,
+
+    /// CHECK: Instructions sysvar for ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Open an escrow for relayed private deposits
+#[derive(Accounts)]
+#[instruction(escrow_authority: Pubkey)]
+pub struct OpenDepositEscrow<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = DepositEscrow::SPACE,
+        seeds = [b"deposit_escrow", escrow_authority.as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, DepositEscrow>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Close an idle deposit escrow, refunding its balance to the owner
+#[derive(Accounts)]
+pub struct CloseDepositEscrow<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"deposit_escrow", escrow.authority.as_ref()],
+        bump = escrow.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub escrow: Account<'info, DepositEscrow>,
+}
+
+/// Private deposit via relayer, funded from a deposit escrow
+///
+/// The escrow authority signs the deposit message off-chain; the ed25519
+/// verify instruction may sit anywhere before this one in the transaction.
+#[derive(Accounts)]
+pub struct PrivateDepositRelayed<'info> {
+    /// Relayer pays gas
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"deposit_escrow", escrow.authority.as_ref()],
+        bump = escrow.bump
+    )]
+    pub escrow: Account<'info, DepositEscrow>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"commitment_tree", commitment_tree.load()?.class.to_le_bytes().as_ref()],
+        bump = commitment_tree.load()?.bump
+    )]
+    pub commitment_tree: AccountLoader<'info, CommitmentTree>,
+
+    #[account(
+        mut,
+        seeds = [b"relayer_info", relayer.key().as_ref()],
+        bump = relayer_info.bump
+    )]
+    pub relayer_info: Account<'info, RelayerInfo>,
+
+    /// Required only while the pool's relayer allowlist mode is on
+    #[account(seeds = [b"relayer_allowlist"], bump = relayer_allowlist.bump)]
+    pub relayer_allowlist: Option<Account<'info, RelayerAllowlist>>,
+
+    /// CHECK: Instructions sysvar for ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+/// Campaign donation via relayer, funded from the donor's deposit escrow
+///
+/// The escrow authority (typically a throwaway key holding no SOL) signs
+/// the donate message; the ed25519 verify instruction may sit anywhere
+/// before this one in the transaction.
+#[derive(Accounts)]
+pub struct DonateRelayed<'info> {
+    /// Relayer pays gas
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"deposit_escrow", escrow.authority.as_ref()],
+        bump = escrow.bump
+    )]
+    pub escrow: Account<'info, DepositEscrow>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.campaign_id.as_bytes()],
+        bump = campaign.campaign_bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    /// CHECK: Vault PDA
+    #[account(
+        mut,
+        seeds = [b"vault", campaign.campaign_id.as_bytes()],
+        bump = campaign.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"relayer_info", relayer.key().as_ref()],
+        bump = relayer_info.bump
+    )]
+    pub relayer_info: Account<'info, RelayerInfo>,
+
+    /// Required only while the pool's relayer allowlist mode is on
+    #[account(seeds = [b"relayer_allowlist"], bump = relayer_allowlist.bump)]
+    pub relayer_allowlist: Option<Account<'info, RelayerAllowlist>>,
+
+    /// CHECK: Instructions sysvar for ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+/// Campaign withdrawal via relayer, authorized by the owner's signature
+///
+/// The owner (e.g. a stealth key) never signs a transaction or pays gas.
+#[derive(Accounts)]
+pub struct WithdrawRelayed<'info> {
+    /// Relayer pays gas and earns the fee
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.campaign_id.as_bytes()],
+        bump = campaign.campaign_bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    /// CHECK: Vault PDA
+    #[account(
+        mut,
+        seeds = [b"vault", campaign.campaign_id.as_bytes()],
+        bump = campaign.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Receives the withdrawal; fixed by the owner's signed message
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"relayer_info", relayer.key().as_ref()],
+        bump = relayer_info.bump
+    )]
+    pub relayer_info: Account<'info, RelayerInfo>,
+
+    /// Required only while the pool's relayer allowlist mode is on
+    #[account(seeds = [b"relayer_allowlist"], bump = relayer_allowlist.bump)]
+    pub relayer_allowlist: Option<Account<'info, RelayerAllowlist>>,
+
     /// CHECK: Instructions sysvar for ed25519 verification
     #[account(address = instructions_sysvar::ID)]
     pub instructions_sysvar: AccountInfo<'info>,
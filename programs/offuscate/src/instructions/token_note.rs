@@ -0,0 +1,148 @@
+//! Shielded SPL Token Note Account Contexts
+//!
+//! Commitment notes over SPL tokens, one tree and pool token account per mint:
+//! - InitTokenPool: Create a mint's note tree and pool token account
+//! - TokenDeposit: Move tokens into the pool behind a commitment
+//! - TokenWithdraw: Spend a token note to a recipient token account
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::state::{PrivacyPool, CommitmentTree, NullifierPDA, SpendIntent};
+
+/// Create the note tree and pool token account for `mint`
+#[derive(Accounts)]
+pub struct InitTokenPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = CommitmentTree::SPACE,
+        seeds = [b"token_tree", mint.key().as_ref()],
+        bump
+    )]
+    pub token_tree: AccountLoader<'info, CommitmentTree>,
+
+    /// Pool token account holding every shielded note of this mint
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = pool,
+        seeds = [b"token_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit tokens behind a note commitment
+///
+/// The amount is public, as for the pool's lamport deposits; common
+/// amounts (payroll runs, round donations) are what hide a deposit.
+#[derive(Accounts)]
+pub struct TokenDeposit<'info> {
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = depositor,
+    )]
+    pub depositor_token: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"token_tree", mint.key().as_ref()],
+        bump = token_tree.load()?.bump
+    )]
+    pub token_tree: AccountLoader<'info, CommitmentTree>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Spend a token note, paying out to a token account of the recipient
+///
+/// Token trees have no spent bitmap, so their nullifier PDAs live under
+/// their own seeds, which `CloseNullifier` does not accept: they stay open
+/// for good and are the permanent double-spend record.
+#[derive(Accounts)]
+#[instruction(nullifier: [u8; 32])]
+pub struct TokenWithdraw<'info> {
+    /// Payer for the transaction (can be anyone)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: The recipient (any address, typically stealth); only owns `recipient_token`
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = recipient,
+    )]
+    pub recipient_token: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        seeds = [b"token_tree", mint.key().as_ref()],
+        bump = token_tree.load()?.bump
+    )]
+    pub token_tree: AccountLoader<'info, CommitmentTree>,
+
+    #[account(
+        mut,
+        seeds = [b"token_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = NullifierPDA::SPACE,
+        seeds = [b"token_nullifier", nullifier.as_ref()],
+        bump
+    )]
+    pub nullifier_pda: Account<'info, NullifierPDA>,
+
+    /// Intent committing to `recipient_token`; closed on use
+    #[account(mut, close = payer)]
+    pub spend_intent: Account<'info, SpendIntent>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
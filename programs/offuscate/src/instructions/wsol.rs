@@ -0,0 +1,180 @@
+//! Wrapped SOL Account Contexts
+//!
+//! Pool deposits and claims for integrators holding wSOL token accounts:
+//! - PoolDepositWsol: Unwrap wSOL into the pool vault
+//! - ClaimWithdrawWsol: Claim a pending withdrawal into a wSOL token account
+//! - ClaimWithdrawSwap: Claim as wSOL and swap it into another token
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::token_interface::TokenAccount as InterfaceTokenAccount;
+use crate::state::{PrivacyPool, PoolShard, PendingWithdraw};
+use crate::errors::ErrorCode;
+
+/// Deposit wSOL into the privacy pool
+///
+/// The wSOL is moved into a temporary pool-owned token account which is then
+/// closed, unwrapping it back to lamports that flow into the pool vault.
+#[derive(Accounts)]
+pub struct PoolDepositWsol<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = native_mint,
+        token::authority = depositor,
+    )]
+    pub depositor_token: Account<'info, TokenAccount>,
+
+    /// Temporary unwrap account - created and closed within the instruction
+    #[account(
+        init,
+        payer = depositor,
+        token::mint = native_mint,
+        token::authority = pool,
+        seeds = [b"wsol_unwrap", depositor.key().as_ref()],
+        bump
+    )]
+    pub unwrap_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(address = token::spl_token::native_mint::ID)]
+    pub native_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim a pending withdrawal into a wSOL token account owned by the recipient
+#[derive(Accounts)]
+pub struct ClaimWithdrawWsol<'info> {
+    pub recipient: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = native_mint,
+        token::authority = recipient,
+    )]
+    pub recipient_token: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pending", recipient.key().as_ref()],
+        bump = pending_withdraw.load()?.bump,
+        constraint = pending_withdraw.load()?.shard == pool_shard.index @ ErrorCode::WrongPoolShard,
+        constraint = pending_withdraw.load()?.recipient == recipient.key() @ ErrorCode::Unauthorized
+    )]
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
+
+    #[account(address = token::spl_token::native_mint::ID)]
+    pub native_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim a pending withdrawal as wSOL and swap it through a whitelisted program
+///
+/// Remaining accounts are forwarded verbatim to the swap instruction. The
+/// recipient signs the swap itself; pool PDAs never sign it.
+#[derive(Accounts)]
+pub struct ClaimWithdrawSwap<'info> {
+    pub recipient: Signer<'info>,
+
+    /// Intermediate wSOL account the claimed lamports are wrapped into
+    #[account(
+        mut,
+        token::mint = native_mint,
+        token::authority = recipient,
+    )]
+    pub recipient_token: Account<'info, TokenAccount>,
+
+    /// Account receiving the swap output (SPL Token or Token-2022)
+    #[account(
+        mut,
+        constraint = output_token.owner == recipient.key() @ ErrorCode::Unauthorized
+    )]
+    pub output_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        seeds = [b"privacy_pool"],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PrivacyPool>,
+
+    #[account(
+        mut,
+        seeds = [b"pool_shard", pool_shard.index.to_le_bytes().as_ref()],
+        bump = pool_shard.bump
+    )]
+    pub pool_shard: Account<'info, PoolShard>,
+
+    /// CHECK: Vault of `pool_shard` - any shard, ideally the least contended
+    #[account(
+        mut,
+        address = pool.shard_vault(pool_shard.index) @ ErrorCode::InvalidVaultShard
+    )]
+    pub pool_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pending", recipient.key().as_ref()],
+        bump = pending_withdraw.load()?.bump,
+        constraint = pending_withdraw.load()?.shard == pool_shard.index @ ErrorCode::WrongPoolShard,
+        constraint = pending_withdraw.load()?.recipient == recipient.key() @ ErrorCode::Unauthorized
+    )]
+    pub pending_withdraw: AccountLoader<'info, PendingWithdraw>,
+
+    /// CHECK: Checked against the pool's swap whitelist
+    #[account(
+        executable,
+        constraint = pool.is_swap_program(&swap_program.key()) @ ErrorCode::SwapProgramNotAllowed
+    )]
+    pub swap_program: UncheckedAccount<'info>,
+
+    #[account(address = token::spl_token::native_mint::ID)]
+    pub native_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
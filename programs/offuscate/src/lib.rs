@@ -7,12 +7,14 @@ use anchor_lang::prelude::*;
 // The codebase is organized into modules:
 // - constants: Program constants (delay times, allowed amounts)
 // - errors: All error codes
+// - events: Anchor events for indexers and relayers
 // - state: Account state structures (PrivacyPool, Campaign, etc.)
 // - instructions: Account contexts for each instruction
 // - handlers: Business logic for each instruction
 
 pub mod constants;
 pub mod errors;
+pub mod events;
 pub mod state;
 pub mod instructions;
 pub mod handlers;
@@ -33,28 +35,153 @@ pub mod offuscate {
     // PRIVACY POOL
     // ==============================================
 
+    pub fn migrate_privacy_pool(ctx: Context<MigratePrivacyPool>) -> Result<()> {
+        handlers::privacy_pool::migrate_pool(ctx)
+    }
+
     pub fn init_privacy_pool(ctx: Context<InitPrivacyPool>) -> Result<()> {
         handlers::privacy_pool::init(ctx)
     }
 
-    pub fn pool_deposit(ctx: Context<PoolDeposit>, amount: u64) -> Result<()> {
-        handlers::privacy_pool::deposit(ctx, amount)
+    pub fn init_pool_shard(ctx: Context<InitPoolShard>, index: u8) -> Result<()> {
+        handlers::privacy_pool::init_pool_shard(ctx, index)
+    }
+
+    pub fn drain_legacy_vault(ctx: Context<DrainLegacyVault>) -> Result<()> {
+        handlers::privacy_pool::drain_legacy_vault(ctx)
+    }
+
+    pub fn transfer_pool_authority(ctx: Context<UpdatePoolConfig>, new_authority: Pubkey) -> Result<()> {
+        handlers::privacy_pool::transfer_authority(ctx, new_authority)
+    }
+
+    pub fn accept_pool_authority(ctx: Context<AcceptPoolAuthority>) -> Result<()> {
+        handlers::privacy_pool::accept_authority(ctx)
+    }
+
+    pub fn set_pool_outflow_limit(ctx: Context<UpdatePoolConfig>, max_epoch_outflow: u64) -> Result<()> {
+        handlers::privacy_pool::set_outflow_limit(ctx, max_epoch_outflow)
+    }
+
+    pub fn set_standard_deposits(ctx: Context<UpdatePoolConfig>, enabled: bool) -> Result<()> {
+        handlers::privacy_pool::set_standard_deposits(ctx, enabled)
+    }
+
+    pub fn set_pool_paused(ctx: Context<UpdatePoolConfig>, paused: bool) -> Result<()> {
+        handlers::privacy_pool::set_paused(ctx, paused)
+    }
+
+    pub fn set_rescue_delay(ctx: Context<UpdatePoolConfig>, rescue_delay_epochs: u64) -> Result<()> {
+        handlers::privacy_pool::set_rescue_delay(ctx, rescue_delay_epochs)
+    }
+
+    pub fn set_max_relay_fee(ctx: Context<UpdatePoolConfig>, max_relay_fee_bps: u16) -> Result<()> {
+        handlers::privacy_pool::set_max_relay_fee(ctx, max_relay_fee_bps)
+    }
+
+    pub fn set_relay_fee_schedule(ctx: Context<UpdatePoolConfig>, base_fee: u64, congestion_bps: u32) -> Result<()> {
+        handlers::privacy_pool::set_relay_fee_schedule(ctx, base_fee, congestion_bps)
+    }
+
+    pub fn set_relayer_allowlist_mode(ctx: Context<UpdatePoolConfig>, enabled: bool) -> Result<()> {
+        handlers::privacy_pool::set_relayer_allowlist_mode(ctx, enabled)
+    }
+
+    pub fn set_relayer_rate_limit(ctx: Context<UpdatePoolConfig>, max_claims_per_epoch: u32) -> Result<()> {
+        handlers::privacy_pool::set_relayer_rate_limit(ctx, max_claims_per_epoch)
+    }
+
+    pub fn sweep_dust(ctx: Context<SweepDust>) -> Result<()> {
+        handlers::privacy_pool::sweep_dust(ctx)
+    }
+
+    pub fn pool_deposit(ctx: Context<PoolDeposit>, amount: u64, memo: Option<Vec<u8>>) -> Result<()> {
+        handlers::privacy_pool::deposit(ctx, amount, memo)
     }
 
     pub fn request_withdraw(ctx: Context<RequestWithdraw>, amount: u64) -> Result<()> {
         handlers::privacy_pool::request_withdraw(ctx, amount)
     }
 
-    pub fn claim_withdraw(ctx: Context<ClaimWithdraw>) -> Result<()> {
-        handlers::privacy_pool::claim_withdraw(ctx)
+    pub fn claim_withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimWithdraw<'info>>,
+        split_amounts: Vec<u64>,
+    ) -> Result<()> {
+        handlers::privacy_pool::claim_withdraw(ctx, split_amounts)
+    }
+
+    pub fn reopen_withdraw(ctx: Context<ReopenWithdraw>) -> Result<()> {
+        handlers::privacy_pool::reopen_withdraw(ctx)
+    }
+
+    pub fn offer_claim_tip(ctx: Context<OfferClaimTip>, tip: u64) -> Result<()> {
+        handlers::privacy_pool::offer_claim_tip(ctx, tip)
+    }
+
+    pub fn execute_tipped_claim(ctx: Context<ExecuteTippedClaim>) -> Result<()> {
+        handlers::privacy_pool::execute_tipped_claim(ctx)
+    }
+
+    pub fn expire_pending_withdraw(ctx: Context<ExpirePendingWithdraw>) -> Result<()> {
+        handlers::privacy_pool::expire_pending_withdraw(ctx)
+    }
+
+    pub fn close_pending_withdraw(ctx: Context<ClosePendingWithdraw>) -> Result<()> {
+        handlers::privacy_pool::close_pending_withdraw(ctx)
+    }
+
+    pub fn migrate_pending_withdraw(ctx: Context<MigratePendingWithdraw>) -> Result<()> {
+        handlers::privacy_pool::migrate_pending_withdraw(ctx)
+    }
+
+    pub fn init_withdraw_queue(ctx: Context<InitWithdrawQueue>, bucket: i64) -> Result<()> {
+        handlers::privacy_pool::init_withdraw_queue(ctx, bucket)
+    }
+
+    pub fn request_withdraw_queued(ctx: Context<RequestWithdrawQueued>, amount: u64) -> Result<()> {
+        handlers::privacy_pool::request_withdraw_queued(ctx, amount)
+    }
+
+    pub fn arm_withdraw_queue_shuffle(ctx: Context<ShuffleWithdrawQueue>) -> Result<()> {
+        handlers::privacy_pool::arm_withdraw_queue_shuffle(ctx)
+    }
+
+    pub fn shuffle_withdraw_queue(ctx: Context<ShuffleWithdrawQueue>) -> Result<()> {
+        handlers::privacy_pool::shuffle_withdraw_queue(ctx)
+    }
+
+    pub fn release_queued_withdraw(ctx: Context<ReleaseQueuedWithdraw>) -> Result<()> {
+        handlers::privacy_pool::release_queued_withdraw(ctx)
     }
 
-    pub fn get_pool_stats(ctx: Context<GetPoolStats>) -> Result<()> {
+    pub fn get_pending_status(ctx: Context<GetPendingStatus>, recipient: Pubkey) -> Result<PendingStatus> {
+        handlers::privacy_pool::get_pending_status(ctx, recipient)
+    }
+
+    pub fn get_pool_stats<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetPoolStats<'info>>,
+    ) -> Result<()> {
         handlers::privacy_pool::get_stats(ctx)
     }
 
-    pub fn batch_claim_withdraw<'a, 'b, 'c, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, BatchClaimWithdraw<'info>>,
+    pub fn reconcile_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReconcilePool<'info>>,
+    ) -> Result<()> {
+        handlers::privacy_pool::reconcile(ctx)
+    }
+
+    pub fn init_epoch_snapshot(ctx: Context<InitEpochSnapshot>, epoch: u64) -> Result<()> {
+        handlers::privacy_pool::init_epoch_snapshot(ctx, epoch)
+    }
+
+    pub fn finalize_epoch_snapshot<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeEpochSnapshot<'info>>,
+    ) -> Result<()> {
+        handlers::privacy_pool::finalize_epoch_snapshot(ctx)
+    }
+
+    pub fn batch_claim_withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchClaimWithdraw<'info>>,
     ) -> Result<()> {
         handlers::privacy_pool::batch_claim(ctx)
     }
@@ -71,21 +198,192 @@ pub mod offuscate {
         handlers::privacy_pool::unchurn(ctx, amount)
     }
 
+    // ==============================================
+    // WRAPPED SOL
+    // ==============================================
+
+    pub fn pool_deposit_wsol(ctx: Context<PoolDepositWsol>, amount: u64) -> Result<()> {
+        handlers::wsol::deposit(ctx, amount)
+    }
+
+    pub fn claim_withdraw_wsol(ctx: Context<ClaimWithdrawWsol>) -> Result<()> {
+        handlers::wsol::claim_withdraw(ctx)
+    }
+
+    pub fn claim_withdraw_swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimWithdrawSwap<'info>>,
+        swap_data: Vec<u8>,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        handlers::wsol::claim_withdraw_swap(ctx, swap_data, min_amount_out)
+    }
+
+    pub fn set_swap_program(ctx: Context<UpdatePoolConfig>, index: u8, program_id: Pubkey) -> Result<()> {
+        handlers::wsol::set_swap_program(ctx, index, program_id)
+    }
+
+    // ==============================================
+    // CONFIDENTIAL POOL (TOKEN-2022)
+    // ==============================================
+
+    pub fn init_confidential_pool(
+        ctx: Context<InitConfidentialPool>,
+        decryptable_zero_balance: [u8; 36],
+    ) -> Result<()> {
+        handlers::confidential_pool::init(ctx, decryptable_zero_balance)
+    }
+
+    pub fn confidential_pool_apply_pending(
+        ctx: Context<ConfidentialPoolApplyPending>,
+        expected_pending_balance_credit_counter: u64,
+        new_decryptable_available_balance: [u8; 36],
+    ) -> Result<()> {
+        handlers::confidential_pool::apply_pending(
+            ctx,
+            expected_pending_balance_credit_counter,
+            new_decryptable_available_balance,
+        )
+    }
+
+    pub fn request_confidential_withdraw(ctx: Context<RequestConfidentialWithdraw>) -> Result<()> {
+        handlers::confidential_pool::request_withdraw(ctx)
+    }
+
+    pub fn confidential_pool_withdraw(
+        ctx: Context<ConfidentialPoolWithdraw>,
+        new_source_decryptable_available_balance: [u8; 36],
+    ) -> Result<()> {
+        handlers::confidential_pool::withdraw(ctx, new_source_decryptable_available_balance)
+    }
+
     // ==============================================
     // RELAYER / GASLESS
     // ==============================================
 
-    pub fn claim_withdraw_relayed(ctx: Context<ClaimWithdrawRelayed>) -> Result<()> {
-        handlers::relayer::claim_withdraw_relayed(ctx)
+    pub fn claim_withdraw_relayed(ctx: Context<ClaimWithdrawRelayed>, valid_until: i64) -> Result<()> {
+        handlers::relayer::claim_withdraw_relayed(ctx, valid_until)
+    }
+
+    pub fn batch_claim_relayed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchClaimRelayed<'info>>,
+        valid_until: Vec<i64>,
+    ) -> Result<()> {
+        handlers::relayer::batch_claim_relayed(ctx, valid_until)
+    }
+
+    pub fn claim_withdraw_relayed_secp256k1(ctx: Context<ClaimWithdrawRelayed>, valid_until: i64) -> Result<()> {
+        handlers::relayer::claim_withdraw_relayed_secp256k1(ctx, valid_until)
+    }
+
+    pub fn claim_withdraw_relayed_durable(
+        ctx: Context<ClaimWithdrawRelayed>,
+        valid_until: i64,
+        durable_nonce: Pubkey,
+    ) -> Result<()> {
+        handlers::relayer::claim_withdraw_relayed_durable(ctx, valid_until, durable_nonce)
+    }
+
+    pub fn claim_withdraw_relayed_session(ctx: Context<ClaimWithdrawRelayedSession>, valid_until: i64) -> Result<()> {
+        handlers::relayer::claim_withdraw_relayed_session(ctx, valid_until)
+    }
+
+    pub fn register_session_key(
+        ctx: Context<RegisterSessionKey>,
+        session_key: Pubkey,
+        expires_at: i64,
+        allowed: u8,
+        valid_until: i64,
+    ) -> Result<()> {
+        handlers::relayer::register_session_key(ctx, session_key, expires_at, allowed, valid_until)
+    }
+
+    pub fn close_session_key(ctx: Context<CloseSessionKey>) -> Result<()> {
+        handlers::relayer::close_session_key(ctx)
+    }
+
+    pub fn set_relay_secp256k1_signer(ctx: Context<SetRelaySigner>, eth_address: [u8; 20]) -> Result<()> {
+        handlers::relayer::set_relay_secp256k1_signer(ctx, eth_address)
+    }
+
+    pub fn register_relayer(ctx: Context<RegisterRelayer>) -> Result<()> {
+        handlers::relayer::register_relayer(ctx)
+    }
+
+    pub fn init_relayer_allowlist(ctx: Context<InitRelayerAllowlist>) -> Result<()> {
+        handlers::relayer::init_relayer_allowlist(ctx)
+    }
+
+    pub fn set_allowed_relayer(ctx: Context<UpdateRelayerAllowlist>, index: u8, relayer: Pubkey) -> Result<()> {
+        handlers::relayer::set_allowed_relayer(ctx, index, relayer)
+    }
+
+    pub fn quote_relay_fee(ctx: Context<QuoteRelayFee>, amount: u64) -> Result<RelayFeeQuote> {
+        handlers::relayer::quote_relay_fee(ctx, amount)
+    }
+
+    pub fn init_relay_nonce(ctx: Context<InitRelayNonce>, signer: Pubkey) -> Result<()> {
+        handlers::relayer::init_relay_nonce(ctx, signer)
     }
 
     pub fn private_withdraw_relayed(
         ctx: Context<PrivateWithdrawRelayed>,
         nullifier: [u8; 32],
-        secret_hash: [u8; 32],
+        owner_salt: [u8; 32],
+        amount: u64,
+        fee: u64,
+        valid_until: i64,
+        proof: MerkleProof,
+    ) -> Result<()> {
+        handlers::relayer::private_withdraw_relayed(ctx, nullifier, owner_salt, amount, fee, valid_until, proof)
+    }
+
+    pub fn private_withdraw_secp256k1(
+        ctx: Context<PrivateWithdrawRelayed>,
+        nullifier: [u8; 32],
+        owner: Secp256k1NoteOwner,
+        amount: u64,
+        fee: u64,
+        valid_until: i64,
+        proof: MerkleProof,
+    ) -> Result<()> {
+        handlers::relayer::private_withdraw_secp256k1(ctx, nullifier, owner, amount, fee, valid_until, proof)
+    }
+
+    pub fn open_deposit_escrow(ctx: Context<OpenDepositEscrow>, escrow_authority: Pubkey) -> Result<()> {
+        handlers::relayer::open_deposit_escrow(ctx, escrow_authority)
+    }
+
+    pub fn close_deposit_escrow(ctx: Context<CloseDepositEscrow>) -> Result<()> {
+        handlers::relayer::close_deposit_escrow(ctx)
+    }
+
+    pub fn open_rent_escrow(ctx: Context<OpenRentEscrow>, escrow_authority: Pubkey) -> Result<()> {
+        handlers::relayer::open_rent_escrow(ctx, escrow_authority)
+    }
+
+    pub fn close_rent_escrow(ctx: Context<CloseRentEscrow>) -> Result<()> {
+        handlers::relayer::close_rent_escrow(ctx)
+    }
+
+    pub fn request_withdraw_relayed(ctx: Context<RequestWithdrawRelayed>, amount: u64, valid_until: i64) -> Result<()> {
+        handlers::relayer::request_withdraw_relayed(ctx, amount, valid_until)
+    }
+
+    pub fn private_deposit_relayed(
+        ctx: Context<PrivateDepositRelayed>,
+        commitment: [u8; 32],
         amount: u64,
+        valid_until: i64,
     ) -> Result<()> {
-        handlers::relayer::private_withdraw_relayed(ctx, nullifier, secret_hash, amount)
+        handlers::relayer::private_deposit_relayed(ctx, commitment, amount, valid_until)
+    }
+
+    pub fn donate_relayed(ctx: Context<DonateRelayed>, amount: u64, valid_until: i64) -> Result<()> {
+        handlers::relayer::donate_relayed(ctx, amount, valid_until)
+    }
+
+    pub fn withdraw_relayed(ctx: Context<WithdrawRelayed>, amount: u64, fee: u64, valid_until: i64) -> Result<()> {
+        handlers::relayer::withdraw_relayed(ctx, amount, fee, valid_until)
     }
 
     // ==============================================
@@ -103,6 +401,31 @@ pub mod offuscate {
         handlers::campaign::create(ctx, campaign_id, title, description, goal, deadline)
     }
 
+    pub fn create_stealth_campaign(
+        ctx: Context<CreateStealthCampaign>,
+        campaign_id: String,
+        title: String,
+        description: String,
+        goal: u64,
+        deadline: i64,
+        owner: Pubkey,
+    ) -> Result<()> {
+        handlers::campaign::create_stealth_owned(ctx, campaign_id, title, description, goal, deadline, owner)
+    }
+
+    pub fn update_campaign(
+        ctx: Context<UpdateCampaign>,
+        title: String,
+        description: String,
+        goal: u64,
+    ) -> Result<()> {
+        handlers::campaign::update(ctx, title, description, goal)
+    }
+
+    pub fn extend_deadline(ctx: Context<UpdateCampaign>, new_deadline: i64) -> Result<()> {
+        handlers::campaign::extend_deadline(ctx, new_deadline)
+    }
+
     pub fn donate(ctx: Context<Donate>, amount: u64) -> Result<()> {
         handlers::campaign::donate(ctx, amount)
     }
@@ -115,6 +438,26 @@ pub mod offuscate {
         handlers::campaign::close(ctx)
     }
 
+    pub fn finalize_campaign(ctx: Context<FinalizeCampaign>) -> Result<()> {
+        handlers::campaign::finalize(ctx)
+    }
+
+    pub fn close_campaign_account(ctx: Context<CloseCampaignAccount>) -> Result<()> {
+        handlers::campaign::close_account(ctx)
+    }
+
+    pub fn init_campaign_registry(ctx: Context<InitCampaignRegistry>) -> Result<()> {
+        handlers::campaign::init_registry(ctx)
+    }
+
+    pub fn init_campaign_registry_page(ctx: Context<InitCampaignRegistryPage>, page: u32) -> Result<()> {
+        handlers::campaign::init_registry_page(ctx, page)
+    }
+
+    pub fn register_campaign(ctx: Context<RegisterCampaign>) -> Result<()> {
+        handlers::campaign::register(ctx)
+    }
+
     // ==============================================
     // STEALTH ADDRESSES
     // ==============================================
@@ -170,6 +513,12 @@ pub mod offuscate {
         handlers::invite::accept_streaming(ctx, stealth_meta_address)
     }
 
+    pub fn crank_expire_invites<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CrankExpireInvites<'info>>,
+    ) -> Result<()> {
+        handlers::invite::crank_expire_invites(ctx)
+    }
+
     // ==============================================
     // STREAMING PAYROLL
     // ==============================================
@@ -178,6 +527,18 @@ pub mod offuscate {
         handlers::payroll::init_master_vault(ctx)
     }
 
+    pub fn transfer_payroll_authority(ctx: Context<UpdatePayrollConfig>, new_authority: Pubkey) -> Result<()> {
+        handlers::payroll::transfer_payroll_authority(ctx, new_authority)
+    }
+
+    pub fn accept_payroll_authority(ctx: Context<AcceptPayrollAuthority>) -> Result<()> {
+        handlers::payroll::accept_payroll_authority(ctx)
+    }
+
+    pub fn set_payroll_paused(ctx: Context<UpdatePayrollConfig>, paused: bool) -> Result<()> {
+        handlers::payroll::set_payroll_paused(ctx, paused)
+    }
+
     pub fn create_batch(ctx: Context<CreateBatch>, title: String) -> Result<()> {
         handlers::payroll::create_batch(ctx, title)
     }
@@ -194,24 +555,213 @@ pub mod offuscate {
         handlers::payroll::fund_batch(ctx, amount)
     }
 
-    pub fn claim_salary(ctx: Context<ClaimSalary>) -> Result<()> {
+    pub fn fund_treasury(ctx: Context<FundTreasury>, amount: u64) -> Result<()> {
+        handlers::payroll::fund_treasury(ctx, amount)
+    }
+
+    pub fn allocate_to_batch(ctx: Context<AllocateToBatch>, amount: u64) -> Result<()> {
+        handlers::payroll::allocate_to_batch(ctx, amount)
+    }
+
+    pub fn init_batch_token_vault(ctx: Context<InitBatchTokenVault>) -> Result<()> {
+        handlers::payroll::init_batch_token_vault(ctx)
+    }
+
+    pub fn fund_batch_token(ctx: Context<FundBatchToken>, amount: u64) -> Result<()> {
+        handlers::payroll::fund_batch_token(ctx, amount)
+    }
+
+    pub fn close_batch_token_vault(ctx: Context<CloseBatchTokenVault>) -> Result<()> {
+        handlers::payroll::close_batch_token_vault(ctx)
+    }
+
+    pub fn claim_salary<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimSalary<'info>>) -> Result<()> {
         handlers::payroll::claim_salary(ctx)
     }
 
+    pub fn claim_salary_token(ctx: Context<ClaimSalaryToken>) -> Result<()> {
+        handlers::payroll::claim_salary_token(ctx)
+    }
+
+    pub fn claim_salary_stealth(ctx: Context<ClaimSalaryStealth>, valid_until: i64) -> Result<()> {
+        handlers::payroll::claim_salary_stealth(ctx, valid_until)
+    }
+
+    pub fn set_payout_address(ctx: Context<SetPayoutAddress>, payout_address: Pubkey) -> Result<()> {
+        handlers::payroll::set_payout_address(ctx, payout_address)
+    }
+
+    pub fn update_employee_stealth(ctx: Context<UpdateEmployeeStealth>, stealth_address: String) -> Result<()> {
+        handlers::payroll::update_employee_stealth(ctx, stealth_address)
+    }
+
+    pub fn set_push_payments(ctx: Context<SetPushPayments>, enabled: bool) -> Result<()> {
+        handlers::payroll::set_push_payments(ctx, enabled)
+    }
+
+    pub fn push_salary(ctx: Context<PushSalary>) -> Result<()> {
+        handlers::payroll::push_salary(ctx)
+    }
+
+    pub fn run_payday<'info>(ctx: Context<'_, '_, 'info, 'info, RunPayday<'info>>) -> Result<()> {
+        handlers::payroll::run_payday(ctx)
+    }
+
+    pub fn crank_claim_ready_salaries<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CrankClaimReadySalaries<'info>>,
+    ) -> Result<()> {
+        handlers::payroll::crank_claim_ready_salaries(ctx)
+    }
+
+    pub fn set_crank_tip(ctx: Context<SetCrankTip>, tip: u64) -> Result<()> {
+        handlers::payroll::set_crank_tip(ctx, tip)
+    }
+
+    pub fn set_min_claim_interval(ctx: Context<SetMinClaimInterval>, interval_seconds: i64) -> Result<()> {
+        handlers::payroll::set_min_claim_interval(ctx, interval_seconds)
+    }
+
+    pub fn claim_salary_to_commitment(
+        ctx: Context<ClaimSalaryToCommitment>,
+        commitment: [u8; 32],
+        amount: u64,
+    ) -> Result<()> {
+        handlers::payroll::claim_salary_to_commitment(ctx, commitment, amount)
+    }
+
+    pub fn claim_salary_to_pool(ctx: Context<ClaimSalaryToPool>, commitment: [u8; 32], amount: u64) -> Result<()> {
+        handlers::payroll::claim_salary_to_pool(ctx, commitment, amount)
+    }
+
     pub fn update_salary_rate(ctx: Context<UpdateSalaryRate>, new_rate: u64) -> Result<()> {
         handlers::payroll::update_salary_rate(ctx, new_rate)
     }
 
+    pub fn schedule_rate_change(ctx: Context<UpdateSalaryRate>, effective_at: i64, rate: u64) -> Result<()> {
+        handlers::payroll::schedule_rate_change(ctx, effective_at, rate)
+    }
+
+    pub fn clear_rate_schedule(ctx: Context<UpdateSalaryRate>) -> Result<()> {
+        handlers::payroll::clear_rate_schedule(ctx)
+    }
+
+    pub fn set_employee_pay_mint(ctx: Context<SetEmployeePayMint>, salary_rate: u64) -> Result<()> {
+        handlers::payroll::set_employee_pay_mint(ctx, salary_rate)
+    }
+
+    pub fn set_claim_cap(ctx: Context<SetClaimCap>, max_claim_per_period: u64, period_seconds: i64) -> Result<()> {
+        handlers::payroll::set_claim_cap(ctx, max_claim_per_period, period_seconds)
+    }
+
+    pub fn propose_payout_splits(ctx: Context<ProposePayoutSplits>, splits: Vec<PayoutSplit>) -> Result<()> {
+        handlers::payroll::propose_payout_splits(ctx, splits)
+    }
+
+    pub fn accept_payout_splits(ctx: Context<AcceptPayoutSplits>, splits: Vec<PayoutSplit>) -> Result<()> {
+        handlers::payroll::accept_payout_splits(ctx, splits)
+    }
+
+    pub fn allocate_employee_budget(ctx: Context<AllocateEmployeeBudget>, amount: u64) -> Result<()> {
+        handlers::payroll::allocate_employee_budget(ctx, amount)
+    }
+
+    pub fn set_pay_schedule(
+        ctx: Context<SetPaySchedule>,
+        schedule: PaySchedule,
+        period_seconds: i64,
+        payday_offset: i64,
+    ) -> Result<()> {
+        handlers::payroll::set_pay_schedule(ctx, schedule, period_seconds, payday_offset)
+    }
+
+    pub fn set_salary_currency(
+        ctx: Context<SetSalaryCurrency>,
+        currency: SalaryCurrency,
+        price_feed_id: [u8; 32],
+    ) -> Result<()> {
+        handlers::payroll::set_salary_currency(ctx, currency, price_feed_id)
+    }
+
     pub fn set_employee_status(ctx: Context<SetEmployeeStatus>, new_status: EmployeeStatus) -> Result<()> {
         handlers::payroll::set_employee_status(ctx, new_status)
     }
 
+    pub fn place_employee_hold(ctx: Context<SetEmployeeStatus>, reason_hash: [u8; 32]) -> Result<()> {
+        handlers::payroll::place_employee_hold(ctx, reason_hash)
+    }
+
+    pub fn lift_employee_hold(ctx: Context<SetEmployeeStatus>) -> Result<()> {
+        handlers::payroll::lift_employee_hold(ctx)
+    }
+
+    pub fn terminate_employee(ctx: Context<TerminateEmployee>, severance_days: Option<u16>) -> Result<()> {
+        handlers::payroll::terminate_employee(ctx, severance_days)
+    }
+
+    pub fn init_roster_page(ctx: Context<InitRosterPage>, page: u32) -> Result<()> {
+        handlers::payroll::init_roster_page(ctx, page)
+    }
+
+    pub fn snapshot_employee_period(ctx: Context<SnapshotEmployeePeriod>) -> Result<()> {
+        handlers::payroll::snapshot_employee_period(ctx)
+    }
+
+    pub fn get_batch_runway<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetBatchRunway<'info>>,
+    ) -> Result<BatchRunway> {
+        handlers::payroll::get_batch_runway(ctx)
+    }
+
+    pub fn close_employee(ctx: Context<CloseEmployee>) -> Result<()> {
+        handlers::payroll::close_employee(ctx)
+    }
+
+    pub fn set_batch_status(ctx: Context<SetBatchStatus>, new_status: BatchStatus) -> Result<()> {
+        handlers::payroll::set_batch_status(ctx, new_status)
+    }
+
+    pub fn grant_batch_role(ctx: Context<GrantBatchRole>, admin: Pubkey, permissions: u8) -> Result<()> {
+        handlers::payroll::grant_batch_role(ctx, admin, permissions)
+    }
+
+    pub fn revoke_batch_role(ctx: Context<RevokeBatchRole>) -> Result<()> {
+        handlers::payroll::revoke_batch_role(ctx)
+    }
+
+    pub fn transfer_batch_ownership(ctx: Context<TransferBatchOwnership>, new_owner: Pubkey) -> Result<()> {
+        handlers::payroll::transfer_batch_ownership(ctx, new_owner)
+    }
+
+    pub fn accept_batch_ownership(ctx: Context<AcceptBatchOwnership>) -> Result<()> {
+        handlers::payroll::accept_batch_ownership(ctx)
+    }
+
+    pub fn transfer_employee(ctx: Context<TransferEmployee>) -> Result<()> {
+        handlers::payroll::transfer_employee(ctx)
+    }
+
+    pub fn close_batch(ctx: Context<CloseBatch>) -> Result<()> {
+        handlers::payroll::close_batch(ctx)
+    }
+
+    pub fn migrate_payroll_batch(ctx: Context<MigratePayrollBatch>) -> Result<()> {
+        handlers::payroll::migrate_payroll_batch(ctx)
+    }
+
+    pub fn migrate_employee(ctx: Context<MigrateEmployee>) -> Result<()> {
+        handlers::payroll::migrate_employee(ctx)
+    }
+
     // ==============================================
     // ANONYMOUS RECEIPTS
     // ==============================================
 
-    pub fn create_receipt(ctx: Context<CreateReceipt>, receipt_secret: [u8; 32]) -> Result<()> {
-        handlers::receipt::create(ctx, receipt_secret)
+    pub fn init_receipt_tree(ctx: Context<InitReceiptTree>, depth: u8) -> Result<()> {
+        handlers::receipt::init_tree(ctx, depth)
+    }
+
+    pub fn create_receipt(ctx: Context<CreateReceipt>, receipt_secret: [u8; 32], membership_key: Pubkey) -> Result<()> {
+        handlers::receipt::create(ctx, receipt_secret, membership_key)
     }
 
     pub fn verify_receipt(
@@ -234,16 +784,69 @@ pub mod offuscate {
         handlers::receipt::verify_blind(ctx, employee_wallet, timestamp_range_start, timestamp_range_end)
     }
 
+    pub fn prove_receipt_membership(
+        ctx: Context<ProveReceiptMembership>,
+        employer: Pubkey,
+        day: i64,
+        range_start: i64,
+        range_end: i64,
+        proof: MerkleProof,
+    ) -> Result<()> {
+        handlers::receipt::prove_membership(ctx, employer, day, range_start, range_end, proof)
+    }
+
     // ==============================================
     // COMMITMENT-BASED PRIVACY (ZK-LIKE)
     // ==============================================
 
+    pub fn init_commitment_tree(ctx: Context<InitCommitmentTree>, class: u8, depth: u8) -> Result<()> {
+        handlers::commitment::init_tree(ctx, class, depth)
+    }
+
+    pub fn set_scheme_deposits(ctx: Context<UpdateCommitmentConfig>, scheme_id: u8, enabled: bool) -> Result<()> {
+        handlers::commitment::set_scheme_deposits(ctx, scheme_id, enabled)
+    }
+
+    pub fn set_timestamp_granularity(ctx: Context<UpdateCommitmentConfig>, granularity: i64) -> Result<()> {
+        handlers::commitment::set_timestamp_granularity(ctx, granularity)
+    }
+
+    pub fn set_legacy_spend_deadline(ctx: Context<UpdateCommitmentConfig>, deadline: i64) -> Result<()> {
+        handlers::commitment::set_legacy_spend_deadline(ctx, deadline)
+    }
+
     pub fn private_deposit(
         ctx: Context<PrivateDeposit>,
         commitment: [u8; 32],
         amount: u64,
+        scheme_id: u8,
+        memo: Option<Vec<u8>>,
+        announcement: Option<NoteAnnouncement>,
+    ) -> Result<()> {
+        handlers::commitment::deposit(ctx, commitment, amount, scheme_id, memo, announcement)
+    }
+
+    pub fn private_deposit_refundable(
+        ctx: Context<PrivateDepositRefundable>,
+        commitment: [u8; 32],
+        amount: u64,
+        refund_authority: Pubkey,
+        memo: Option<Vec<u8>>,
+        announcement: Option<NoteAnnouncement>,
     ) -> Result<()> {
-        handlers::commitment::deposit(ctx, commitment, amount)
+        handlers::commitment::deposit_refundable(ctx, commitment, amount, refund_authority, memo, announcement)
+    }
+
+    pub fn refund_note(ctx: Context<RefundNote>) -> Result<()> {
+        handlers::commitment::refund_note(ctx)
+    }
+
+    pub fn post_spend_intent(ctx: Context<PostSpendIntent>, statement: [u8; 32]) -> Result<()> {
+        handlers::commitment::post_spend_intent(ctx, statement)
+    }
+
+    pub fn cancel_spend_intent(ctx: Context<CancelSpendIntent>) -> Result<()> {
+        handlers::commitment::cancel_spend_intent(ctx)
     }
 
     pub fn private_withdraw(
@@ -251,7 +854,192 @@ pub mod offuscate {
         nullifier: [u8; 32],
         secret_hash: [u8; 32],
         amount: u64,
+        scheme_id: u8,
+        proof: MerkleProof,
+    ) -> Result<()> {
+        handlers::commitment::withdraw(ctx, nullifier, secret_hash, amount, scheme_id, proof)
+    }
+
+    pub fn withdraw_commitment_pda(
+        ctx: Context<WithdrawCommitmentPda>,
+        nullifier: [u8; 32],
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        handlers::commitment::withdraw_commitment_pda(ctx, nullifier, secret_hash)
+    }
+
+    pub fn private_withdraw_delegated(
+        ctx: Context<PrivateWithdrawDelegated>,
+        nullifier: [u8; 32],
+        spend: DelegatedSpend,
+        amount: u64,
+        valid_until: i64,
+        proof: MerkleProof,
+    ) -> Result<()> {
+        handlers::commitment::withdraw_delegated(ctx, nullifier, spend, amount, valid_until, proof)
+    }
+
+    pub fn rescue_note(
+        ctx: Context<PrivateWithdraw>,
+        nullifier: [u8; 32],
+        secret_hash: [u8; 32],
+        amount: u64,
+        scheme_id: u8,
+        version: u8,
+        proof: MerkleProof,
+    ) -> Result<()> {
+        handlers::commitment::rescue_note(ctx, nullifier, secret_hash, amount, scheme_id, version, proof)
+    }
+
+    pub fn rescue_owned_note(
+        ctx: Context<RescueOwnedNote>,
+        nullifier: [u8; 32],
+        owner: NoteOwnerKey,
+        amount: u64,
+        valid_until: i64,
+        proof: MerkleProof,
+    ) -> Result<()> {
+        handlers::commitment::rescue_owned_note(ctx, nullifier, owner, amount, valid_until, proof)
+    }
+
+    pub fn private_withdraw_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PrivateWithdrawBatch<'info>>,
+        spends: Vec<NoteSpend>,
+        root: [u8; 32],
+        siblings: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        handlers::commitment::withdraw_batch(ctx, spends, root, siblings)
+    }
+
+    pub fn migrate_note(
+        ctx: Context<MigrateNote>,
+        nullifier: [u8; 32],
+        secret_hash: [u8; 32],
+        amount: u64,
+        version: u8,
+        proof: MerkleProof,
+        new_commitment: [u8; 32],
+    ) -> Result<()> {
+        handlers::commitment::migrate_note(ctx, nullifier, secret_hash, amount, version, proof, new_commitment)
+    }
+
+    pub fn transfer_note(
+        ctx: Context<TransferNote>,
+        nullifier: [u8; 32],
+        secret_hash: [u8; 32],
+        amount: u64,
+        proof: MerkleProof,
+        new_commitment: [u8; 32],
+        announcement: Option<NoteAnnouncement>,
+    ) -> Result<()> {
+        handlers::commitment::transfer_note(ctx, nullifier, secret_hash, amount, proof, new_commitment, announcement)
+    }
+
+    pub fn private_donate(
+        ctx: Context<PrivateDonate>,
+        nullifier: [u8; 32],
+        secret_hash: [u8; 32],
+        amount: u64,
+        proof: MerkleProof,
+    ) -> Result<()> {
+        handlers::commitment::private_donate(ctx, nullifier, secret_hash, amount, proof)
+    }
+
+    pub fn fund_batch_private(
+        ctx: Context<FundBatchPrivate>,
+        nullifier: [u8; 32],
+        secret_hash: [u8; 32],
+        amount: u64,
+        proof: MerkleProof,
+    ) -> Result<()> {
+        handlers::commitment::fund_batch_private(ctx, nullifier, secret_hash, amount, proof)
+    }
+
+    pub fn init_spent_bitmap(ctx: Context<InitSpentBitmap>, class: u8, chunk: u64) -> Result<()> {
+        handlers::commitment::init_spent_bitmap(ctx, class, chunk)
+    }
+
+    pub fn get_shielded_stats<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetShieldedStats>,
+    ) -> Result<ShieldedStats> {
+        handlers::commitment::get_shielded_stats(ctx)
+    }
+
+    pub fn close_nullifier(ctx: Context<CloseNullifier>) -> Result<()> {
+        handlers::commitment::close_nullifier(ctx)
+    }
+
+    pub fn register_disclosure(
+        ctx: Context<RegisterDisclosure>,
+        commitment: [u8; 32],
+        opening: OwnedNoteOpening,
+        auditor: Pubkey,
+        ciphertext: Vec<u8>,
+    ) -> Result<()> {
+        handlers::commitment::register_disclosure(ctx, commitment, opening, auditor, ciphertext)
+    }
+
+    pub fn close_disclosure(ctx: Context<CloseDisclosure>) -> Result<()> {
+        handlers::commitment::close_disclosure(ctx)
+    }
+
+    // ==============================================
+    // HIDDEN-AMOUNT NOTES (PEDERSEN)
+    // ==============================================
+
+    pub fn private_deposit_hidden(
+        ctx: Context<PrivateDepositHidden>,
+        commitment: [u8; 32],
+        value_commitment: [u8; 32],
+        amount: u64,
+        opening: OpeningProof,
+        announcement: Option<NoteAnnouncement>,
+    ) -> Result<()> {
+        handlers::hidden_note::deposit(ctx, commitment, value_commitment, amount, opening, announcement)
+    }
+
+    pub fn private_withdraw_hidden(
+        ctx: Context<PrivateWithdrawHidden>,
+        nullifier: [u8; 32],
+        note: HiddenNoteOpening,
+        amount: u64,
+        proof: MerkleProof,
+        change: HiddenChange,
+        balance_proof: OpeningProof,
+    ) -> Result<()> {
+        handlers::hidden_note::withdraw(ctx, nullifier, note, amount, proof, change, balance_proof)
+    }
+
+    pub fn rescue_hidden_note(
+        ctx: Context<PrivateWithdraw>,
+        nullifier: [u8; 32],
+        note: HiddenNoteOpening,
+        amount: u64,
+        blinding: [u8; 32],
+        version: u8,
+        proof: MerkleProof,
+    ) -> Result<()> {
+        handlers::hidden_note::rescue(ctx, nullifier, note, amount, blinding, version, proof)
+    }
+    // ==============================================
+    // SHIELDED SPL TOKEN NOTES
+    // ==============================================
+
+    pub fn init_token_pool(ctx: Context<InitTokenPool>, depth: u8) -> Result<()> {
+        handlers::token_note::init_pool(ctx, depth)
+    }
+
+    pub fn token_deposit(ctx: Context<TokenDeposit>, commitment: [u8; 32], amount: u64) -> Result<()> {
+        handlers::token_note::deposit(ctx, commitment, amount)
+    }
+
+    pub fn token_withdraw(
+        ctx: Context<TokenWithdraw>,
+        nullifier: [u8; 32],
+        secret_hash: [u8; 32],
+        amount: u64,
+        proof: MerkleProof,
     ) -> Result<()> {
-        handlers::commitment::withdraw(ctx, nullifier, secret_hash, amount)
+        handlers::token_note::withdraw(ctx, nullifier, secret_hash, amount, proof)
     }
 }
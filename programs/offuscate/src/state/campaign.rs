@@ -4,8 +4,11 @@
 //! - Campaign: Campaign details and stats
 //! - StealthRegistry: Records stealth payments for scanning
 //! - CampaignStatus: Campaign lifecycle status
+//! - CampaignRegistry / CampaignRegistryPage: Sequential index of campaigns
 
 use anchor_lang::prelude::*;
+use crate::constants::CAMPAIGN_REGISTRY_PAGE_SIZE;
+use crate::errors::ErrorCode;
 
 /// Campaign status enum
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -34,6 +37,10 @@ pub struct Campaign {
     pub stealth_meta_address: String,    // 4 + 200 = 204 bytes (st:viewPub:spendPub)
     pub stealth_donations: u64,          // 8 bytes (count of stealth donations)
     pub stealth_total: u64,              // 8 bytes (total stealth amount - for display)
+    pub deadline_extension: i64,         // 8 bytes (seconds added to the original deadline)
+    pub withdraw_nonce: u64,             // 8 bytes (replay protection for relayed withdrawals)
+    pub registered: bool,                // 1 byte (listed in the campaign registry)
+    pub registry_index: u64,             // 8 bytes (index in the campaign registry)
 }
 
 impl Campaign {
@@ -53,7 +60,37 @@ impl Campaign {
         (4 + 200) +               // stealth_meta_address
         8 +                        // stealth_donations
         8 +                        // stealth_total
+        8 +                        // deadline_extension
+        8 +                        // withdraw_nonce
+        1 +                        // registered
+        8 +                        // registry_index
         64;                        // padding for safety
+
+    /// Message the owner signs to have `amount` withdrawn to `recipient` by a
+    /// relayer, which keeps `fee` of it
+    pub fn withdraw_message(
+        campaign: &Pubkey,
+        nonce: u64,
+        recipient: &Pubkey,
+        amount: u64,
+        fee: u64,
+        valid_until: i64,
+    ) -> Vec<u8> {
+        let mut message = Vec::with_capacity(18 + 32 + 8 + 32 + 8 + 8 + 8);
+        message.extend_from_slice(b"campaign_withdraw:");
+        message.extend_from_slice(campaign.as_ref());
+        message.extend_from_slice(&nonce.to_le_bytes());
+        message.extend_from_slice(recipient.as_ref());
+        message.extend_from_slice(&amount.to_le_bytes());
+        message.extend_from_slice(&fee.to_le_bytes());
+        message.extend_from_slice(&valid_until.to_le_bytes());
+        message
+    }
+
+    /// Whether anyone has donated yet, to the vault or to a stealth address
+    pub fn has_donations(&self) -> bool {
+        self.donor_count > 0 || self.stealth_donations > 0
+    }
 }
 
 /// Registry entry for a stealth payment
@@ -78,3 +115,53 @@ impl StealthRegistry {
         1 +                         // bump
         16;                         // padding
 }
+
+/// Campaign registry - hands out sequential campaign indices
+/// Seeds: ["campaign_registry"]
+#[account]
+pub struct CampaignRegistry {
+    pub campaign_count: u64,        // 8 bytes - campaigns registered so far
+    pub bump: u8,                   // 1 byte
+}
+
+impl CampaignRegistry {
+    pub const SPACE: usize = 8 +   // discriminator
+        8 +                         // campaign_count
+        1 +                         // bump
+        16;                         // padding
+}
+
+/// One page of the campaign registry, CAMPAIGN_REGISTRY_PAGE_SIZE campaigns by index
+/// Seeds: ["campaign_registry_page", page]
+///
+/// Lets explorers list every campaign by reading pages 0.. instead of
+/// indexing transactions; a default key marks a slot not used yet or whose
+/// campaign account was closed.
+#[account(zero_copy)]
+pub struct CampaignRegistryPage {
+    pub campaigns: [Pubkey; CAMPAIGN_REGISTRY_PAGE_SIZE], // 4096 bytes - campaign key per slot
+    pub page: u32,                  // 4 bytes
+    pub bump: u8,                   // 1 byte
+    pub _reserved: [u8; 3],         // 3 bytes - explicit alignment padding
+}
+
+impl CampaignRegistryPage {
+    pub const SPACE: usize = 8 +   // discriminator
+        32 * CAMPAIGN_REGISTRY_PAGE_SIZE + // campaigns
+        4 +                         // page
+        1 +                         // bump
+        3 +                         // _reserved
+        16;                         // padding
+
+    /// Page holding campaign `index`
+    pub fn page_of(index: u64) -> u32 {
+        (index / CAMPAIGN_REGISTRY_PAGE_SIZE as u64) as u32
+    }
+
+    /// Record `campaign` at registry index `index`
+    pub fn record(&mut self, index: u64, campaign: Pubkey) -> Result<()> {
+        require!(Self::page_of(index) == self.page, ErrorCode::InvalidRegistryPage);
+        self.campaigns[index as usize % CAMPAIGN_REGISTRY_PAGE_SIZE] = campaign;
+        Ok(())
+    }
+}
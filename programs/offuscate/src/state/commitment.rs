@@ -1,14 +1,498 @@
 //! Commitment-based Privacy State
 //!
 //! ZK-like privacy using commitments and nullifiers:
-//! - CommitmentPDA: Stores deposit commitments
+//! - CommitmentTree: Incremental Merkle tree of one note class's commitments
+//! - MerkleProof: Membership proof supplied at withdrawal
+//! - NoteSpend: One note of a batched withdrawal
+//! - NoteHeader: Canonical versioned note encoding
+//! - OpeningProof, HiddenNoteOpening, HiddenChange: Hidden-amount note inputs
+//! - NoteAnnouncement: Encrypted note for a third-party recipient
 //! - NullifierPDA: Tracks used nullifiers to prevent double-spend
+//! - CommitmentPDA: Per-deposit commitment of the original scheme (spend only)
+//! - SpentBitmap: Compact record of spent leaf indices
+//! - ShieldedStats: Return data of `get_shielded_stats`
+//! - RefundTicket: Optional timelocked refund clause for a deposit
+//! - SpendIntent: Commitment to where a bare-secret spend sends its value
+//! - Disclosure: Opt-in auditor-readable disclosure for a commitment
+//! - DepositEscrow: Pre-funded escrow for relayed private deposits and donations
+//! - RentEscrow: Pre-funded escrow for the rent of relayer-created accounts
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::constants::{
+    ALLOWED_AMOUNTS, COMMITMENT_TREE_DEPTH, ROOT_HISTORY_SIZE, NOTE_VERSION, NOTE_SCHEME_SHA256,
+    NOTE_SCHEME_PEDERSEN, SPENT_BITMAP_BYTES, MIN_ANONYMITY_DEPOSITS,
+    DENOMINATION_COUNT, NOTE_CLASS_COUNT, MAX_DISCLOSURE_LEN, DEFAULT_TIMESTAMP_GRANULARITY, LEAF_DOMAIN,
+    NOTE_SCHEME_TOKEN, TOKEN_TREE_CLASS,
+    ESCROW_REFUND_DELAY_SECONDS, SPEND_INTENT_DOMAIN, SPEND_INTENT_DELAY_SLOTS,
+};
+use crate::errors::ErrorCode;
 
-/// Individual PDA for each commitment
-/// Created when a private deposit is made
-/// Stores: commitment hash, amount, timestamp, spent status
+/// Incremental Merkle tree holding every commitment leaf of one note class
+/// PRIVACY: Deposits only append a leaf, so a withdrawal names a root rather
+/// than a deposit account. It still reveals the note preimage and leaf index,
+/// so anyone replaying the tree can link the two; hiding the leaf needs a
+/// zero-knowledge membership proof in place of the Merkle path check.
+///
+/// There is one tree per denomination plus one for hidden-amount notes, each
+/// with its own roots and counter, so a tree's activity says nothing about
+/// the others and its depth can match its expected volume.
+///
+/// Leaves are hash(tag || note header || commitment) so version, scheme and
+/// amount are bound on-chain. Zero-copy because the tree state is a few kilobytes.
+#[account(zero_copy)]
+pub struct CommitmentTree {
+    pub filled_subtrees: [[u8; 32]; COMMITMENT_TREE_DEPTH], // 640 bytes - last left node per level, first `depth` used
+    pub zeros: [[u8; 32]; COMMITMENT_TREE_DEPTH],           // 640 bytes - empty subtree hash per level, first `depth` used
+    pub roots: [[u8; 32]; ROOT_HISTORY_SIZE],               // 960 bytes - ring buffer of recent roots
+    pub next_index: u64,           // 8 bytes - next free leaf index
+    pub current_root_index: u64,   // 8 bytes - position of the latest root in `roots`
+    pub timestamp_granularity: i64, // 8 bytes - rounding for stored times, 0 = omit them
+    pub legacy_spends_until: i64,  // 8 bytes - version 1 notes spendable directly until then
+    pub bump: u8,                  // 1 byte
+    pub disabled_deposit_schemes: u8, // 1 byte - bit per scheme id; spends stay allowed
+    pub class: u8,                 // 1 byte - note class (NoteHeader::class) of every leaf
+    pub depth: u8,                 // 1 byte - levels in use, at most COMMITMENT_TREE_DEPTH
+    pub _reserved: [u8; 4],        // 4 bytes - explicit alignment padding
+}
+
+impl CommitmentTree {
+    pub const SPACE: usize = 8 +  // discriminator
+        32 * COMMITMENT_TREE_DEPTH + // filled_subtrees
+        32 * COMMITMENT_TREE_DEPTH + // zeros
+        32 * ROOT_HISTORY_SIZE +   // roots
+        8 +                        // next_index
+        8 +                        // current_root_index
+        8 +                        // timestamp_granularity
+        8 +                        // legacy_spends_until
+        1 +                        // bump
+        1 +                        // disabled_deposit_schemes
+        1 +                        // class
+        1 +                        // depth
+        4 +                        // _reserved
+        16;                        // padding
+
+    pub fn capacity(&self) -> u64 {
+        1 << self.depth
+    }
+
+    pub fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        hashv(&[left, right]).to_bytes()
+    }
+
+    /// Leaf stored for a commitment with the given note header
+    ///
+    /// Interior nodes stay untagged so existing trees keep their roots; a
+    /// tagged leaf preimage is 52 bytes and can never be read as a 64-byte node.
+    pub fn leaf(header: &NoteHeader, commitment: &[u8; 32]) -> [u8; 32] {
+        const _: () = assert!(LEAF_DOMAIN.len() + NoteHeader::LEN + 32 == 52);
+        match header.version {
+            0 => {
+                let amount = ALLOWED_AMOUNTS[header.denomination_index as usize];
+                hashv(&[commitment, &amount.to_le_bytes()]).to_bytes()
+            }
+            1 => hashv(&[&header.encode(), commitment]).to_bytes(),
+            _ => hashv(&[LEAF_DOMAIN, &header.encode(), commitment]).to_bytes(),
+        }
+    }
+
+    /// Set up an empty tree of `depth` levels for notes of `class`
+    pub fn initialize(&mut self, class: u8, depth: u8, bump: u8) {
+        let mut zero = [0u8; 32];
+        for level in 0..depth as usize {
+            self.zeros[level] = zero;
+            self.filled_subtrees[level] = zero;
+            zero = Self::hash_pair(&zero, &zero);
+        }
+        self.roots = [[0u8; 32]; ROOT_HISTORY_SIZE];
+        self.roots[0] = zero;
+        self.next_index = 0;
+        self.current_root_index = 0;
+        self.timestamp_granularity = DEFAULT_TIMESTAMP_GRANULARITY;
+        self.legacy_spends_until = i64::MAX;
+        self.class = class;
+        self.depth = depth;
+        self.bump = bump;
+    }
+
+    /// Require that notes with this header live in this tree
+    pub fn require_class(&self, header: &NoteHeader) -> Result<()> {
+        require!(header.class() == self.class as usize, ErrorCode::WrongCommitmentTree);
+        Ok(())
+    }
+
+    /// Reject new notes of a scheme that has been deprecated for deposits
+    pub fn require_deposits_enabled(&self, header: &NoteHeader) -> Result<()> {
+        require!(
+            self.disabled_deposit_schemes & (1 << header.scheme_id) == 0,
+            ErrorCode::NoteSchemeDeprecated
+        );
+        Ok(())
+    }
+
+    /// Whether version 1 notes can still be spent without migrating first
+    pub fn legacy_spends_open(&self) -> Result<bool> {
+        Ok(Clock::get()?.unix_timestamp < self.legacy_spends_until)
+    }
+
+    /// Current time rounded down to the configured granularity (0 if omitted)
+    pub fn coarse_timestamp(&self) -> Result<i64> {
+        if self.timestamp_granularity == 0 {
+            return Ok(0);
+        }
+        let now = Clock::get()?.unix_timestamp;
+        Ok(now - now.rem_euclid(self.timestamp_granularity))
+    }
+
+    /// Require at least `MIN_ANONYMITY_DEPOSITS` leaves after `leaf_index`
+    ///
+    /// Every leaf of a tree is the same class, so the leaf count is enough.
+    pub fn require_anonymity_set(&self, leaf_index: u64) -> Result<()> {
+        let later = self.next_index.saturating_sub(leaf_index.saturating_add(1));
+        require!(later >= MIN_ANONYMITY_DEPOSITS as u64, ErrorCode::AnonymitySetTooSmall);
+        Ok(())
+    }
+
+    pub fn current_root(&self) -> [u8; 32] {
+        self.roots[self.current_root_index as usize]
+    }
+
+    /// Append a leaf, returning its index
+    pub fn insert(&mut self, leaf: [u8; 32]) -> Result<u64> {
+        let index = self.next_index;
+        require!(index < self.capacity(), ErrorCode::CommitmentTreeFull);
+
+        let mut node = leaf;
+        let mut position = index;
+        for level in 0..self.depth as usize {
+            if position & 1 == 0 {
+                self.filled_subtrees[level] = node;
+                node = Self::hash_pair(&node, &self.zeros[level]);
+            } else {
+                node = Self::hash_pair(&self.filled_subtrees[level], &node);
+            }
+            position >>= 1;
+        }
+
+        self.current_root_index = (self.current_root_index + 1) % ROOT_HISTORY_SIZE as u64;
+        self.roots[self.current_root_index as usize] = node;
+        self.next_index = index + 1;
+
+        Ok(index)
+    }
+
+    /// Whether `root` is one of the recent roots
+    pub fn is_known_root(&self, root: &[u8; 32]) -> bool {
+        *root != [0u8; 32] && self.roots.contains(root)
+    }
+
+    /// Ensure `leaf` is included under a recent root
+    pub fn verify(&self, leaf: [u8; 32], proof: &MerkleProof) -> Result<()> {
+        require!(proof.path.len() == self.depth as usize, ErrorCode::InvalidMerkleProof);
+        require!(proof.leaf_index < self.next_index, ErrorCode::InvalidMerkleProof);
+        require!(self.is_known_root(&proof.root), ErrorCode::UnknownMerkleRoot);
+
+        let mut node = leaf;
+        let mut position = proof.leaf_index;
+        for sibling in proof.path.iter() {
+            node = if position & 1 == 0 {
+                Self::hash_pair(&node, sibling)
+            } else {
+                Self::hash_pair(sibling, &node)
+            };
+            position >>= 1;
+        }
+
+        require!(node == proof.root, ErrorCode::InvalidMerkleProof);
+        Ok(())
+    }
+
+    /// Verify several leaves against one root with a shared multiproof
+    ///
+    /// `leaves` must be sorted by strictly increasing index. `siblings` holds,
+    /// level by level from the leaves up, every sibling not computable from
+    /// the leaves themselves, so notes close together in the tree share most
+    /// of their path.
+    pub fn verify_many(&self, leaves: &[(u64, [u8; 32])], root: &[u8; 32], siblings: &[[u8; 32]]) -> Result<()> {
+        require!(self.is_known_root(root), ErrorCode::UnknownMerkleRoot);
+        require!(
+            leaves.windows(2).all(|pair| pair[0].0 < pair[1].0)
+                && leaves.last().is_some_and(|(index, _)| *index < self.next_index),
+            ErrorCode::InvalidMerkleProof
+        );
+
+        let mut siblings = siblings.iter();
+        let mut layer = leaves.to_vec();
+        for _ in 0..self.depth {
+            let mut next = Vec::with_capacity(layer.len());
+            let mut i = 0;
+            while i < layer.len() {
+                let (index, node) = layer[i];
+                let parent = if index & 1 == 0 {
+                    if layer.get(i + 1).is_some_and(|(right, _)| *right == index + 1) {
+                        i += 1;
+                        Self::hash_pair(&node, &layer[i].1)
+                    } else {
+                        let sibling = siblings.next().ok_or(ErrorCode::InvalidMerkleProof)?;
+                        Self::hash_pair(&node, sibling)
+                    }
+                } else {
+                    let sibling = siblings.next().ok_or(ErrorCode::InvalidMerkleProof)?;
+                    Self::hash_pair(sibling, &node)
+                };
+                next.push((index >> 1, parent));
+                i += 1;
+            }
+            layer = next;
+        }
+
+        require!(
+            siblings.next().is_none() && layer.len() == 1 && layer[0].1 == *root,
+            ErrorCode::InvalidMerkleProof
+        );
+        Ok(())
+    }
+}
+
+/// Canonical note header, serialized as `[version, denomination_index, scheme_id]`
+///
+/// Wallets encode notes as this header followed by the secret and nullifier.
+/// New fields only ever come with a new version.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NoteHeader {
+    pub version: u8,
+    pub denomination_index: u8,   // index into ALLOWED_AMOUNTS
+    pub scheme_id: u8,            // commitment scheme (NOTE_SCHEME_*), selects the spend check
+}
+
+impl NoteHeader {
+    pub const LEN: usize = 3;
+
+    /// Header of a note written today for `amount`
+    pub fn current(amount: u64) -> Result<Self> {
+        Self::with_version(NOTE_VERSION, amount)
+    }
+
+    pub fn with_version(version: u8, amount: u64) -> Result<Self> {
+        require!(version <= NOTE_VERSION, ErrorCode::UnsupportedNoteVersion);
+        let denomination_index = ALLOWED_AMOUNTS.iter()
+            .position(|&allowed| allowed == amount)
+            .ok_or(ErrorCode::InvalidWithdrawAmount)?;
+        Ok(Self {
+            version,
+            denomination_index: denomination_index as u8,
+            scheme_id: NOTE_SCHEME_SHA256,
+        })
+    }
+
+    /// Header of a new note using commitment scheme `scheme_id`
+    ///
+    /// Only the sha256 preimage scheme has a spend check; Pedersen notes use
+    /// `pedersen()`.
+    pub fn with_scheme(scheme_id: u8, amount: u64) -> Result<Self> {
+        require!(scheme_id == NOTE_SCHEME_SHA256, ErrorCode::UnsupportedNoteScheme);
+        Ok(Self { scheme_id, ..Self::current(amount)? })
+    }
+
+    /// Header of a hidden-amount note; `denomination_index` is unused
+    pub fn pedersen() -> Self {
+        Self {
+            version: NOTE_VERSION,
+            denomination_index: 0,
+            scheme_id: NOTE_SCHEME_PEDERSEN,
+        }
+    }
+
+    /// Header of an SPL token note; the amount is bound by the commitment instead
+    pub fn token() -> Self {
+        Self {
+            version: NOTE_VERSION,
+            denomination_index: 0,
+            scheme_id: NOTE_SCHEME_TOKEN,
+        }
+    }
+
+    pub fn amount(&self) -> u64 {
+        ALLOWED_AMOUNTS[self.denomination_index as usize]
+    }
+
+    /// Anonymity class: the denomination, or a shared class for hidden-amount
+    /// notes and for token notes (whose trees are split by mint instead)
+    pub fn class(&self) -> usize {
+        match self.scheme_id {
+            NOTE_SCHEME_PEDERSEN => DENOMINATION_COUNT,
+            NOTE_SCHEME_TOKEN => TOKEN_TREE_CLASS as usize,
+            _ => self.denomination_index as usize,
+        }
+    }
+
+    pub fn encode(&self) -> [u8; Self::LEN] {
+        [self.version, self.denomination_index, self.scheme_id]
+    }
+}
+
+/// Schnorr proof that a Ristretto point is `r * H` for some known `r`,
+/// i.e. a Pedersen commitment to zero
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct OpeningProof {
+    pub nonce_point: [u8; 32], // R = k * H
+    pub response: [u8; 32],    // z = k + c * r (canonical scalar)
+}
+
+/// What a hidden-amount note reveals when spent
+///
+/// The Pedersen commitment is revealed, its amount is not.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct HiddenNoteOpening {
+    pub secret_hash: [u8; 32],
+    pub value_commitment: [u8; 32], // amount * G + blinding * H
+}
+
+/// Owner of a note spendable with a secp256k1 (Ethereum-style) key
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Secp256k1NoteOwner {
+    pub eth_address: [u8; 20],
+    pub salt: [u8; 32], // secret_hash = hash("secp256k1-owner" || eth_address || salt)
+}
+
+/// Opening of an ed25519-owner note, shown to register a disclosure for it
+///
+/// The signing owner key plus `owner_salt` give the note's `secret_hash`.
+/// Revealing this ties the note to its owner and its nullifier, which the
+/// disclosure makes public anyway; a spend still needs the owner signature.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct OwnedNoteOpening {
+    pub nullifier: [u8; 32],
+    pub owner_salt: [u8; 32],
+    pub amount: u64,
+    pub scheme_id: u8,
+}
+
+/// Change note created by a hidden-amount withdrawal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct HiddenChange {
+    pub commitment: [u8; 32],       // sha256(secret_hash || nullifier) of the change note
+    pub value_commitment: [u8; 32], // Pedersen commitment to the remaining amount
+}
+
+/// Encrypted note handed to a recipient who did not create it
+///
+/// The ciphertext carries the note secrets, encrypted to a key derived from
+/// the recipient's view key and `ephemeral_pubkey`. `view_tag` is the first
+/// byte of the shared secret so scanners can skip most announcements cheaply.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct NoteAnnouncement {
+    pub ephemeral_pubkey: [u8; 32],
+    pub view_tag: u8,
+    pub ciphertext: Vec<u8>,   // up to MAX_NOTE_CIPHERTEXT_LEN bytes
+}
+
+/// Return data of `get_shielded_stats`
+/// Lets wallets show the anonymity set before a deposit. Entries are indexed
+/// by note class; classes whose tree was not passed read as zero.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct ShieldedStats {
+    pub commitments: u64,      // leaves across the passed trees
+    pub class_commitments: [u64; NOTE_CLASS_COUNT],
+    pub class_roots: [[u8; 32]; NOTE_CLASS_COUNT],
+}
+
+/// Merkle membership proof for a commitment leaf
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MerkleProof {
+    pub root: [u8; 32],
+    pub leaf_index: u64,
+    pub path: Vec<[u8; 32]>,   // sibling per level, leaf level first
+}
+
+/// Key and salt of an owned note, shown to rescue it while the pool is frozen
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum NoteOwnerKey {
+    /// Spent with `private_withdraw_relayed`
+    Ed25519 { owner: Pubkey, salt: [u8; 32] },
+    /// Spent with `private_withdraw_secp256k1`
+    Secp256k1(Secp256k1NoteOwner),
+    /// Spent with `private_withdraw_delegated`; `by_delegate` picks the signer
+    Delegated(DelegatedSpend),
+}
+
+impl NoteOwnerKey {
+    pub fn secret_hash(&self) -> [u8; 32] {
+        match self {
+            Self::Ed25519 { owner, salt } => hashv(&[b"ed25519-owner", owner.as_ref(), salt]).to_bytes(),
+            Self::Secp256k1(owner) => hashv(&[b"secp256k1-owner", &owner.eth_address, &owner.salt]).to_bytes(),
+            Self::Delegated(spend) => spend.secret_hash(),
+        }
+    }
+}
+
+/// Owner-or-delegate spend key for a delegated note
+///
+/// A delegated note is an owned note with
+/// `secret_hash = hash("delegated-note" || owner || delegate || salt)`.
+/// Either key spends with an ed25519 signature over the withdraw message;
+/// `by_delegate` picks which one signed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct DelegatedSpend {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub salt: [u8; 32],
+    pub by_delegate: bool,
+}
+
+impl DelegatedSpend {
+    pub fn secret_hash(&self) -> [u8; 32] {
+        hashv(&[b"delegated-note", self.owner.as_ref(), self.delegate.as_ref(), &self.salt]).to_bytes()
+    }
+}
+
+/// One note in a batched withdrawal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct NoteSpend {
+    pub nullifier: [u8; 32],
+    pub secret_hash: [u8; 32],
+    pub amount: u64,
+    pub scheme_id: u8,
+    pub leaf_index: u64,
+}
+
+/// Individual PDA for each used nullifier
+/// Created when a private withdrawal is made
+/// Existence of this PDA proves the nullifier has been used; once the spent
+/// leaf is recorded in the bitmap it may be closed to refund its rent
+#[account]
+pub struct NullifierPDA {
+    pub nullifier: [u8; 32],   // 32 bytes - the nullifier hash
+    pub used_at: i64,          // 8 bytes - when used, coarsened (0 if omitted)
+    pub bump: u8,              // 1 byte
+    pub leaf_index: u64,       // 8 bytes - spent leaf, checked in the bitmap on close
+    pub rent_payer: Pubkey,    // 32 bytes - refunded when the PDA is closed
+    pub delegated: bool,       // 1 byte - spent by the note's delegate rather than its owner
+}
+
+impl NullifierPDA {
+    pub const SPACE: usize = 8 +   // discriminator
+        32 +                        // nullifier
+        8 +                         // used_at
+        1 +                         // bump
+        8 +                         // leaf_index
+        32 +                        // rent_payer
+        1 +                         // delegated
+        16;                         // padding
+
+    pub fn record(&mut self, nullifier: [u8; 32], leaf_index: u64, rent_payer: Pubkey, used_at: i64, bump: u8) {
+        self.nullifier = nullifier;
+        self.used_at = used_at;
+        self.bump = bump;
+        self.leaf_index = leaf_index;
+        self.rent_payer = rent_payer;
+        self.delegated = false;
+    }
+}
+
+/// Individual PDA for each commitment, from before the commitment tree
+/// No longer created; deposits made with it are spent by
+/// `withdraw_commitment_pda`, which closes the PDA
 #[account]
 pub struct CommitmentPDA {
     pub commitment: [u8; 32],  // 32 bytes - the commitment hash
@@ -28,20 +512,311 @@ impl CommitmentPDA {
         16;                         // padding
 }
 
-/// Individual PDA for each used nullifier
-/// Created when a private withdrawal is made
-/// Existence of this PDA proves the nullifier has been used
+/// One chunk of a tree's spent-leaf bitmap
+/// Bit `i` of chunk `c` is leaf `c * SpentBitmap::LEAVES + i` of the tree of `class`
+#[account(zero_copy)]
+pub struct SpentBitmap {
+    pub bits: [u8; SPENT_BITMAP_BYTES], // 1024 bytes
+    pub chunk: u64,            // 8 bytes
+    pub bump: u8,              // 1 byte
+    pub class: u8,             // 1 byte - commitment tree this chunk belongs to
+    pub _reserved: [u8; 6],    // 6 bytes - explicit alignment padding
+}
+
+impl SpentBitmap {
+    pub const SPACE: usize = 8 +  // discriminator
+        SPENT_BITMAP_BYTES +       // bits
+        8 +                        // chunk
+        1 +                        // bump
+        1 +                        // class
+        6 +                        // _reserved
+        16;                        // padding
+
+    pub const LEAVES: u64 = (SPENT_BITMAP_BYTES * 8) as u64;
+
+    pub fn chunk_of(leaf_index: u64) -> u64 {
+        leaf_index / Self::LEAVES
+    }
+
+    fn position(&self, leaf_index: u64) -> Result<(usize, u8)> {
+        require!(Self::chunk_of(leaf_index) == self.chunk, ErrorCode::WrongSpentBitmap);
+        let bit = (leaf_index % Self::LEAVES) as usize;
+        Ok((bit / 8, 1 << (bit % 8)))
+    }
+
+    pub fn is_spent(&self, leaf_index: u64) -> Result<bool> {
+        let (byte, mask) = self.position(leaf_index)?;
+        Ok(self.bits[byte] & mask != 0)
+    }
+
+    /// Mark a leaf spent, failing if it already was
+    pub fn mark_spent(&mut self, leaf_index: u64) -> Result<()> {
+        let (byte, mask) = self.position(leaf_index)?;
+        require!(self.bits[byte] & mask == 0, ErrorCode::NullifierAlreadyUsed);
+        self.bits[byte] |= mask;
+        Ok(())
+    }
+}
+
+/// Refund clause attached to a commitment at deposit time
+/// Lets `refund_authority` reclaim the note after `unlock_at` if it was never
+/// spent, e.g. when the nullifier secret is lost
+///
+/// PRIVACY: The ticket ties the leaf to the refund key on-chain, so a
+/// refundable note is only as private as that key.
 #[account]
-pub struct NullifierPDA {
-    pub nullifier: [u8; 32],   // 32 bytes - the nullifier hash
-    pub used_at: i64,          // 8 bytes - when used
+pub struct RefundTicket {
+    pub refund_authority: Pubkey, // 32 bytes
+    pub commitment: [u8; 32],  // 32 bytes
+    pub leaf_index: u64,       // 8 bytes
+    pub amount: u64,           // 8 bytes
+    pub unlock_at: i64,        // 8 bytes
     pub bump: u8,              // 1 byte
 }
 
-impl NullifierPDA {
+impl RefundTicket {
     pub const SPACE: usize = 8 +   // discriminator
-        32 +                        // nullifier
-        8 +                         // used_at
+        32 +                        // refund_authority
+        32 +                        // commitment
+        8 +                         // leaf_index
+        8 +                         // amount
+        8 +                         // unlock_at
+        1 +                         // bump
+        16;                         // padding
+}
+
+/// Commitment to a bare-secret note spend, posted before the spend itself
+///
+/// A note without an owner key is spent by revealing its `secret_hash`,
+/// which anyone seeing the pending transaction could copy into a spend of
+/// their own. The spend only lands against an intent committing to its
+/// destination that is SPEND_INTENT_DELAY_SLOTS old, so a copycat would
+/// have to post an intent of its own and wait, long after the original
+/// spend has landed. The spend closes the intent.
+///
+/// PRIVACY: The intent is opaque until used, but `payer` posts it; post it
+/// from the wallet that pays for the spend, not from the deposit wallet.
+#[account]
+pub struct SpendIntent {
+    pub statement: [u8; 32],   // 32 bytes - SpendIntent::statement of the spend
+    pub payer: Pubkey,         // 32 bytes - posted the intent, may cancel it
+    pub created_slot: u64,     // 8 bytes
+    pub bump: u8,              // 1 byte
+}
+
+impl SpendIntent {
+    pub const SPACE: usize = 8 +   // discriminator
+        32 +                        // statement
+        32 +                        // payer
+        8 +                         // created_slot
         1 +                         // bump
         16;                         // padding
+
+    /// Hash committing to a spend: `action` names the instruction, `notes`
+    /// holds the nullifier and secret hash of each spent note and
+    /// `destination` the account or commitment receiving the value
+    pub fn statement(action: &[u8], notes: &[[u8; 32]], destination: &[u8], amount: u64) -> [u8; 32] {
+        let amount = amount.to_le_bytes();
+        let mut parts: Vec<&[u8]> = vec![SPEND_INTENT_DOMAIN, action, destination, &amount];
+        parts.extend(notes.iter().map(|part| part.as_slice()));
+        hashv(&parts).to_bytes()
+    }
+
+    /// Check the intent commits to `statement` and is old enough to use at `slot`
+    pub fn require_matured(&self, statement: &[u8; 32], slot: u64) -> Result<()> {
+        require!(self.statement == *statement, ErrorCode::SpendIntentMismatch);
+        require!(
+            slot >= self.created_slot.saturating_add(SPEND_INTENT_DELAY_SLOTS),
+            ErrorCode::SpendIntentTooRecent
+        );
+        Ok(())
+    }
+}
+
+/// Opt-in disclosure of a commitment's note to a designated auditor
+/// The blob is encrypted to `auditor`'s key; only the auditor can read it
+///
+/// PRIVACY: Registering publicly ties `owner` to `commitment`. The auditor
+/// learns whatever the blob contains, nobody else learns more than that link.
+#[account]
+pub struct Disclosure {
+    pub owner: Pubkey,         // 32 bytes - registered the disclosure, may close it
+    pub commitment: [u8; 32],  // 32 bytes
+    pub auditor: Pubkey,       // 32 bytes - key the blob is encrypted to
+    pub ciphertext: Vec<u8>,   // 4 + MAX_DISCLOSURE_LEN bytes
+    pub created_at: i64,       // 8 bytes
+    pub bump: u8,              // 1 byte
+}
+
+impl Disclosure {
+    pub const SPACE: usize = 8 +   // discriminator
+        32 +                        // owner
+        32 +                        // commitment
+        32 +                        // auditor
+        4 + MAX_DISCLOSURE_LEN +    // ciphertext
+        8 +                         // created_at
+        1 +                         // bump
+        16;                         // padding
+}
+
+/// Escrow that funds relayed private deposits
+/// PRIVACY: The commitment is inserted later by a relayer, authorized by an
+/// ed25519 signature of a one-time escrow key, not by the funding wallet
+///
+/// The escrow holds lamports directly; anyone may top it up with a transfer.
+/// It also funds relayed campaign donations, under its own message prefix.
+/// Once idle for `ESCROW_REFUND_DELAY_SECONDS` its owner may close it.
+#[account]
+pub struct DepositEscrow {
+    pub authority: Pubkey,     // 32 bytes - one-time key that signs deposit authorizations
+    pub nonce: u64,            // 8 bytes - replay protection for authorizations
+    pub bump: u8,              // 1 byte
+    pub owner: Pubkey,         // 32 bytes - wallet that opened the escrow, refunded on close
+    pub last_used_at: i64,     // 8 bytes - when it was opened or last released funds
+}
+
+impl DepositEscrow {
+    pub const SPACE: usize = 8 +   // discriminator
+        32 +                        // authority
+        8 +                         // nonce
+        1 +                         // bump
+        32 +                        // owner
+        8 +                         // last_used_at
+        16;                         // padding
+
+    /// Whether the owner may close the escrow at `now`
+    pub fn refundable(&self, now: i64) -> bool {
+        now >= self.last_used_at.saturating_add(ESCROW_REFUND_DELAY_SECONDS)
+    }
+
+    /// Message the escrow authority signs to release one deposit
+    pub fn deposit_message(escrow: &Pubkey, nonce: u64, commitment: &[u8; 32], amount: u64, valid_until: i64) -> Vec<u8> {
+        let mut message = Vec::with_capacity(8 + 32 + 8 + 32 + 8 + 8);
+        message.extend_from_slice(b"deposit:");
+        message.extend_from_slice(escrow.as_ref());
+        message.extend_from_slice(&nonce.to_le_bytes());
+        message.extend_from_slice(commitment);
+        message.extend_from_slice(&amount.to_le_bytes());
+        message.extend_from_slice(&valid_until.to_le_bytes());
+        message
+    }
+
+    /// Message the escrow authority signs to release one campaign donation
+    pub fn donate_message(escrow: &Pubkey, nonce: u64, campaign: &Pubkey, amount: u64, valid_until: i64) -> Vec<u8> {
+        let mut message = Vec::with_capacity(7 + 32 + 8 + 32 + 8 + 8);
+        message.extend_from_slice(b"donate:");
+        message.extend_from_slice(escrow.as_ref());
+        message.extend_from_slice(&nonce.to_le_bytes());
+        message.extend_from_slice(campaign.as_ref());
+        message.extend_from_slice(&amount.to_le_bytes());
+        message.extend_from_slice(&valid_until.to_le_bytes());
+        message
+    }
+}
+
+/// Escrow that pays the rent of accounts relayers create for a user
+/// PRIVACY: Without it the pending withdrawal or nullifier account would be
+/// funded by some wallet of the user's; here the relayer fronts the rent and
+/// draws it back from an escrow topped up anonymously
+///
+/// Each draw is authorized by the escrow key for one specific account.
+/// Once idle for `ESCROW_REFUND_DELAY_SECONDS` its owner may close it.
+#[account]
+pub struct RentEscrow {
+    pub authority: Pubkey,     // 32 bytes - one-time key that signs rent authorizations
+    pub nonce: u64,            // 8 bytes - replay protection for authorizations
+    pub bump: u8,              // 1 byte
+    pub owner: Pubkey,         // 32 bytes - wallet that opened the escrow, refunded on close
+    pub last_used_at: i64,     // 8 bytes - when it was opened or last paid rent
+}
+
+impl RentEscrow {
+    pub const SPACE: usize = 8 +   // discriminator
+        32 +                        // authority
+        8 +                         // nonce
+        1 +                         // bump
+        32 +                        // owner
+        8 +                         // last_used_at
+        16;                         // padding
+
+    /// Whether the owner may close the escrow at `now`
+    pub fn refundable(&self, now: i64) -> bool {
+        now >= self.last_used_at.saturating_add(ESCROW_REFUND_DELAY_SECONDS)
+    }
+
+    /// Message the escrow authority signs to pay the rent of `account`
+    pub fn rent_message(escrow: &Pubkey, nonce: u64, account: &Pubkey, valid_until: i64) -> Vec<u8> {
+        let mut message = Vec::with_capacity(5 + 32 + 8 + 32 + 8);
+        message.extend_from_slice(b"rent:");
+        message.extend_from_slice(escrow.as_ref());
+        message.extend_from_slice(&nonce.to_le_bytes());
+        message.extend_from_slice(account.as_ref());
+        message.extend_from_slice(&valid_until.to_le_bytes());
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_note_headers() {
+        let header = NoteHeader::current(ALLOWED_AMOUNTS[1]).unwrap();
+        assert_eq!(header.encode(), [NOTE_VERSION, 1, NOTE_SCHEME_SHA256]);
+        assert_eq!(header.amount(), ALLOWED_AMOUNTS[1]);
+        assert_eq!(header.class(), 1);
+
+        assert_eq!(NoteHeader::pedersen().class(), DENOMINATION_COUNT);
+        assert!(NoteHeader::with_version(NOTE_VERSION + 1, ALLOWED_AMOUNTS[1]).is_err());
+        assert!(NoteHeader::current(ALLOWED_AMOUNTS[1] + 1).is_err());
+        assert!(NoteHeader::with_scheme(NOTE_SCHEME_PEDERSEN, ALLOWED_AMOUNTS[1]).is_err());
+    }
+
+    #[test]
+    fn hashes_leaves_by_note_version() {
+        let commitment = [5u8; 32];
+        let amount = ALLOWED_AMOUNTS[2];
+        let leaf = |version| CommitmentTree::leaf(&NoteHeader::with_version(version, amount).unwrap(), &commitment);
+
+        assert_eq!(leaf(0), hashv(&[&commitment, &amount.to_le_bytes()]).to_bytes());
+        assert_eq!(leaf(1), hashv(&[&[1, 2, NOTE_SCHEME_SHA256], &commitment]).to_bytes());
+        assert_eq!(leaf(2), hashv(&[LEAF_DOMAIN, &[2, 2, NOTE_SCHEME_SHA256], &commitment]).to_bytes());
+        assert_ne!(leaf(1), leaf(2));
+    }
+
+    #[test]
+    fn verifies_inserted_leaves() {
+        let mut tree: CommitmentTree = bytemuck::Zeroable::zeroed();
+        tree.initialize(2, 3, 255);
+        let leaves = [[1u8; 32], [2u8; 32]];
+        for leaf in leaves {
+            tree.insert(leaf).unwrap();
+        }
+
+        let proof = MerkleProof {
+            root: tree.current_root(),
+            leaf_index: 1,
+            path: vec![leaves[0], tree.zeros[1], tree.zeros[2]],
+        };
+        assert!(tree.verify(leaves[1], &proof).is_ok());
+        assert!(tree.verify(leaves[0], &proof).is_err());
+        assert!(tree.verify(leaves[1], &MerkleProof { leaf_index: 2, ..proof.clone() }).is_err());
+        assert!(tree.verify(leaves[1], &MerkleProof { root: [9; 32], ..proof }).is_err());
+    }
+
+    #[test]
+    fn derives_owned_note_secrets() {
+        let (owner, delegate, salt) = (Pubkey::new_unique(), Pubkey::new_unique(), [3u8; 32]);
+
+        let ed25519 = NoteOwnerKey::Ed25519 { owner, salt };
+        assert_eq!(ed25519.secret_hash(), hashv(&[b"ed25519-owner", owner.as_ref(), &salt]).to_bytes());
+
+        let secp256k1 = NoteOwnerKey::Secp256k1(Secp256k1NoteOwner { eth_address: [4; 20], salt });
+        assert_eq!(secp256k1.secret_hash(), hashv(&[b"secp256k1-owner", &[4; 20], &salt]).to_bytes());
+
+        let spend = DelegatedSpend { owner, delegate, salt, by_delegate: true };
+        assert_eq!(NoteOwnerKey::Delegated(spend).secret_hash(), spend.secret_hash());
+        assert_ne!(ed25519.secret_hash(), NoteOwnerKey::Delegated(spend).secret_hash());
+    }
 }
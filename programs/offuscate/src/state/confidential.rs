@@ -0,0 +1,62 @@
+//! Confidential Pool State
+//!
+//! Token-2022 pool variant where balances are encrypted end-to-end:
+//! - ConfidentialPool: Pool config and aggregate counters for one mint
+//! - ConfidentialWithdrawRequest: A destination owner's consent to one withdrawal
+
+use anchor_lang::prelude::*;
+
+/// A pool backed by a Token-2022 mint with the confidential transfer extension
+///
+/// PRIVACY: Deposits arrive as confidential transfers into the vault token
+/// account, so amounts are never visible on-chain. Moving funds out of the
+/// vault requires proofs over its encrypted balance, so the `authority`
+/// (operator) holds the vault's ElGamal/AE keys and co-signs withdrawals.
+/// It can only pay destinations whose owner opened a withdraw request at
+/// least CONFIDENTIAL_WITHDRAW_DELAY_SECONDS earlier; since the operator can
+/// decrypt the vault, the pool stays custodial beyond that.
+#[account]
+pub struct ConfidentialPool {
+    pub authority: Pubkey,      // 32 bytes - operator holding the vault decryption keys
+    pub mint: Pubkey,           // 32 bytes - Token-2022 mint with confidential transfers
+    pub vault: Pubkey,          // 32 bytes - pool-owned confidential token account
+    pub apply_count: u64,       // 8 bytes - pending balance applications
+    pub withdraw_count: u64,    // 8 bytes - confidential withdrawals
+    pub bump: u8,               // 1 byte
+    pub vault_bump: u8,         // 1 byte
+}
+
+impl ConfidentialPool {
+    pub const SPACE: usize = 8 +    // discriminator
+        32 +                         // authority
+        32 +                         // mint
+        32 +                         // vault
+        8 +                          // apply_count
+        8 +                          // withdraw_count
+        1 +                          // bump
+        1 +                          // vault_bump
+        32;                          // padding
+}
+
+/// Consent of a destination owner to receive one confidential withdrawal
+///
+/// Opened by the owner, consumed (and closed back to the owner) by the
+/// withdrawal it authorizes.
+#[account]
+pub struct ConfidentialWithdrawRequest {
+    pub pool: Pubkey,           // 32 bytes - confidential pool paying out
+    pub destination: Pubkey,    // 32 bytes - confidential token account to credit
+    pub owner: Pubkey,          // 32 bytes - destination owner, receives the rent back
+    pub available_at: i64,      // 8 bytes - earliest withdrawal time
+    pub bump: u8,               // 1 byte
+}
+
+impl ConfidentialWithdrawRequest {
+    pub const SPACE: usize = 8 +    // discriminator
+        32 +                         // pool
+        32 +                         // destination
+        32 +                         // owner
+        8 +                          // available_at
+        1 +                          // bump
+        16;                          // padding
+}
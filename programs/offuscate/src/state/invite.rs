@@ -5,6 +5,7 @@
 //! - InviteStatus: Invite lifecycle status
 
 use anchor_lang::prelude::*;
+use crate::constants::INVITE_EXPIRY_SECONDS;
 
 /// Invite status enum
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -12,6 +13,7 @@ pub enum InviteStatus {
     Pending,
     Accepted,
     Revoked,
+    Expired,
 }
 
 /// Invite account - stores invitation for a recipient to join a payroll batch
@@ -42,4 +44,10 @@ impl Invite {
         8 +                         // accepted_at
         1 +                         // bump
         32;                         // padding
+
+    /// Pending, but too old to be accepted at `now`
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.status == InviteStatus::Pending
+            && now >= self.created_at.saturating_add(INVITE_EXPIRY_SECONDS)
+    }
 }
@@ -1,12 +1,13 @@
 //! State module - Account structures for Offuscate
 //!
 //! Organized by feature:
-//! - privacy_pool: Privacy pool, pending withdrawals, churn vaults
+//! - privacy_pool: Privacy pool, pool shards, pending withdrawals, churn vaults
 //! - campaign: Campaigns and stealth registry
 //! - commitment: Commitment-based privacy (ZK-like)
 //! - invite: Invite system for onboarding
 //! - payroll: Streaming payroll (master vault, batches, employees)
 //! - receipt: Anonymous payment receipts
+//! - confidential: Token-2022 confidential transfer pool
 
 pub mod privacy_pool;
 pub mod campaign;
@@ -14,6 +15,7 @@ pub mod commitment;
 pub mod invite;
 pub mod payroll;
 pub mod receipt;
+pub mod confidential;
 
 // Re-export all state types
 pub use privacy_pool::*;
@@ -22,3 +24,4 @@ pub use commitment::*;
 pub use invite::*;
 pub use payroll::*;
 pub use receipt::*;
+pub use confidential::*;
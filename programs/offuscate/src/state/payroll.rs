@@ -2,11 +2,115 @@
 //!
 //! Index-based payroll for enhanced privacy:
 //! - MasterVault: Global singleton tracking all indices
-//! - PayrollBatch: Batch of employees
-//! - Employee: Individual employee with streaming salary
+//! - PayrollBatch: Zero-copy batch of employees, with a SOL vault and optional SPL token vaults
+//! - Employee: Zero-copy individual employee with streaming salary
 //! - BatchStatus/EmployeeStatus: Status enums
+//! - PaySchedule: How a batch releases accrued salary
+//! - RateChange: A scheduled raise or rate ramp step
+//! - PayoutSplit: A fixed share of every claim owed to a third party
+//! - ClaimRecord: One entry of an employee's bucketed claim history
+//! - SalaryCurrency/UsdPrice: Salary rate units and their SOL conversion
+//! - BatchRole: Scoped permissions for a secondary batch admin
+//! - EarningSnapshot: An employee's earning totals, once per month
+//! - RosterPage: Zero-copy index -> (wallet, status) page for enumeration
+//! - LegacyPayrollBatch/LegacyEmployee: The Borsh layouts, read once to migrate
 
 use anchor_lang::prelude::*;
+use crate::constants::{CLAIM_HISTORY_LEN, MAX_PAYOUT_SPLITS, MAX_RATE_CHANGES, ROSTER_PAGE_SIZE};
+use crate::errors::ErrorCode;
+
+/// Unit a batch's salary rates are denominated in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SalaryCurrency {
+    /// Lamports per second
+    Lamports,
+    /// USD cents per second, converted at claim time with a Pyth SOL/USD price
+    UsdCents,
+}
+
+impl SalaryCurrency {
+    fn from_code(code: u8) -> Result<Self> {
+        match code {
+            0 => Ok(Self::Lamports),
+            1 => Ok(Self::UsdCents),
+            _ => err!(ErrorCode::InvalidStateCode),
+        }
+    }
+}
+
+/// A SOL/USD price: one SOL is worth `price * 10^exponent` USD
+#[derive(Clone, Copy)]
+pub struct UsdPrice {
+    pub price: u64,
+    pub exponent: i32,
+}
+
+impl UsdPrice {
+    /// (numerator, denominator) turning cents into lamports:
+    /// lamports = cents * 10^7 / (price * 10^exponent)
+    fn cents_to_lamports(&self) -> Result<(u128, u128)> {
+        let shift = 7i32.checked_sub(self.exponent).ok_or(ErrorCode::Overflow)?;
+        let scale = 10u128.checked_pow(shift.unsigned_abs()).ok_or(ErrorCode::Overflow)?;
+        let price = u128::from(self.price);
+        Ok(if shift >= 0 {
+            (scale, price)
+        } else {
+            (1, price.checked_mul(scale).ok_or(ErrorCode::Overflow)?)
+        })
+    }
+
+    /// Lamports worth `cents`, rounded down
+    pub fn to_lamports(&self, cents: u64) -> Result<u64> {
+        let (numerator, denominator) = self.cents_to_lamports()?;
+        let lamports = u128::from(cents).checked_mul(numerator).ok_or(ErrorCode::Overflow)? / denominator;
+        Ok(u64::try_from(lamports).map_err(|_| ErrorCode::Overflow)?)
+    }
+
+    /// Cents worth `lamports`, rounded up
+    pub fn to_cents_ceil(&self, lamports: u64) -> Result<u64> {
+        let (numerator, denominator) = self.cents_to_lamports()?;
+        let cents = u128::from(lamports).checked_mul(denominator).ok_or(ErrorCode::Overflow)?
+            .div_ceil(numerator);
+        Ok(u64::try_from(cents).map_err(|_| ErrorCode::Overflow)?)
+    }
+}
+
+/// Salary rate that takes effect at `effective_at`
+#[zero_copy]
+pub struct RateChange {
+    pub effective_at: i64,
+    pub rate: u64,
+}
+
+/// Share of every salary claim paid to `recipient`, e.g. a garnishment
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PayoutSplit {
+    pub recipient: Pubkey,
+    pub bps: u16,
+}
+
+impl PayoutSplit {
+    /// Part of a claimed `amount` owed to the recipient, rounded down
+    pub fn share(&self, amount: u64) -> u64 {
+        (u128::from(amount) * u128::from(self.bps) / 10_000) as u64
+    }
+}
+
+/// A past claim, with its amount reduced to an order of magnitude
+#[zero_copy]
+pub struct ClaimRecord {
+    pub timestamp: i64,
+    /// Number of decimal digits of the amount: the claim was at least
+    /// 10^(bucket - 1) and below 10^bucket (0 = empty slot)
+    pub amount_bucket: u8,
+    pub _reserved: [u8; 7],
+}
+
+impl ClaimRecord {
+    pub fn bucket(amount: u64) -> u8 {
+        amount.checked_ilog10().map_or(0, |digits| digits as u8 + 1)
+    }
+}
 
 /// Batch status enum
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -16,16 +120,66 @@ pub enum BatchStatus {
     Closed,
 }
 
+impl BatchStatus {
+    fn from_code(code: u8) -> Result<Self> {
+        match code {
+            0 => Ok(Self::Active),
+            1 => Ok(Self::Paused),
+            2 => Ok(Self::Closed),
+            _ => err!(ErrorCode::InvalidStateCode),
+        }
+    }
+}
+
+/// When accrued salary becomes claimable
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PaySchedule {
+    /// Every second, as it accrues
+    Streaming,
+    /// Up to the latest payday at `payday_offset + k * pay_period_seconds`
+    Periodic,
+    /// Up to the latest payday, `payday_offset` seconds into each calendar month (UTC)
+    Monthly,
+}
+
+impl PaySchedule {
+    fn from_code(code: u8) -> Result<Self> {
+        match code {
+            0 => Ok(Self::Streaming),
+            1 => Ok(Self::Periodic),
+            2 => Ok(Self::Monthly),
+            _ => err!(ErrorCode::InvalidStateCode),
+        }
+    }
+}
+
 /// Employee status enum
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum EmployeeStatus {
     Active,
     Paused,
     Terminated,
+    /// Disputed: salary keeps accruing but cannot be claimed until the hold is lifted
+    OnHold,
+}
+
+impl EmployeeStatus {
+    fn from_code(code: u8) -> Result<Self> {
+        match code {
+            0 => Ok(Self::Active),
+            1 => Ok(Self::Paused),
+            2 => Ok(Self::Terminated),
+            3 => Ok(Self::OnHold),
+            _ => err!(ErrorCode::InvalidStateCode),
+        }
+    }
 }
 
 /// Master Vault - Global singleton that tracks all indices
 /// This hides organizational relationships by using sequential indices
+///
+/// `authority` controls global payroll settings and can be handed over in
+/// two steps, e.g. to a Squads multisig vault.
 #[account]
 pub struct MasterVault {
     pub authority: Pubkey,          // 32 bytes - who can modify
@@ -34,6 +188,9 @@ pub struct MasterVault {
     pub total_deposited: u64,       // 8 bytes - total deposited
     pub total_paid: u64,            // 8 bytes - total paid out
     pub bump: u8,                   // 1 byte
+    pub closed_batches: u32,        // 4 bytes - batches closed so far (indices are never reused)
+    pub pending_authority: Pubkey,  // 32 bytes - proposed new authority (default = none)
+    pub paused: bool,               // 1 byte - no new batches or funding while set
 }
 
 impl MasterVault {
@@ -44,59 +201,860 @@ impl MasterVault {
         8 +                          // total_deposited
         8 +                          // total_paid
         1 +                          // bump
+        4 +                          // closed_batches
+        32 +                         // pending_authority
+        1 +                          // paused
         32;                          // padding
+
+    /// Reject new batches and funding while payroll is paused; claims stay
+    /// open, so employees are never locked out of what they earned
+    pub fn require_not_paused(&self) -> Result<()> {
+        require!(!self.paused, ErrorCode::PayrollPaused);
+        Ok(())
+    }
 }
 
 /// PayrollBatch - Index-based PDA (no pubkey or name in seeds)
 /// Seeds: ["batch", master_vault, index]
-#[account]
+///
+/// Zero-copy, like Employee, so claims read the fields they touch in place
+/// instead of deserializing the whole account. Enums are stored as their
+/// variant index and read through accessors.
+///
+/// The discriminator is the one Anchor derives for `PayrollBatchV2`, so a
+/// batch still in the Borsh layout fails to load instead of being misread
+/// until `migrate_payroll_batch` converts it.
+#[account(zero_copy, discriminator = [130, 233, 31, 6, 194, 30, 4, 241])]
 pub struct PayrollBatch {
-    pub master_vault: Pubkey,       // 32 bytes - reference to master vault
-    pub owner: Pubkey,              // 32 bytes - company wallet
-    pub index: u32,                 // 4 bytes - sequential index
-    pub title: String,              // 4 + 64 = 68 bytes - batch name
-    pub employee_count: u32,        // 4 bytes - number of employees
     pub total_budget: u64,          // 8 bytes - total budget allocated
     pub total_paid: u64,            // 8 bytes - total paid out
     pub created_at: i64,            // 8 bytes
-    pub status: BatchStatus,        // 1 byte
+    pub pay_period_seconds: i64,    // 8 bytes - Periodic: length of a pay period
+    pub payday_offset: i64,         // 8 bytes - Periodic: first payday; Monthly: offset into the month
+    pub allocated_budget: u64,      // 8 bytes - vault lamports set aside for employees, not yet claimed
+    pub paused_at: i64,             // 8 bytes - when the batch stopped being Active (0 = Active)
+    pub paused_seconds: i64,        // 8 bytes - total length of finished pauses
+    pub remaining_budget: u64,      // 8 bytes - funded and not yet paid out
+    pub crank_tip: u64,             // 8 bytes - lamports paid to keepers per crank job
+    pub min_claim_interval: i64,    // 8 bytes - shortest time between two payouts to one employee
+    pub master_vault: Pubkey,       // 32 bytes - reference to master vault
+    pub owner: Pubkey,              // 32 bytes - company wallet
+    pub pending_owner: Pubkey,      // 32 bytes - proposed new owner (default = none)
+    pub price_feed_id: [u8; 32],    // 32 bytes - UsdCents: Pyth SOL/USD feed to convert with
+    pub title: [u8; 64],            // 64 bytes - batch name, UTF-8, first title_len bytes
+    pub index: u32,                 // 4 bytes - sequential index
+    pub employee_count: u32,        // 4 bytes - number of current employees
+    pub removed_count: u32,         // 4 bytes - employees whose accounts were closed
+    pub title_len: u8,              // 1 byte
+    pub status: u8,                 // 1 byte - BatchStatus
     pub vault_bump: u8,             // 1 byte
     pub batch_bump: u8,             // 1 byte
+    pub pay_schedule: u8,           // 1 byte - PaySchedule
+    pub salary_currency: u8,        // 1 byte - SalaryCurrency, unit of employee salary rates
+    pub token_vault_count: u8,      // 1 byte - open SPL token vaults, one per mint
+    pub _reserved: [u8; 5],         // 5 bytes - explicit alignment padding
 }
 
 impl PayrollBatch {
     pub const SPACE: usize = 8 +    // discriminator
+        8 +                          // total_budget
+        8 +                          // total_paid
+        8 +                          // created_at
+        8 +                          // pay_period_seconds
+        8 +                          // payday_offset
+        8 +                          // allocated_budget
+        8 +                          // paused_at
+        8 +                          // paused_seconds
+        8 +                          // remaining_budget
+        8 +                          // crank_tip
+        8 +                          // min_claim_interval
         32 +                         // master_vault
         32 +                         // owner
+        32 +                         // pending_owner
+        32 +                         // price_feed_id
+        64 +                         // title
         4 +                          // index
-        (4 + 64) +                   // title
         4 +                          // employee_count
-        8 +                          // total_budget
-        8 +                          // total_paid
-        8 +                          // created_at
+        4 +                          // removed_count
+        1 +                          // title_len
         1 +                          // status
         1 +                          // vault_bump
         1 +                          // batch_bump
+        1 +                          // pay_schedule
+        1 +                          // salary_currency
+        1 +                          // token_vault_count
+        5 +                          // _reserved
         32;                          // padding
+
+    pub fn status(&self) -> Result<BatchStatus> {
+        BatchStatus::from_code(self.status)
+    }
+
+    pub fn pay_schedule(&self) -> Result<PaySchedule> {
+        PaySchedule::from_code(self.pay_schedule)
+    }
+
+    pub fn salary_currency(&self) -> Result<SalaryCurrency> {
+        SalaryCurrency::from_code(self.salary_currency)
+    }
+
+    pub fn title(&self) -> String {
+        String::from_utf8_lossy(&self.title[..self.title_len as usize]).into_owned()
+    }
+
+    pub fn set_title(&mut self, title: &str) -> Result<()> {
+        require!(title.len() <= self.title.len(), ErrorCode::TitleTooLong);
+        self.title = [0u8; 64];
+        self.title[..title.len()].copy_from_slice(title.as_bytes());
+        self.title_len = title.len() as u8;
+        Ok(())
+    }
+
+    /// Index of the next employee added; closed employees keep their index
+    pub fn next_employee_index(&self) -> u32 {
+        self.employee_count.saturating_add(self.removed_count)
+    }
+
+    /// Whether an employee last paid at `last_paid_at` may be paid again at `now`
+    pub fn claim_interval_elapsed(&self, last_paid_at: i64, now: i64) -> bool {
+        last_paid_at == 0 || now >= last_paid_at.saturating_add(self.min_claim_interval)
+    }
+
+    /// Time salary has accrued up to at `now`; frozen while the batch is paused
+    pub fn accrual_end(&self, now: i64) -> i64 {
+        if self.paused_at != 0 { self.paused_at.min(now) } else { now }
+    }
+
+    /// Change status, freezing accrual for every employee on pause and
+    /// resuming it on reactivation
+    pub fn set_status(&mut self, status: BatchStatus, now: i64) -> Result<()> {
+        require!(self.status()? != BatchStatus::Closed, ErrorCode::BatchNotActive);
+        if status != BatchStatus::Active && self.paused_at == 0 {
+            self.paused_at = now;
+        } else if status == BatchStatus::Active && self.paused_at != 0 {
+            let paused_for = now.checked_sub(self.paused_at).ok_or(ErrorCode::Overflow)?;
+            self.paused_seconds = self.paused_seconds.checked_add(paused_for)
+                .ok_or(ErrorCode::Overflow)?;
+            self.paused_at = 0;
+        }
+        self.status = status as u8;
+        Ok(())
+    }
+
+    /// Time up to which salary may be claimed at `now`: the latest payday
+    pub fn accrual_cutoff(&self, now: i64) -> Result<i64> {
+        let now = self.accrual_end(now);
+        Ok(match self.pay_schedule()? {
+            PaySchedule::Streaming => now,
+            PaySchedule::Periodic => {
+                let periods = now.saturating_sub(self.payday_offset).div_euclid(self.pay_period_seconds);
+                self.payday_offset.saturating_add(periods.saturating_mul(self.pay_period_seconds))
+            }
+            PaySchedule::Monthly => {
+                let (year, month) = month_of(now);
+                let payday = month_start(year, month) + self.payday_offset;
+                if payday <= now {
+                    payday
+                } else if month == 1 {
+                    month_start(year - 1, 12) + self.payday_offset
+                } else {
+                    month_start(year, month - 1) + self.payday_offset
+                }
+            }
+        })
+    }
+}
+
+/// Return data of `get_batch_runway`
+///
+/// Covers the employees passed in; `depletes_at` is 0 when nothing accrues.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct BatchRunway {
+    pub remaining_budget: u64,  // lamports funded and not yet paid out
+    pub outstanding: u64,       // accrued salary not yet claimed
+    pub burn_rate: u64,         // lamports per second across active employees
+    pub seconds_remaining: i64, // until the budget no longer covers accrual
+    pub depletes_at: i64,       // unix timestamp of depletion (0 = never)
+}
+
+/// BatchRole - Permissions the batch owner granted a secondary admin
+/// Seeds: ["batch_role", batch, admin]
+///
+/// Roles never cover withdrawing or closing; those stay with the owner.
+#[account]
+pub struct BatchRole {
+    pub batch: Pubkey,              // 32 bytes - batch the role applies to
+    pub admin: Pubkey,              // 32 bytes - wallet holding the role
+    pub permissions: u8,            // 1 byte - bitmask of BatchRole::* permissions
+    pub bump: u8,                   // 1 byte
+}
+
+impl BatchRole {
+    pub const SPACE: usize = 8 +    // discriminator
+        32 +                         // batch
+        32 +                         // admin
+        1 +                          // permissions
+        1 +                          // bump
+        16;                          // padding
+
+    /// Add employees to the batch
+    pub const ADD_EMPLOYEES: u8 = 1 << 0;
+    /// Allocate vault funds to employee budgets
+    pub const FUND: u8 = 1 << 1;
+    /// Change employee salary rates
+    pub const UPDATE_RATES: u8 = 1 << 2;
+    pub const ALL: u8 = Self::ADD_EMPLOYEES | Self::FUND | Self::UPDATE_RATES;
+
+    /// Whether this role lets `admin` use `permission` on `batch`
+    pub fn allows(&self, batch: &Pubkey, admin: &Pubkey, permission: u8) -> bool {
+        self.batch == *batch && self.admin == *admin && self.permissions & permission == permission
+    }
+}
+
+/// EarningSnapshot - An employee's earnings as of a monthly snapshot
+/// Seeds: ["earning_snapshot", employee, period]
+///
+/// Taken once per month, by anyone; the difference between two consecutive
+/// snapshots is what was earned and claimed in between.
+#[account]
+pub struct EarningSnapshot {
+    pub employee: Pubkey,           // 32 bytes
+    pub period: u32,                // 4 bytes - year * 100 + month, UTC
+    pub accrued: u64,               // 8 bytes - earned but unclaimed, in the batch's salary unit
+    pub total_claimed: u64,         // 8 bytes - lamports claimed up to the snapshot
+    pub salary_rate: u64,           // 8 bytes - rate in effect at the snapshot
+    pub taken_at: i64,              // 8 bytes
+    pub bump: u8,                   // 1 byte
+}
+
+impl EarningSnapshot {
+    pub const SPACE: usize = 8 +    // discriminator
+        32 +                         // employee
+        4 +                          // period
+        8 +                          // accrued
+        8 +                          // total_claimed
+        8 +                          // salary_rate
+        8 +                          // taken_at
+        1 +                          // bump
+        16;                          // padding
+
+    /// Snapshot period a timestamp falls in: year * 100 + month, UTC
+    pub fn period_of(timestamp: i64) -> u32 {
+        let (year, month) = month_of(timestamp);
+        (year * 100 + month) as u32
+    }
+}
+
+/// RosterPage - Wallet and status of ROSTER_PAGE_SIZE consecutive employees
+/// Seeds: ["roster", batch, page]
+///
+/// Employee `index` sits in page `index / ROSTER_PAGE_SIZE`, so clients can
+/// list a whole batch by reading its pages instead of deriving every PDA.
+#[account(zero_copy)]
+pub struct RosterPage {
+    pub wallets: [Pubkey; ROSTER_PAGE_SIZE], // 4096 bytes - employee wallet per slot
+    pub statuses: [u8; ROSTER_PAGE_SIZE],    // 128 bytes - RosterPage::* status per slot
+    pub batch: Pubkey,             // 32 bytes
+    pub page: u32,                 // 4 bytes
+    pub bump: u8,                  // 1 byte
+    pub _reserved: [u8; 3],        // 3 bytes - explicit alignment padding
+}
+
+impl RosterPage {
+    pub const SPACE: usize = 8 +  // discriminator
+        32 * ROSTER_PAGE_SIZE +    // wallets
+        ROSTER_PAGE_SIZE +         // statuses
+        32 +                       // batch
+        4 +                        // page
+        1 +                        // bump
+        3 +                        // _reserved
+        16;                        // padding
+
+    pub const EMPTY: u8 = 0;
+    pub const ACTIVE: u8 = 1;
+    pub const PAUSED: u8 = 2;
+    pub const TERMINATED: u8 = 3;
+    /// The employee account was closed or moved to another batch
+    pub const REMOVED: u8 = 4;
+    pub const ON_HOLD: u8 = 5;
+
+    /// Page holding employee `index`
+    pub fn page_of(index: u32) -> u32 {
+        index / ROSTER_PAGE_SIZE as u32
+    }
+
+    pub fn status_code(status: EmployeeStatus) -> u8 {
+        match status {
+            EmployeeStatus::Active => Self::ACTIVE,
+            EmployeeStatus::Paused => Self::PAUSED,
+            EmployeeStatus::Terminated => Self::TERMINATED,
+            EmployeeStatus::OnHold => Self::ON_HOLD,
+        }
+    }
+
+    /// Record employee `index` as `wallet` with roster status `status`
+    pub fn record(&mut self, index: u32, wallet: Pubkey, status: u8) -> Result<()> {
+        require!(Self::page_of(index) == self.page, ErrorCode::InvalidRosterPage);
+        let slot = index as usize % ROSTER_PAGE_SIZE;
+        self.wallets[slot] = wallet;
+        self.statuses[slot] = status;
+        Ok(())
+    }
+}
+
+/// (year, month) of a unix timestamp, UTC
+fn month_of(timestamp: i64) -> (i64, i64) {
+    // Civil-from-days over 400-year eras, with years starting in March
+    let days = timestamp.div_euclid(86_400) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month)
+}
+
+/// Unix timestamp of the first second of a month, UTC
+fn month_start(year: i64, month: i64) -> i64 {
+    // Days-from-civil, the inverse of `month_of`
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    (era * 146_097 + day_of_era - 719_468) * 86_400
 }
 
 /// Employee - Index-based PDA with streaming salary
 /// Seeds: ["employee", batch, index]
-#[account]
+///
+/// Zero-copy: every claim touches an employee, and reading a fixed layout in
+/// place is far cheaper than Borsh-decoding the stealth address and friends.
+/// Variable-length data is stored as a fixed array plus a length; enums and
+/// flags are stored as u8 and read through accessors.
+///
+/// Versioned like PayrollBatch: the discriminator is Anchor's for
+/// `EmployeeV2`, and `migrate_employee` converts Borsh-layout accounts.
+#[account(zero_copy, discriminator = [156, 81, 12, 118, 55, 89, 225, 25])]
 pub struct Employee {
-    pub batch: Pubkey,              // 32 bytes - which batch
-    pub wallet: Pubkey,             // 32 bytes - employee wallet
-    pub index: u32,                 // 4 bytes - sequential index within batch
-    pub stealth_address: String,    // 4 + 200 = 204 bytes - stealth meta address
-    pub salary_rate: u64,           // 8 bytes - lamports per second
+    pub salary_rate: u64,           // 8 bytes - per second, in base units of the pay currency
     pub start_time: i64,            // 8 bytes - when salary started
     pub last_claimed_at: i64,       // 8 bytes - last claim timestamp
     pub total_claimed: u64,         // 8 bytes - total claimed so far
-    pub status: EmployeeStatus,     // 1 byte
+    pub paused_at: i64,             // 8 bytes - when accrual was halted by pause or termination (0 = accruing)
+    pub claim_nonce: u64,           // 8 bytes - replay protection for signed stealth claims
+    pub max_claim_per_period: u64,  // 8 bytes - claim cap per period (0 = uncapped)
+    pub claim_period_seconds: i64,  // 8 bytes - length of a cap period
+    pub period_start: i64,          // 8 bytes - start of the current cap period
+    pub period_claimed: u64,        // 8 bytes - claimed in the current cap period
+    pub budget_remaining: u64,      // 8 bytes - allocated budget not yet claimed
+    pub pending_accrued: u64,       // 8 bytes - earned at a previous rate, up to last_claimed_at
+    pub batch_paused_seconds: i64,  // 8 bytes - batch.paused_seconds already skipped over
+    pub held_at: i64,               // 8 bytes - OnHold: when the hold was placed
+    pub severance_paid: u64,        // 8 bytes - paid on termination, not part of total_claimed
+    pub rate_schedule: [RateChange; MAX_RATE_CHANGES], // 16 * MAX_RATE_CHANGES bytes - upcoming rates, soonest first
+    pub claim_history: [ClaimRecord; CLAIM_HISTORY_LEN], // 16 * CLAIM_HISTORY_LEN bytes - latest claims, ring buffer
+    pub batch: Pubkey,              // 32 bytes - which batch
+    pub wallet: Pubkey,             // 32 bytes - employee wallet
+    pub payout_address: Pubkey,     // 32 bytes - stealth address run_payday pays (default = none)
+    pub pay_mint: Pubkey,           // 32 bytes - SPL mint salary is paid in (default = SOL)
+    pub rent_payer: Pubkey,         // 32 bytes - paid the account's rent, refunded on close
+    pub hold_reason: [u8; 32],      // 32 bytes - OnHold: hash of the dispute reason
+    pub split_recipients: [Pubkey; MAX_PAYOUT_SPLITS], // 32 * MAX_PAYOUT_SPLITS bytes - payout split recipients
+    pub proposed_split_recipients: [Pubkey; MAX_PAYOUT_SPLITS], // 32 * MAX_PAYOUT_SPLITS bytes - awaiting acknowledgement
+    pub stealth_address: [u8; 200], // 200 bytes - stealth meta address, first stealth_address_len bytes
+    pub index: u32,                 // 4 bytes - sequential index within batch
+    pub split_bps: [u16; MAX_PAYOUT_SPLITS], // 2 * MAX_PAYOUT_SPLITS bytes - share of each claim per recipient
+    pub proposed_split_bps: [u16; MAX_PAYOUT_SPLITS], // 2 * MAX_PAYOUT_SPLITS bytes
+    pub stealth_address_len: u8,    // 1 byte
+    pub status: u8,                 // 1 byte - EmployeeStatus
     pub bump: u8,                   // 1 byte
+    pub budget_tracked: u8,         // 1 byte - claims are capped at budget_remaining
+    pub push_enabled: u8,           // 1 byte - employee lets the owner push salary to its wallet
+    pub rate_schedule_len: u8,      // 1 byte - scheduled rate changes in use
+    pub split_count: u8,            // 1 byte - payout splits in use
+    pub proposed_split_count: u8,   // 1 byte - proposed payout splits
+    pub split_proposal_pending: u8, // 1 byte - a split proposal awaits acknowledgement
+    pub claim_history_next: u8,     // 1 byte - slot the next claim is written to
+    pub _reserved: [u8; 6],         // 6 bytes - explicit alignment padding
 }
 
 impl Employee {
+    pub const SPACE: usize = 8 +    // discriminator
+        8 +                          // salary_rate
+        8 +                          // start_time
+        8 +                          // last_claimed_at
+        8 +                          // total_claimed
+        8 +                          // paused_at
+        8 +                          // claim_nonce
+        8 +                          // max_claim_per_period
+        8 +                          // claim_period_seconds
+        8 +                          // period_start
+        8 +                          // period_claimed
+        8 +                          // budget_remaining
+        8 +                          // pending_accrued
+        8 +                          // batch_paused_seconds
+        8 +                          // held_at
+        8 +                          // severance_paid
+        16 * MAX_RATE_CHANGES +      // rate_schedule
+        16 * CLAIM_HISTORY_LEN +     // claim_history
+        32 +                         // batch
+        32 +                         // wallet
+        32 +                         // payout_address
+        32 +                         // pay_mint
+        32 +                         // rent_payer
+        32 +                         // hold_reason
+        32 * MAX_PAYOUT_SPLITS +     // split_recipients
+        32 * MAX_PAYOUT_SPLITS +     // proposed_split_recipients
+        200 +                        // stealth_address
+        4 +                          // index
+        2 * MAX_PAYOUT_SPLITS +      // split_bps
+        2 * MAX_PAYOUT_SPLITS +      // proposed_split_bps
+        1 +                          // stealth_address_len
+        1 +                          // status
+        1 +                          // bump
+        1 +                          // budget_tracked
+        1 +                          // push_enabled
+        1 +                          // rate_schedule_len
+        1 +                          // split_count
+        1 +                          // proposed_split_count
+        1 +                          // split_proposal_pending
+        1 +                          // claim_history_next
+        6 +                          // _reserved
+        32;                          // padding
+
+    pub fn status(&self) -> Result<EmployeeStatus> {
+        EmployeeStatus::from_code(self.status)
+    }
+
+    pub fn budget_tracked(&self) -> bool {
+        self.budget_tracked != 0
+    }
+
+    pub fn push_enabled(&self) -> bool {
+        self.push_enabled != 0
+    }
+
+    pub fn stealth_address(&self) -> String {
+        String::from_utf8_lossy(&self.stealth_address[..self.stealth_address_len as usize]).into_owned()
+    }
+
+    pub fn set_stealth_address(&mut self, stealth_address: &str) -> Result<()> {
+        require!(stealth_address.len() <= self.stealth_address.len(), ErrorCode::MetaAddressTooLong);
+        self.stealth_address = [0u8; 200];
+        self.stealth_address[..stealth_address.len()].copy_from_slice(stealth_address.as_bytes());
+        self.stealth_address_len = stealth_address.len() as u8;
+        Ok(())
+    }
+
+    /// Upcoming rate changes, soonest first
+    pub fn rate_schedule(&self) -> &[RateChange] {
+        &self.rate_schedule[..self.rate_schedule_len as usize]
+    }
+
+    pub fn clear_rate_schedule(&mut self) {
+        self.rate_schedule_len = 0;
+    }
+
+    /// Payout splits taken out of every claim
+    pub fn payout_splits(&self) -> Vec<PayoutSplit> {
+        read_splits(&self.split_recipients, &self.split_bps, self.split_count)
+    }
+
+    pub fn has_payout_splits(&self) -> bool {
+        self.split_count != 0
+    }
+
+    /// Payout splits awaiting the employee's acknowledgement, if any
+    pub fn proposed_payout_splits(&self) -> Option<Vec<PayoutSplit>> {
+        (self.split_proposal_pending != 0)
+            .then(|| read_splits(&self.proposed_split_recipients, &self.proposed_split_bps, self.proposed_split_count))
+    }
+
+    /// Replace the pending proposal; at most MAX_PAYOUT_SPLITS splits
+    pub fn propose_payout_splits(&mut self, splits: &[PayoutSplit]) {
+        self.proposed_split_count = write_splits(&mut self.proposed_split_recipients, &mut self.proposed_split_bps, splits);
+        self.split_proposal_pending = 1;
+    }
+
+    /// Make the pending proposal the active payout splits
+    pub fn accept_proposed_payout_splits(&mut self) {
+        self.split_recipients = self.proposed_split_recipients;
+        self.split_bps = self.proposed_split_bps;
+        self.split_count = self.proposed_split_count;
+        self.split_proposal_pending = 0;
+    }
+
+    /// Message the employee wallet signs to have its salary paid to `recipient`
+    pub fn stealth_claim_message(employee: &Pubkey, recipient: &Pubkey, nonce: u64, valid_until: i64) -> Vec<u8> {
+        let mut message = Vec::with_capacity(7 + 32 + 32 + 8 + 8);
+        message.extend_from_slice(b"salary:");
+        message.extend_from_slice(employee.as_ref());
+        message.extend_from_slice(recipient.as_ref());
+        message.extend_from_slice(&nonce.to_le_bytes());
+        message.extend_from_slice(&valid_until.to_le_bytes());
+        message
+    }
+
+    /// Salary accrued from `last_claimed_at` until `cutoff`, not counting
+    /// time spent paused
+    ///
+    /// Checkpointed salary is only included once `cutoff` has reached the
+    /// checkpoint, so pay schedules still hold it until payday.
+    pub fn accrued(&self, cutoff: i64) -> Result<u64> {
+        let until = if self.paused_at != 0 { self.paused_at.min(cutoff) } else { cutoff };
+        let elapsed = until.checked_sub(self.last_claimed_at)
+            .ok_or(ErrorCode::Overflow)?
+            .max(0) as u64;
+        let pending = if cutoff >= self.last_claimed_at { self.pending_accrued } else { 0 };
+
+        self.salary_rate.checked_mul(elapsed)
+            .and_then(|streamed| streamed.checked_add(pending))
+            .ok_or(ErrorCode::Overflow.into())
+    }
+
+    /// Move salary accrued at the current rate until `now` into `pending_accrued`
+    ///
+    /// Called before a rate change, so time already worked keeps its old price.
+    pub fn checkpoint(&mut self, now: i64) -> Result<()> {
+        let until = if self.paused_at != 0 { self.paused_at.min(now) } else { now };
+        if until > self.last_claimed_at {
+            let earned = self.salary_rate.checked_mul((until - self.last_claimed_at) as u64)
+                .ok_or(ErrorCode::Overflow)?;
+            self.pending_accrued = self.pending_accrued.checked_add(earned)
+                .ok_or(ErrorCode::Overflow)?;
+            self.last_claimed_at = until;
+        }
+        Ok(())
+    }
+
+    /// Consume `amount` of the salary accrued until `cutoff`, checkpointed
+    /// salary first, then as many seconds as the rest pays for (rounded up)
+    pub fn consume_accrued(&mut self, amount: u64, cutoff: i64) -> Result<()> {
+        if amount >= self.accrued(cutoff)? {
+            self.last_claimed_at = cutoff;
+            self.pending_accrued = 0;
+            return Ok(());
+        }
+
+        let from_pending = amount.min(self.pending_accrued);
+        self.pending_accrued -= from_pending;
+        let paid_seconds = (amount - from_pending).div_ceil(self.salary_rate);
+        self.last_claimed_at = self.last_claimed_at.checked_add(paid_seconds as i64)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Who gets the account's rent back on close; accounts created before
+    /// `rent_payer` was recorded refund `fallback`
+    pub fn rent_refund_to(&self, fallback: &Pubkey) -> Pubkey {
+        if self.rent_payer == Pubkey::default() { *fallback } else { self.rent_payer }
+    }
+
+    /// Whether salary is paid in SOL from the batch vault, rather than from
+    /// one of the batch's token vaults
+    pub fn paid_in_sol(&self) -> bool {
+        self.pay_mint == Pubkey::default()
+    }
+
+    /// Most that may still be claimed in the cap period running at `now`
+    pub fn claim_allowance(&self, now: i64) -> u64 {
+        if self.max_claim_per_period == 0 {
+            u64::MAX
+        } else if now >= self.period_start.saturating_add(self.claim_period_seconds) {
+            self.max_claim_per_period
+        } else {
+            self.max_claim_per_period.saturating_sub(self.period_claimed)
+        }
+    }
+
+    /// Count `amount` against the cap period running at `now`, starting a
+    /// new period once the current one is over
+    pub fn record_period_claim(&mut self, amount: u64, now: i64) -> Result<()> {
+        require!(amount <= self.claim_allowance(now), ErrorCode::ClaimCapExceeded);
+        if now >= self.period_start.saturating_add(self.claim_period_seconds) {
+            self.period_start = now;
+            self.period_claimed = 0;
+        }
+        self.period_claimed = self.period_claimed.saturating_add(amount);
+        Ok(())
+    }
+
+    /// Add a claim of `amount` at `now` to the history, overwriting the oldest
+    pub fn record_claim_history(&mut self, amount: u64, now: i64) {
+        let slot = self.claim_history_next as usize % CLAIM_HISTORY_LEN;
+        self.claim_history[slot] = ClaimRecord {
+            timestamp: now,
+            amount_bucket: ClaimRecord::bucket(amount),
+            _reserved: [0u8; 7],
+        };
+        self.claim_history_next = ((slot + 1) % CLAIM_HISTORY_LEN) as u8;
+    }
+
+    /// When the employee was last paid, from the claim history (0 = never)
+    pub fn last_paid_at(&self) -> i64 {
+        let latest = (self.claim_history_next as usize + CLAIM_HISTORY_LEN - 1) % CLAIM_HISTORY_LEN;
+        self.claim_history[latest].timestamp
+    }
+
+    /// Most the employee's allocated budget can still pay
+    pub fn budget_share(&self) -> u64 {
+        if self.budget_tracked() { self.budget_remaining } else { u64::MAX }
+    }
+
+    /// Skip the batch pauses that ended since the last sync, so they are
+    /// never paid for
+    pub fn sync_batch_pause(&mut self, batch: &PayrollBatch) -> Result<()> {
+        let skipped = batch.paused_seconds.checked_sub(self.batch_paused_seconds)
+            .ok_or(ErrorCode::Overflow)?;
+        if skipped > 0 {
+            // Shifting both ends keeps an own pause the same length
+            self.last_claimed_at = self.last_claimed_at.checked_add(skipped)
+                .ok_or(ErrorCode::Overflow)?;
+            if self.paused_at != 0 {
+                self.paused_at = self.paused_at.checked_add(skipped)
+                    .ok_or(ErrorCode::Overflow)?;
+            }
+            self.batch_paused_seconds = batch.paused_seconds;
+        }
+        Ok(())
+    }
+
+    /// Bring the employee up to date with `batch` at `now`: skip finished
+    /// batch pauses, then apply the scheduled rates that are due
+    ///
+    /// Must run before any accrual is computed against `batch`.
+    pub fn catch_up(&mut self, batch: &PayrollBatch, now: i64) -> Result<()> {
+        self.sync_batch_pause(batch)?;
+        self.apply_rate_schedule(batch.accrual_end(now))
+    }
+
+    /// Switch to every scheduled rate due by `now`, pricing the time before
+    /// each change at the rate it replaces
+    pub fn apply_rate_schedule(&mut self, now: i64) -> Result<()> {
+        let due = self.rate_schedule().iter().take_while(|change| change.effective_at <= now).count();
+        for position in 0..due {
+            let change = self.rate_schedule[position];
+            self.checkpoint(change.effective_at)?;
+            self.salary_rate = change.rate;
+        }
+        let len = self.rate_schedule_len as usize;
+        self.rate_schedule.copy_within(due..len, 0);
+        self.rate_schedule_len -= due as u8;
+        Ok(())
+    }
+
+    /// Queue `change`, keeping the schedule ordered by effective time
+    pub fn schedule_rate(&mut self, change: RateChange) -> Result<()> {
+        let len = self.rate_schedule_len as usize;
+        require!(len < MAX_RATE_CHANGES, ErrorCode::RateScheduleFull);
+        let position = self.rate_schedule().iter()
+            .position(|scheduled| scheduled.effective_at > change.effective_at)
+            .unwrap_or(len);
+        self.rate_schedule.copy_within(position..len, position + 1);
+        self.rate_schedule[position] = change;
+        self.rate_schedule_len += 1;
+        Ok(())
+    }
+
+    /// Change status, halting accrual on pause or termination and resuming
+    /// it on reactivation; a hold keeps accruing
+    pub fn set_status(&mut self, status: EmployeeStatus, now: i64) -> Result<()> {
+        let accrues = matches!(status, EmployeeStatus::Active | EmployeeStatus::OnHold);
+        if !accrues && self.paused_at == 0 {
+            self.paused_at = now;
+        } else if accrues && self.paused_at != 0 {
+            // Skip the paused interval, so it is never paid for
+            let paused_for = now.checked_sub(self.paused_at).ok_or(ErrorCode::Overflow)?;
+            self.last_claimed_at = self.last_claimed_at.checked_add(paused_for)
+                .ok_or(ErrorCode::Overflow)?;
+            self.paused_at = 0;
+        }
+        self.status = status as u8;
+        Ok(())
+    }
+}
+
+fn read_splits(recipients: &[Pubkey; MAX_PAYOUT_SPLITS], bps: &[u16; MAX_PAYOUT_SPLITS], count: u8) -> Vec<PayoutSplit> {
+    recipients.iter().zip(bps.iter())
+        .take(count as usize)
+        .map(|(recipient, bps)| PayoutSplit { recipient: *recipient, bps: *bps })
+        .collect()
+}
+
+fn write_splits(
+    recipients: &mut [Pubkey; MAX_PAYOUT_SPLITS],
+    bps: &mut [u16; MAX_PAYOUT_SPLITS],
+    splits: &[PayoutSplit],
+) -> u8 {
+    *recipients = [Pubkey::default(); MAX_PAYOUT_SPLITS];
+    *bps = [0; MAX_PAYOUT_SPLITS];
+    for (position, split) in splits.iter().take(MAX_PAYOUT_SPLITS).enumerate() {
+        recipients[position] = split.recipient;
+        bps[position] = split.bps;
+    }
+    splits.len().min(MAX_PAYOUT_SPLITS) as u8
+}
+
+/// Salary rate change as stored in the Borsh Employee layout
+#[derive(AnchorDeserialize)]
+pub struct LegacyRateChange {
+    pub effective_at: i64,
+    pub rate: u64,
+}
+
+/// Claim history entry as stored in the Borsh Employee layout
+#[derive(AnchorDeserialize)]
+pub struct LegacyClaimRecord {
+    pub timestamp: i64,
+    pub amount_bucket: u8,
+}
+
+/// PayrollBatch as it was stored before the zero-copy layout
+///
+/// Read once by `migrate_payroll_batch`. A batch created before the later
+/// fields existed is shorter than this layout, so it is decoded from a copy
+/// zero-extended to `SPACE` and those fields read as zero.
+#[derive(AnchorDeserialize)]
+pub struct LegacyPayrollBatch {
+    pub master_vault: Pubkey,
+    pub owner: Pubkey,
+    pub index: u32,
+    pub title: String,
+    pub employee_count: u32,
+    pub total_budget: u64,
+    pub total_paid: u64,
+    pub created_at: i64,
+    pub status: BatchStatus,
+    pub vault_bump: u8,
+    pub batch_bump: u8,
+    pub removed_count: u32,
+    pub pay_schedule: PaySchedule,
+    pub pay_period_seconds: i64,
+    pub payday_offset: i64,
+    pub allocated_budget: u64,
+    pub paused_at: i64,
+    pub paused_seconds: i64,
+    pub pending_owner: Pubkey,
+    pub salary_currency: SalaryCurrency,
+    pub price_feed_id: [u8; 32],
+    pub remaining_budget: u64,
+    pub crank_tip: u64,
+    pub token_vault_count: u8,
+}
+
+impl LegacyPayrollBatch {
+    /// Anchor's default discriminator for `PayrollBatch`, used by the Borsh layout
+    pub const DISCRIMINATOR: [u8; 8] = [163, 228, 23, 27, 184, 54, 182, 104];
+
+    pub const SPACE: usize = 8 +    // discriminator
+        32 +                         // master_vault
+        32 +                         // owner
+        4 +                          // index
+        (4 + 64) +                   // title
+        4 +                          // employee_count
+        8 +                          // total_budget
+        8 +                          // total_paid
+        8 +                          // created_at
+        1 +                          // status
+        1 +                          // vault_bump
+        1 +                          // batch_bump
+        4 +                          // removed_count
+        1 +                          // pay_schedule
+        8 +                          // pay_period_seconds
+        8 +                          // payday_offset
+        8 +                          // allocated_budget
+        8 +                          // paused_at
+        8 +                          // paused_seconds
+        32 +                         // pending_owner
+        1 +                          // salary_currency
+        32 +                         // price_feed_id
+        8 +                          // remaining_budget
+        8 +                          // crank_tip
+        1 +                          // token_vault_count
+        32;                          // padding
+
+    /// Decode the account data of a Borsh-layout batch
+    pub fn read(data: &[u8]) -> Result<Self> {
+        read_legacy(data, &Self::DISCRIMINATOR, PayrollBatch::DISCRIMINATOR, Self::SPACE)
+    }
+
+    /// Copy every field into a zeroed zero-copy batch
+    pub fn migrate(&self, batch: &mut PayrollBatch) -> Result<()> {
+        batch.total_budget = self.total_budget;
+        batch.total_paid = self.total_paid;
+        batch.created_at = self.created_at;
+        batch.pay_period_seconds = self.pay_period_seconds;
+        batch.payday_offset = self.payday_offset;
+        batch.allocated_budget = self.allocated_budget;
+        batch.paused_at = self.paused_at;
+        batch.paused_seconds = self.paused_seconds;
+        batch.remaining_budget = self.remaining_budget;
+        batch.crank_tip = self.crank_tip;
+        batch.master_vault = self.master_vault;
+        batch.owner = self.owner;
+        batch.pending_owner = self.pending_owner;
+        batch.price_feed_id = self.price_feed_id;
+        batch.set_title(&self.title)?;
+        batch.index = self.index;
+        batch.employee_count = self.employee_count;
+        batch.removed_count = self.removed_count;
+        batch.status = self.status as u8;
+        batch.vault_bump = self.vault_bump;
+        batch.batch_bump = self.batch_bump;
+        batch.pay_schedule = self.pay_schedule as u8;
+        batch.salary_currency = self.salary_currency as u8;
+        batch.token_vault_count = self.token_vault_count;
+        Ok(())
+    }
+}
+
+/// Employee as it was stored before the zero-copy layout
+///
+/// Read once by `migrate_employee`, zero-extended like LegacyPayrollBatch.
+#[derive(AnchorDeserialize)]
+pub struct LegacyEmployee {
+    pub batch: Pubkey,
+    pub wallet: Pubkey,
+    pub index: u32,
+    pub stealth_address: String,
+    pub salary_rate: u64,
+    pub start_time: i64,
+    pub last_claimed_at: i64,
+    pub total_claimed: u64,
+    pub status: EmployeeStatus,
+    pub bump: u8,
+    pub paused_at: i64,
+    pub claim_nonce: u64,
+    pub max_claim_per_period: u64,
+    pub claim_period_seconds: i64,
+    pub period_start: i64,
+    pub period_claimed: u64,
+    pub budget_tracked: bool,
+    pub budget_remaining: u64,
+    pub payout_address: Pubkey,
+    pub push_enabled: bool,
+    pub pending_accrued: u64,
+    pub batch_paused_seconds: i64,
+    pub rate_schedule: Vec<LegacyRateChange>,
+    pub pay_mint: Pubkey,
+    pub payout_splits: Vec<PayoutSplit>,
+    pub proposed_payout_splits: Option<Vec<PayoutSplit>>,
+    pub hold_reason: [u8; 32],
+    pub held_at: i64,
+    pub severance_paid: u64,
+    pub rent_payer: Pubkey,
+    pub claim_history: [LegacyClaimRecord; CLAIM_HISTORY_LEN],
+    pub claim_history_next: u8,
+}
+
+impl LegacyEmployee {
+    /// Anchor's default discriminator for `Employee`, used by the Borsh layout
+    pub const DISCRIMINATOR: [u8; 8] = [98, 238, 61, 252, 130, 77, 105, 67];
+
     pub const SPACE: usize = 8 +    // discriminator
         32 +                         // batch
         32 +                         // wallet
@@ -108,5 +1066,194 @@ impl Employee {
         8 +                          // total_claimed
         1 +                          // status
         1 +                          // bump
+        8 +                          // paused_at
+        8 +                          // claim_nonce
+        8 +                          // max_claim_per_period
+        8 +                          // claim_period_seconds
+        8 +                          // period_start
+        8 +                          // period_claimed
+        1 +                          // budget_tracked
+        8 +                          // budget_remaining
+        32 +                         // payout_address
+        1 +                          // push_enabled
+        8 +                          // pending_accrued
+        8 +                          // batch_paused_seconds
+        (4 + 16 * MAX_RATE_CHANGES) + // rate_schedule
+        32 +                         // pay_mint
+        (4 + 34 * MAX_PAYOUT_SPLITS) + // payout_splits
+        (1 + 4 + 34 * MAX_PAYOUT_SPLITS) + // proposed_payout_splits
+        32 +                         // hold_reason
+        8 +                          // held_at
+        8 +                          // severance_paid
+        32 +                         // rent_payer
+        9 * CLAIM_HISTORY_LEN +      // claim_history
+        1 +                          // claim_history_next
         32;                          // padding
+
+    /// Decode the account data of a Borsh-layout employee
+    pub fn read(data: &[u8]) -> Result<Self> {
+        read_legacy(data, &Self::DISCRIMINATOR, Employee::DISCRIMINATOR, Self::SPACE)
+    }
+
+    /// Copy every field into a zeroed zero-copy employee
+    pub fn migrate(&self, employee: &mut Employee) -> Result<()> {
+        require!(self.rate_schedule.len() <= MAX_RATE_CHANGES, ErrorCode::RateScheduleFull);
+        require!(self.payout_splits.len() <= MAX_PAYOUT_SPLITS, ErrorCode::InvalidPayoutSplits);
+
+        employee.salary_rate = self.salary_rate;
+        employee.start_time = self.start_time;
+        employee.last_claimed_at = self.last_claimed_at;
+        employee.total_claimed = self.total_claimed;
+        employee.paused_at = self.paused_at;
+        employee.claim_nonce = self.claim_nonce;
+        employee.max_claim_per_period = self.max_claim_per_period;
+        employee.claim_period_seconds = self.claim_period_seconds;
+        employee.period_start = self.period_start;
+        employee.period_claimed = self.period_claimed;
+        employee.budget_remaining = self.budget_remaining;
+        employee.pending_accrued = self.pending_accrued;
+        employee.batch_paused_seconds = self.batch_paused_seconds;
+        employee.held_at = self.held_at;
+        employee.severance_paid = self.severance_paid;
+        for (slot, change) in employee.rate_schedule.iter_mut().zip(&self.rate_schedule) {
+            *slot = RateChange { effective_at: change.effective_at, rate: change.rate };
+        }
+        employee.rate_schedule_len = self.rate_schedule.len() as u8;
+        for (slot, record) in employee.claim_history.iter_mut().zip(&self.claim_history) {
+            *slot = ClaimRecord { timestamp: record.timestamp, amount_bucket: record.amount_bucket, _reserved: [0; 7] };
+        }
+        employee.claim_history_next = self.claim_history_next;
+        employee.batch = self.batch;
+        employee.wallet = self.wallet;
+        employee.payout_address = self.payout_address;
+        employee.pay_mint = self.pay_mint;
+        employee.rent_payer = self.rent_payer;
+        employee.hold_reason = self.hold_reason;
+        employee.split_count = write_splits(&mut employee.split_recipients, &mut employee.split_bps, &self.payout_splits);
+        if let Some(proposed) = &self.proposed_payout_splits {
+            require!(proposed.len() <= MAX_PAYOUT_SPLITS, ErrorCode::InvalidPayoutSplits);
+            employee.propose_payout_splits(proposed);
+        }
+        employee.set_stealth_address(&self.stealth_address)?;
+        employee.index = self.index;
+        employee.status = self.status as u8;
+        employee.bump = self.bump;
+        employee.budget_tracked = self.budget_tracked as u8;
+        employee.push_enabled = self.push_enabled as u8;
+        Ok(())
+    }
+}
+
+/// Decode a Borsh-layout payroll account of at most `space` bytes from a
+/// zero-extended copy of its data
+fn read_legacy<T: AnchorDeserialize>(data: &[u8], legacy: &[u8; 8], current: &[u8], space: usize) -> Result<T> {
+    require!(!data.starts_with(current), ErrorCode::PayrollAccountCurrent);
+    require!(data.starts_with(legacy), ErrorCode::InvalidLegacyPayrollAccount);
+    let mut buf = data[legacy.len()..].to_vec();
+    buf.resize(buf.len().max(space - legacy.len()), 0);
+    T::deserialize(&mut buf.as_slice()).map_err(|_| ErrorCode::InvalidLegacyPayrollAccount.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Baseline batch image: every field the first release stored, then zeros
+    fn baseline_batch(master_vault: Pubkey, owner: Pubkey, title: &str) -> Vec<u8> {
+        let mut data = LegacyPayrollBatch::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(master_vault.as_ref());
+        data.extend_from_slice(owner.as_ref());
+        data.extend_from_slice(&7u32.to_le_bytes());
+        data.extend_from_slice(&(title.len() as u32).to_le_bytes());
+        data.extend_from_slice(title.as_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&5_000u64.to_le_bytes());
+        data.extend_from_slice(&1_200u64.to_le_bytes());
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        data.extend_from_slice(&[1, 254, 253]); // Paused, vault_bump, batch_bump
+        data.resize(8 + 32 + 32 + 4 + (4 + 64) + 4 + 8 + 8 + 8 + 1 + 1 + 1 + 32, 0);
+        data
+    }
+
+    /// Baseline employee image: every field the first release stored, then zeros
+    fn baseline_employee(batch: Pubkey, wallet: Pubkey, stealth_address: &str) -> Vec<u8> {
+        let mut data = LegacyEmployee::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(batch.as_ref());
+        data.extend_from_slice(wallet.as_ref());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&(stealth_address.len() as u32).to_le_bytes());
+        data.extend_from_slice(stealth_address.as_bytes());
+        data.extend_from_slice(&10u64.to_le_bytes());
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        data.extend_from_slice(&1_700_000_500i64.to_le_bytes());
+        data.extend_from_slice(&4_000u64.to_le_bytes());
+        data.extend_from_slice(&[2, 251]); // Terminated, bump
+        data.resize(8 + 32 + 32 + 4 + (4 + 200) + 8 + 8 + 8 + 8 + 1 + 1 + 32, 0);
+        data
+    }
+
+    #[test]
+    fn migrates_baseline_batch() {
+        let (master_vault, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let data = baseline_batch(master_vault, owner, "Engineering");
+        assert!(data.len() < LegacyPayrollBatch::SPACE);
+
+        let mut batch: PayrollBatch = bytemuck::Zeroable::zeroed();
+        LegacyPayrollBatch::read(&data).unwrap().migrate(&mut batch).unwrap();
+        assert_eq!(batch.master_vault, master_vault);
+        assert_eq!(batch.owner, owner);
+        assert_eq!(batch.index, 7);
+        assert_eq!(batch.title(), "Engineering");
+        assert_eq!(batch.employee_count, 3);
+        assert_eq!(batch.total_budget, 5_000);
+        assert_eq!(batch.total_paid, 1_200);
+        assert_eq!(batch.created_at, 1_700_000_000);
+        assert!(batch.status().unwrap() == BatchStatus::Paused);
+        assert_eq!((batch.vault_bump, batch.batch_bump), (254, 253));
+        assert!(batch.pay_schedule().unwrap() == PaySchedule::Streaming);
+        assert!(batch.salary_currency().unwrap() == SalaryCurrency::Lamports);
+        assert_eq!((batch.removed_count, batch.allocated_budget, batch.crank_tip), (0, 0, 0));
+    }
+
+    #[test]
+    fn migrates_baseline_employee_with_full_stealth_address() {
+        let (batch, wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let stealth_address = "st:".repeat(66);
+        let data = baseline_employee(batch, wallet, &stealth_address);
+        assert!(data.len() < LegacyEmployee::SPACE);
+
+        let mut employee: Employee = bytemuck::Zeroable::zeroed();
+        LegacyEmployee::read(&data).unwrap().migrate(&mut employee).unwrap();
+        assert_eq!(employee.batch, batch);
+        assert_eq!(employee.wallet, wallet);
+        assert_eq!(employee.index, 2);
+        assert_eq!(employee.stealth_address(), stealth_address);
+        assert_eq!(employee.salary_rate, 10);
+        assert_eq!(employee.start_time, 1_700_000_000);
+        assert_eq!(employee.last_claimed_at, 1_700_000_500);
+        assert_eq!(employee.total_claimed, 4_000);
+        assert!(employee.status().unwrap() == EmployeeStatus::Terminated);
+        assert_eq!(employee.bump, 251);
+        assert!(!employee.budget_tracked() && !employee.has_payout_splits());
+        assert!(employee.rate_schedule().is_empty() && employee.proposed_payout_splits().is_none());
+        assert!(employee.paid_in_sol());
+        assert_eq!(employee.rent_refund_to(&wallet), wallet);
+    }
+
+    #[test]
+    fn rejects_current_and_foreign_accounts() {
+        let mut current = PayrollBatch::DISCRIMINATOR.to_vec();
+        current.resize(PayrollBatch::SPACE, 0);
+        assert!(LegacyPayrollBatch::read(&current).is_err());
+        assert!(LegacyEmployee::read(&[0u8; 64]).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_state_codes() {
+        let mut batch: PayrollBatch = bytemuck::Zeroable::zeroed();
+        batch.status = 3;
+        assert!(batch.status().is_err());
+        batch.pay_schedule = 9;
+        assert!(batch.accrual_cutoff(0).is_err());
+    }
 }
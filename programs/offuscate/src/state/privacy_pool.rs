@@ -1,14 +1,34 @@
 //! Privacy Pool State
 //!
 //! Accounts for the privacy pool feature:
-//! - PrivacyPool: Global pool storing aggregate stats
+//! - PrivacyPool: Global pool configuration and pre-shard totals
+//! - PoolShard: Booked balance and activity counters of one vault shard
 //! - PendingWithdraw: Delayed withdrawal request
+//! - RelayNonce: One-time counter for a key's relayed authorizations
+//! - SessionKey: Short-lived key authorizing relayed operations for a recipient
+//! - RelayerAllowlist: Vetted relayers, enforced in allowlist mode
+//! - RelayerInfo: Per-relayer reputation counters and epoch rate limit
 //! - ChurnVaultState: Internal mixing vault state
+//! - WithdrawQueue: Time bucket of withdrawals released in shuffled order
+//! - PendingStatus: Return data of `get_pending_status`
+//! - RelayFeeQuote: Return data of `quote_relay_fee`
+//! - EpochSnapshot: Persisted per-epoch pool activity
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::slot_hashes::MAX_ENTRIES as SLOT_HASHES_MAX_ENTRIES;
+use crate::constants::{
+    ALLOWED_AMOUNTS, DENOMINATION_COUNT, MAX_QUEUE_ENTRIES, QUEUE_BUCKET_SECONDS, VAULT_SHARD_COUNT,
+    MAX_SWAP_PROGRAMS, BPS_DENOMINATOR, MAX_ALLOWED_RELAYERS, DEFAULT_RESCUE_DELAY_EPOCHS,
+};
+use crate::errors::ErrorCode;
 
 /// The global privacy pool that holds aggregated funds
 /// PRIVACY: Only stores aggregate stats, no individual deposit tracking
+///
+/// Deposits and withdrawals only read the pool; their bookings go to the
+/// `PoolShard` of the vault shard they use. The counters below were booked
+/// before pool shards existed and are frozen since (`vault_balance` and
+/// `total_reserved` are moved to the shards by `init_pool_shard`).
 #[account]
 pub struct PrivacyPool {
     pub total_deposited: u64,  // 8 bytes - aggregate deposits
@@ -17,7 +37,42 @@ pub struct PrivacyPool {
     pub withdraw_count: u64,   // 8 bytes - number of withdrawals
     pub churn_count: u64,      // 8 bytes - number of churn operations
     pub bump: u8,              // 1 byte
-    pub vault_bump: u8,        // 1 byte
+    pub vault_bumps: [u8; VAULT_SHARD_COUNT], // 4 bytes - one per vault shard
+    // Per-denomination anonymity tracking (indexed like ALLOWED_AMOUNTS)
+    pub denomination_deposits: [u64; DENOMINATION_COUNT],    // 48 bytes
+    pub denomination_withdrawals: [u64; DENOMINATION_COUNT], // 48 bytes
+    pub total_reserved: u64,   // 8 bytes - lamports held for unclaimed pending withdrawals
+    pub void_count: u64,       // 8 bytes - pending withdrawals voided after timeout
+    pub authority: Pubkey,     // 32 bytes - may update pool configuration
+    pub pending_authority: Pubkey, // 32 bytes - proposed authority (default = none)
+    // Circuit breaker: max lamports withdrawn per Solana epoch (0 = unlimited),
+    // split evenly across the vault shards
+    pub max_epoch_outflow: u64, // 8 bytes
+    pub outflow_epoch: u64,    // 8 bytes - epoch the outflow counter belongs to
+    pub epoch_outflow: u64,    // 8 bytes - lamports withdrawn in outflow_epoch
+    pub total_dust_swept: u64, // 8 bytes - non-denominated dust moved to the treasury
+    // Vault sharding
+    pub vault_shards: [Pubkey; VAULT_SHARD_COUNT], // 128 bytes - shard PDAs in index order
+    pub next_deposit_shard: u8, // 1 byte - unused; deposits pick their shard
+    pub vault_balance: u64,    // 8 bytes - lamports booked across all shards
+    pub swap_programs: [Pubkey; MAX_SWAP_PROGRAMS], // 128 bytes - whitelisted swap programs (default = empty slot)
+    pub standard_deposits_only: bool, // 1 byte - deposits must be an allowed denomination
+    // Per-epoch activity, rolled over on the first pool action of a new epoch
+    pub stats_epoch: u64,      // 8 bytes - epoch of current_epoch_stats
+    pub current_epoch_stats: EpochStats,  // 40 bytes
+    pub previous_stats_epoch: u64,        // 8 bytes - epoch of previous_epoch_stats
+    pub previous_epoch_stats: EpochStats, // 40 bytes
+    // Emergency pause and the user self-rescue escape hatch
+    pub paused: bool,          // 1 byte - blocks every booked deposit and withdrawal
+    pub paused_epoch: u64,     // 8 bytes - epoch the current pause started
+    pub rescue_delay_epochs: u64, // 8 bytes - paused epochs before self-rescue opens (0 = default)
+    pub max_relay_fee_bps: u16, // 2 bytes - cap on relayer fees taken from relayed withdrawals
+    pub relay_base_fee: u64,   // 8 bytes - lamports a relayed withdrawal must pay its relayer
+    pub relay_congestion_bps: u32, // 4 bytes - multiplier on the base fee (10000 = 1x)
+    pub relayer_allowlist_enabled: bool, // 1 byte - relayed instructions only accept allowlisted relayers
+    pub max_relayer_claims_per_epoch: u32, // 4 bytes - relayed claims one relayer may submit per epoch (0 = unlimited)
+    pub rescue_delay_locked: bool, // 1 byte - the authority has set the rescue delay
+    pub resumed_epoch: u64,    // 8 bytes - epoch the last pause ended
 }
 
 impl PrivacyPool {
@@ -28,8 +83,331 @@ impl PrivacyPool {
         8 +                        // withdraw_count
         8 +                        // churn_count
         1 +                        // bump
-        1 +                        // vault_bump
+        VAULT_SHARD_COUNT +        // vault_bumps
+        8 * DENOMINATION_COUNT +   // denomination_deposits
+        8 * DENOMINATION_COUNT +   // denomination_withdrawals
+        8 +                        // total_reserved
+        8 +                        // void_count
+        32 +                       // authority
+        32 +                       // pending_authority
+        8 +                        // max_epoch_outflow
+        8 +                        // outflow_epoch
+        8 +                        // epoch_outflow
+        8 +                        // total_dust_swept
+        32 * VAULT_SHARD_COUNT +   // vault_shards
+        1 +                        // next_deposit_shard
+        8 +                        // vault_balance
+        32 * MAX_SWAP_PROGRAMS +   // swap_programs
+        1 +                        // standard_deposits_only
+        8 +                        // stats_epoch
+        EpochStats::SPACE +        // current_epoch_stats
+        8 +                        // previous_stats_epoch
+        EpochStats::SPACE +        // previous_epoch_stats
+        1 +                        // paused
+        8 +                        // paused_epoch
+        8 +                        // rescue_delay_epochs
+        2 +                        // max_relay_fee_bps
+        8 +                        // relay_base_fee
+        4 +                        // relay_congestion_bps
+        1 +                        // relayer_allowlist_enabled
+        4 +                        // max_relayer_claims_per_epoch
+        1 +                        // rescue_delay_locked
+        8 +                        // resumed_epoch
         16;                        // padding
+
+    /// Index of a standardized denomination, if the amount is one
+    pub fn denomination_index(amount: u64) -> Option<usize> {
+        ALLOWED_AMOUNTS.iter().position(|&allowed| allowed == amount)
+    }
+
+    /// Index of a vault shard address
+    pub fn shard_index(&self, vault: &Pubkey) -> Option<u8> {
+        self.vault_shards.iter().position(|shard| shard == vault).map(|index| index as u8)
+    }
+
+    pub fn is_vault_shard(&self, vault: &Pubkey) -> bool {
+        self.shard_index(vault).is_some()
+    }
+
+    /// Vault of shard `index`
+    pub fn shard_vault(&self, index: u8) -> Pubkey {
+        self.vault_shards[index as usize]
+    }
+
+    /// Seed suffix (`[shard_index]`, `[bump]`) for signing as a vault shard
+    pub fn vault_signer(&self, vault: &Pubkey) -> Result<[[u8; 1]; 2]> {
+        let shard = self.shard_index(vault).ok_or(ErrorCode::InvalidVaultShard)?;
+        Ok([[shard], [self.vault_bumps[shard as usize]]])
+    }
+
+    /// Reject non-standard deposit amounts when the pool requires denominations
+    /// PRIVACY: Unusual deposit amounts are easy to match with later withdrawals
+    pub fn check_deposit_amount(&self, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        if self.standard_deposits_only {
+            require!(ALLOWED_AMOUNTS.contains(&amount), ErrorCode::NonStandardDeposit);
+        }
+        Ok(())
+    }
+
+    /// Pre-shard activity of a finished epoch, if it is still retained
+    pub fn finished_epoch_stats(&self, epoch: u64) -> Option<EpochStats> {
+        EpochStats::retained(
+            epoch,
+            (self.stats_epoch, self.current_epoch_stats),
+            (self.previous_stats_epoch, self.previous_epoch_stats),
+        )
+    }
+
+    /// Withdrawal limit of each shard for one epoch (0 = unlimited)
+    pub fn shard_outflow_limit(&self) -> u64 {
+        self.max_epoch_outflow / VAULT_SHARD_COUNT as u64
+    }
+
+    /// Largest relayer fee allowed on a relayed withdrawal of `amount`
+    pub fn max_relay_fee(&self, amount: u64) -> u64 {
+        (amount as u128 * self.max_relay_fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Relayer fee currently required: the base fee scaled by the congestion multiplier
+    pub fn required_relay_fee(&self) -> u64 {
+        let fee = self.relay_base_fee as u128 * self.relay_congestion_bps as u128 / BPS_DENOMINATOR as u128;
+        fee.min(u64::MAX as u128) as u64
+    }
+
+    /// Reject a relayer fee below the required fee or above the configured share of `amount`
+    pub fn check_relay_fee(&self, amount: u64, fee: u64) -> Result<()> {
+        require!(fee >= self.required_relay_fee(), ErrorCode::RelayFeeTooLow);
+        require!(fee <= self.max_relay_fee(amount), ErrorCode::RelayFeeTooHigh);
+        Ok(())
+    }
+
+    /// Reject flows that have no lamport booking of their own while paused
+    pub fn require_not_paused(&self) -> Result<()> {
+        require!(!self.paused, ErrorCode::PoolPaused);
+        Ok(())
+    }
+
+    /// Require `relayer` to be allowlisted while allowlist mode is on
+    ///
+    /// With the mode off any wallet may relay and `allowlist` is ignored.
+    pub fn check_relayer(&self, relayer: &Pubkey, allowlist: Option<&RelayerAllowlist>) -> Result<()> {
+        if !self.relayer_allowlist_enabled {
+            return Ok(());
+        }
+        let allowlist = allowlist.ok_or(ErrorCode::RelayerAllowlistMissing)?;
+        require!(allowlist.contains(relayer), ErrorCode::RelayerNotAllowed);
+        Ok(())
+    }
+
+    /// Paused epochs before users may self-rescue
+    pub fn rescue_delay(&self) -> u64 {
+        if self.rescue_delay_epochs == 0 {
+            DEFAULT_RESCUE_DELAY_EPOCHS
+        } else {
+            self.rescue_delay_epochs
+        }
+    }
+
+    /// Whether the pool has been paused long enough for users to self-rescue
+    pub fn rescue_open(&self, epoch: u64) -> bool {
+        self.paused && epoch >= self.paused_epoch.saturating_add(self.rescue_delay())
+    }
+
+    /// Whether a program may be used for swap claims
+    pub fn is_swap_program(&self, program: &Pubkey) -> bool {
+        *program != Pubkey::default() && self.swap_programs.contains(program)
+    }
+}
+
+/// Booked balance and activity counters of one vault shard
+///
+/// Every deposit and withdrawal books only the shard it uses, so
+/// transactions on different shards never write the same account. A pending
+/// withdrawal reserves lamports on one shard and is paid from that shard.
+#[account]
+pub struct PoolShard {
+    pub index: u8,             // 1 byte - vault shard index
+    pub bump: u8,              // 1 byte
+    pub vault_balance: u64,    // 8 bytes - lamports booked in this shard's vault
+    pub total_reserved: u64,   // 8 bytes - lamports held for pending withdrawals on this shard
+    pub total_deposited: u64,  // 8 bytes
+    pub total_withdrawn: u64,  // 8 bytes
+    pub deposit_count: u64,    // 8 bytes
+    pub withdraw_count: u64,   // 8 bytes
+    pub churn_count: u64,      // 8 bytes
+    pub void_count: u64,       // 8 bytes
+    pub denomination_deposits: [u64; DENOMINATION_COUNT],    // 48 bytes
+    pub denomination_withdrawals: [u64; DENOMINATION_COUNT], // 48 bytes
+    pub outflow_epoch: u64,    // 8 bytes - epoch the outflow counter belongs to
+    pub epoch_outflow: u64,    // 8 bytes - lamports withdrawn in outflow_epoch
+    // Per-epoch activity, rolled over on the first shard action of a new epoch
+    pub stats_epoch: u64,      // 8 bytes - epoch of current_epoch_stats
+    pub current_epoch_stats: EpochStats,  // 40 bytes
+    pub previous_stats_epoch: u64,        // 8 bytes - epoch of previous_epoch_stats
+    pub previous_epoch_stats: EpochStats, // 40 bytes
+}
+
+impl PoolShard {
+    pub const SPACE: usize = 8 +  // discriminator
+        1 +                        // index
+        1 +                        // bump
+        8 +                        // vault_balance
+        8 +                        // total_reserved
+        8 +                        // total_deposited
+        8 +                        // total_withdrawn
+        8 +                        // deposit_count
+        8 +                        // withdraw_count
+        8 +                        // churn_count
+        8 +                        // void_count
+        8 * DENOMINATION_COUNT +   // denomination_deposits
+        8 * DENOMINATION_COUNT +   // denomination_withdrawals
+        8 +                        // outflow_epoch
+        8 +                        // epoch_outflow
+        8 +                        // stats_epoch
+        EpochStats::SPACE +        // current_epoch_stats
+        8 +                        // previous_stats_epoch
+        EpochStats::SPACE +        // previous_epoch_stats
+        16;                        // padding
+
+    /// Book lamports entering the shard's vault (deposits, unchurn)
+    pub fn credit_vault(&mut self, amount: u64) -> Result<()> {
+        self.vault_balance = self.vault_balance.checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Book lamports leaving the shard's vault (claims, churn, sweeps)
+    pub fn debit_vault(&mut self, amount: u64) -> Result<()> {
+        self.vault_balance = self.vault_balance.checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientPoolFunds)?;
+        Ok(())
+    }
+
+    /// Start a new epoch's activity counters if `epoch` has moved on
+    pub fn roll_epoch_stats(&mut self, epoch: u64) {
+        if epoch > self.stats_epoch {
+            self.previous_epoch_stats = self.current_epoch_stats;
+            self.previous_stats_epoch = self.stats_epoch;
+            self.current_epoch_stats = EpochStats::default();
+            self.stats_epoch = epoch;
+        }
+    }
+
+    /// Activity of a finished epoch, if it is still retained
+    pub fn finished_epoch_stats(&self, epoch: u64) -> Option<EpochStats> {
+        EpochStats::retained(
+            epoch,
+            (self.stats_epoch, self.current_epoch_stats),
+            (self.previous_stats_epoch, self.previous_epoch_stats),
+        )
+    }
+
+    /// Record a churn operation
+    pub fn record_churn(&mut self) -> Result<()> {
+        self.roll_epoch_stats(Clock::get()?.epoch);
+        self.churn_count = self.churn_count.saturating_add(1);
+        self.current_epoch_stats.churn_count = self.current_epoch_stats.churn_count.saturating_add(1);
+        Ok(())
+    }
+
+    /// Record a deposit into this shard's vault
+    pub fn record_deposit(&mut self, pool: &PrivacyPool, amount: u64) -> Result<()> {
+        pool.require_not_paused()?;
+        self.roll_epoch_stats(Clock::get()?.epoch);
+        self.current_epoch_stats.deposit_count = self.current_epoch_stats.deposit_count.saturating_add(1);
+        self.current_epoch_stats.deposited = self.current_epoch_stats.deposited.saturating_add(amount);
+
+        self.credit_vault(amount)?;
+
+        self.total_deposited = self.total_deposited.checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        self.deposit_count = self.deposit_count.checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        if let Some(index) = PrivacyPool::denomination_index(amount) {
+            self.denomination_deposits[index] = self.denomination_deposits[index]
+                .checked_add(1)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether `amount` still fits under this shard's share of the epoch outflow limit
+    pub fn outflow_allows(&self, pool: &PrivacyPool, amount: u64, epoch: u64) -> bool {
+        let limit = pool.shard_outflow_limit();
+        if limit == 0 {
+            return true;
+        }
+        let used = if epoch == self.outflow_epoch { self.epoch_outflow } else { 0 };
+        used.saturating_add(amount) <= limit
+    }
+
+    /// Record a withdrawal from this shard's vault
+    ///
+    /// Fails while the pool is paused, and once the shard's share of the
+    /// epoch outflow limit would be exceeded, until the next epoch.
+    pub fn record_withdrawal(&mut self, pool: &PrivacyPool, amount: u64) -> Result<()> {
+        pool.require_not_paused()?;
+        let epoch = Clock::get()?.epoch;
+        require!(self.outflow_allows(pool, amount, epoch), ErrorCode::EpochOutflowLimitReached);
+        self.book_withdrawal(amount, epoch)
+    }
+
+    /// Record a self-rescue exit
+    ///
+    /// Only allowed once the rescue delay has passed; ignores the pause and
+    /// the outflow limit, which an absent authority could otherwise use to
+    /// keep funds locked.
+    pub fn record_rescue(&mut self, pool: &PrivacyPool, amount: u64) -> Result<()> {
+        let epoch = Clock::get()?.epoch;
+        require!(pool.rescue_open(epoch), ErrorCode::RescueNotAvailable);
+        self.book_withdrawal(amount, epoch)
+    }
+
+    fn book_withdrawal(&mut self, amount: u64, epoch: u64) -> Result<()> {
+        if epoch != self.outflow_epoch {
+            self.outflow_epoch = epoch;
+            self.epoch_outflow = 0;
+        }
+        self.epoch_outflow = self.epoch_outflow.saturating_add(amount);
+        self.debit_vault(amount)?;
+
+        self.roll_epoch_stats(epoch);
+        self.current_epoch_stats.withdraw_count = self.current_epoch_stats.withdraw_count.saturating_add(1);
+        self.current_epoch_stats.withdrawn = self.current_epoch_stats.withdrawn.saturating_add(amount);
+
+        self.total_withdrawn = self.total_withdrawn.checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        self.withdraw_count = self.withdraw_count.checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        if let Some(index) = PrivacyPool::denomination_index(amount) {
+            self.denomination_withdrawals[index] = self.denomination_withdrawals[index]
+                .checked_add(1)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+
+        Ok(())
+    }
+
+    /// Booked vault balance not reserved by pending withdrawals
+    pub fn unreserved_balance(&self) -> u64 {
+        self.vault_balance.saturating_sub(self.total_reserved)
+    }
+
+    /// Hold lamports for a new pending withdrawal
+    pub fn reserve(&mut self, amount: u64) -> Result<()> {
+        self.total_reserved = self.total_reserved.checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Release a pending withdrawal's hold (claimed or voided)
+    pub fn release_reservation(&mut self, amount: u64) {
+        self.total_reserved = self.total_reserved.saturating_sub(amount);
+    }
 }
 
 /// State for a churn vault (internal mixing vault)
@@ -55,14 +433,25 @@ impl ChurnVaultState {
 
 /// A pending withdrawal request with time delay
 /// PRIVACY: Only stores recipient (stealth address), not sender
-#[account]
+///
+/// Zero-copy so batch claims can load many of these cheaply from
+/// remaining accounts. The fields up to `bump` match the original Borsh
+/// layout; later fields are appended after them and read as zero on
+/// accounts resized by `migrate_pending_withdraw`.
+#[account(zero_copy)]
 pub struct PendingWithdraw {
     pub recipient: Pubkey,     // 32 bytes - stealth address
     pub amount: u64,           // 8 bytes - standardized amount
     pub requested_at: i64,     // 8 bytes - when requested
     pub available_at: i64,     // 8 bytes - when can be claimed
-    pub claimed: bool,         // 1 byte
+    pub claimed: u8,           // 1 byte - bool (u8 for zero-copy)
     pub bump: u8,              // 1 byte
+    pub voided: u8,            // 1 byte - bool, abandoned and returned to the pool
+    pub queued: u8,            // 1 byte - bool, released by the queue crank only
+    pub shard: u8,             // 1 byte - vault shard holding the reservation
+    pub _reserved: [u8; 3],    // 3 bytes - explicit alignment padding
+    pub expires_at: i64,       // 8 bytes - claim deadline (0 = never expires)
+    pub claim_tip: u64,        // 8 bytes - paid to any executor of the claim (0 = recipient claims)
 }
 
 impl PendingWithdraw {
@@ -73,5 +462,486 @@ impl PendingWithdraw {
         8 +                        // available_at
         1 +                        // claimed
         1 +                        // bump
+        1 +                        // voided
+        1 +                        // queued
+        1 +                        // shard
+        3 +                        // _reserved
+        8 +                        // expires_at
+        8 +                        // claim_tip
+        16;                        // padding
+
+    /// Size of accounts created with the original Borsh layout
+    pub const LEGACY_SPACE: usize = 8 + 32 + 8 + 8 + 8 + 1 + 1 + 16;
+
+    /// Offset of `bump` in the account data, the same in every layout
+    pub const BUMP_OFFSET: usize = 8 + 32 + 8 + 8 + 8 + 1;
+
+    pub fn is_claimed(&self) -> bool {
+        self.claimed != 0
+    }
+
+    pub fn is_voided(&self) -> bool {
+        self.voided != 0
+    }
+
+    /// Claimed or voided; nothing is left to pay out
+    pub fn is_settled(&self) -> bool {
+        self.is_claimed() || self.is_voided()
+    }
+
+    /// Part of a shuffled withdrawal queue
+    pub fn is_queued(&self) -> bool {
+        self.queued != 0
+    }
+
+    /// Claimable by anyone, who keeps `claim_tip`
+    pub fn offers_claim_tip(&self) -> bool {
+        self.claim_tip != 0
+    }
+
+    /// Abandoned long enough that anyone may void it
+    pub fn is_abandoned(&self, now: i64, timeout: i64) -> bool {
+        now > self.available_at.saturating_add(timeout)
+    }
+
+    /// Past its claim window; must be reopened before it can be claimed
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && now > self.expires_at
+    }
+
+    /// Ensure the withdrawal can be claimed right now
+    pub fn check_claimable(&self, now: i64) -> Result<()> {
+        require!(!self.is_claimed(), ErrorCode::AlreadyClaimed);
+        require!(!self.is_voided(), ErrorCode::WithdrawVoided);
+        require!(!self.is_queued(), ErrorCode::WithdrawQueued);
+        require!(now >= self.available_at, ErrorCode::WithdrawNotReady);
+        require!(!self.is_expired(now), ErrorCode::WithdrawExpired);
+        Ok(())
+    }
+}
+
+/// Per-key nonce consumed by every relayed claim it authorizes
+///
+/// Pending withdrawal PDAs are reused once closed, so a signature over the
+/// PDA alone could be replayed against a later withdrawal; the nonce makes
+/// each signed claim message valid exactly once.
+#[account]
+pub struct RelayNonce {
+    pub signer: Pubkey,        // 32 bytes - key whose authorizations this counts
+    pub nonce: u64,            // 8 bytes - nonce the next authorization must carry
+    pub bump: u8,              // 1 byte
+    pub secp256k1_signer: [u8; 20], // 20 bytes - Ethereum address that may also authorize claims (zero = none)
+}
+
+impl RelayNonce {
+    pub const SPACE: usize = 8 +   // discriminator
+        32 +                        // signer
+        8 +                         // nonce
+        1 +                         // bump
+        20 +                        // secp256k1_signer
+        16;                         // padding
+
+    pub fn has_secp256k1_signer(&self) -> bool {
+        self.secp256k1_signer != [0u8; 20]
+    }
+
+    /// Message a recipient signs to authorize a relayed claim:
+    /// "claim:<pending_pda>:<nonce>:<valid_until>"
+    ///
+    /// The "claim:" prefix keeps it apart from the binary withdraw and
+    /// deposit authorizations, so no signature is valid for two instruction types.
+    pub fn claim_message(pending: &Pubkey, nonce: u64, valid_until: i64) -> Vec<u8> {
+        format!("claim:{}:{}:{}", pending, nonce, valid_until).into_bytes()
+    }
+
+    /// Claim text bound to a durable nonce account, for authorizations signed
+    /// offline ahead of time
+    pub fn durable_claim_message(pending: &Pubkey, nonce: u64, valid_until: i64, durable_nonce: &Pubkey) -> Vec<u8> {
+        format!("claim:{}:{}:{}:{}", pending, nonce, valid_until, durable_nonce).into_bytes()
+    }
+
+    /// Text the recipient signs to have a relayer open `pending` for `amount`
+    pub fn request_message(pending: &Pubkey, amount: u64, nonce: u64, valid_until: i64) -> Vec<u8> {
+        format!("request:{}:{}:{}:{}", pending, amount, nonce, valid_until).into_bytes()
+    }
+
+    /// Move past the current nonce
+    pub fn consume(&mut self) -> Result<()> {
+        self.nonce = self.nonce.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+}
+
+/// Relayers vetted by the pool authority
+/// Only consulted while `PrivacyPool::relayer_allowlist_enabled` is set
+#[account]
+pub struct RelayerAllowlist {
+    pub relayers: [Pubkey; MAX_ALLOWED_RELAYERS], // 512 bytes - default = empty slot
+    pub bump: u8,              // 1 byte
+}
+
+impl RelayerAllowlist {
+    pub const SPACE: usize = 8 +   // discriminator
+        32 * MAX_ALLOWED_RELAYERS + // relayers
+        1 +                         // bump
+        16;                         // padding
+
+    pub fn contains(&self, relayer: &Pubkey) -> bool {
+        *relayer != Pubkey::default() && self.relayers.contains(relayer)
+    }
+}
+
+/// Activity of one relayer
+///
+/// The totals let wallets rank relayers by reliability straight from chain.
+/// PRIVACY: Capping claims per epoch stops a single relayer from submitting
+/// most claims and so learning (and shaping) when they happen
+#[account]
+pub struct RelayerInfo {
+    pub relayer: Pubkey,       // 32 bytes
+    pub epoch: u64,            // 8 bytes - epoch epoch_claims belongs to
+    pub epoch_claims: u32,     // 4 bytes - relayed claims and withdrawals this epoch
+    pub successful_relays: u64, // 8 bytes - since registration
+    pub failed_relays: u64,    // 8 bytes - batch entries submitted but skipped
+    pub fees_earned: u64,      // 8 bytes - lamports, since registration
+    pub bump: u8,              // 1 byte
+}
+
+impl RelayerInfo {
+    pub const SPACE: usize = 8 +   // discriminator
+        32 +                        // relayer
+        8 +                         // epoch
+        4 +                         // epoch_claims
+        8 +                         // successful_relays
+        8 +                         // failed_relays
+        8 +                         // fees_earned
+        1 +                         // bump
+        16;                         // padding
+
+    /// Count one relayed claim paying the relayer `fee`, failing once `cap`
+    /// claims (0 = unlimited) were already submitted this epoch
+    pub fn record_claim(&mut self, cap: u32, fee: u64) -> Result<()> {
+        let epoch = Clock::get()?.epoch;
+        if epoch != self.epoch {
+            self.epoch = epoch;
+            self.epoch_claims = 0;
+        }
+        require!(cap == 0 || self.epoch_claims < cap, ErrorCode::RelayerRateLimited);
+        self.epoch_claims += 1;
+        self.record_relay(fee);
+        Ok(())
+    }
+
+    /// Count one relayed operation that landed, paying the relayer `fee`
+    pub fn record_relay(&mut self, fee: u64) {
+        self.successful_relays = self.successful_relays.saturating_add(1);
+        self.fees_earned = self.fees_earned.saturating_add(fee);
+    }
+
+    /// Count one relayed operation that was submitted but not settled
+    ///
+    /// A failing transaction reverts its own counters, so only entries a
+    /// batch skips are ever recorded here.
+    pub fn record_failure(&mut self) {
+        self.failed_relays = self.failed_relays.saturating_add(1);
+    }
+}
+
+/// Short-lived key that may authorize relayed operations for `owner`
+///
+/// Registered once with the owner's (cold stealth key) signature; until it
+/// expires the session key signs in its place, for the operations in `allowed`.
+#[account]
+pub struct SessionKey {
+    pub owner: Pubkey,         // 32 bytes - recipient the session acts for
+    pub session_key: Pubkey,   // 32 bytes - hot ed25519 key
+    pub expires_at: i64,       // 8 bytes
+    pub allowed: u8,           // 1 byte - SESSION_ALLOW_* bits
+    pub rent_payer: Pubkey,    // 32 bytes - refunded when the session is closed
+    pub bump: u8,              // 1 byte
+}
+
+impl SessionKey {
+    pub const SPACE: usize = 8 +   // discriminator
+        32 +                        // owner
+        32 +                        // session_key
+        8 +                         // expires_at
+        1 +                         // allowed
+        32 +                        // rent_payer
+        1 +                         // bump
+        16;                         // padding
+
+    /// Message the owner signs to register a session:
+    /// "session:<owner>:<session_key>:<expires_at>:<allowed>:<nonce>:<valid_until>"
+    pub fn register_message(
+        owner: &Pubkey,
+        session_key: &Pubkey,
+        expires_at: i64,
+        allowed: u8,
+        nonce: u64,
+        valid_until: i64,
+    ) -> Vec<u8> {
+        format!("session:{}:{}:{}:{}:{}:{}", owner, session_key, expires_at, allowed, nonce, valid_until).into_bytes()
+    }
+
+    /// Ensure the session is live and grants `permission`
+    pub fn check_allows(&self, permission: u8, now: i64) -> Result<()> {
+        require!(now <= self.expires_at, ErrorCode::SessionExpired);
+        require!(self.allowed & permission != 0, ErrorCode::SessionNotPermitted);
+        Ok(())
+    }
+}
+
+/// Withdrawals of one time bucket, released by a crank in shuffled order
+/// PRIVACY: Claim ordering is decided on-chain, so it carries no information
+/// about when each recipient requested or came back online
+#[account]
+pub struct WithdrawQueue {
+    pub bucket: i64,           // 8 bytes - unix time / QUEUE_BUCKET_SECONDS
+    pub entries: Vec<Pubkey>,  // 4 + 32 * MAX_QUEUE_ENTRIES - pending withdraw accounts
+    pub released: u8,          // 1 byte - entries already processed by the crank
+    pub shuffled: bool,        // 1 byte - release order is fixed
+    pub bump: u8,              // 1 byte
+    pub seed_slot: u64,        // 8 bytes - slot whose hash seeds the shuffle (0 = not armed)
+}
+
+impl WithdrawQueue {
+    pub const SPACE: usize = 8 +  // discriminator
+        8 +                        // bucket
+        4 + 32 * MAX_QUEUE_ENTRIES + // entries
+        1 +                        // released
+        1 +                        // shuffled
+        1 +                        // bump
+        8 +                        // seed_slot
+        8;                         // padding
+
+    /// Bucket index for a unix timestamp
+    pub fn bucket_at(now: i64) -> i64 {
+        now / QUEUE_BUCKET_SECONDS
+    }
+
+    /// When the bucket stops accepting requests and can be shuffled
+    pub fn closes_at(&self) -> i64 {
+        (self.bucket + 1) * QUEUE_BUCKET_SECONDS
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.entries.len() >= MAX_QUEUE_ENTRIES
+    }
+
+    /// Whether a shuffle seed slot has been fixed that is still within
+    /// reach of the SlotHashes sysvar at `slot`
+    pub fn seed_armed(&self, slot: u64) -> bool {
+        self.seed_slot != 0 && slot <= self.seed_slot.saturating_add(SLOT_HASHES_MAX_ENTRIES as u64)
+    }
+
+    /// Next pending withdraw to release, if any remain
+    pub fn next_entry(&self) -> Option<Pubkey> {
+        self.entries.get(self.released as usize).copied()
+    }
+}
+
+/// Lifecycle state of a pending withdrawal, as seen by wallets
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PendingState {
+    None,
+    Waiting,
+    Ready,
+    Expired,
+    Queued,
+    Claimed,
+    Voided,
+}
+
+/// Return data of `quote_relay_fee`
+/// The fee to sign into a withdraw authorization, and the bounds it must meet
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RelayFeeQuote {
+    pub fee: u64,                  // currently required fee, in lamports
+    pub max_fee: u64,              // cap for the quoted amount; relaying fails if fee exceeds it
+    pub base_fee: u64,
+    pub congestion_bps: u32,       // 10000 = 1x
+}
+
+/// Return data of `get_pending_status`
+/// Lets wallets check a withdrawal without knowing the account layout
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PendingStatus {
+    pub exists: bool,
+    pub state: PendingState,
+    pub amount: u64,
+    pub denomination_index: Option<u8>, // index into ALLOWED_AMOUNTS
+    pub seconds_remaining: i64,         // until claimable (0 once ready)
+    pub expires_at: i64,                // 0 = never expires
+}
+
+impl PendingStatus {
+    pub fn missing() -> Self {
+        Self {
+            exists: false,
+            state: PendingState::None,
+            amount: 0,
+            denomination_index: None,
+            seconds_remaining: 0,
+            expires_at: 0,
+        }
+    }
+
+    pub fn of(pending: &PendingWithdraw, now: i64) -> Self {
+        let state = if pending.is_claimed() {
+            PendingState::Claimed
+        } else if pending.is_voided() {
+            PendingState::Voided
+        } else if pending.is_queued() {
+            PendingState::Queued
+        } else if now < pending.available_at {
+            PendingState::Waiting
+        } else if pending.is_expired(now) {
+            PendingState::Expired
+        } else {
+            PendingState::Ready
+        };
+
+        Self {
+            exists: true,
+            state,
+            amount: pending.amount,
+            denomination_index: PrivacyPool::denomination_index(pending.amount).map(|index| index as u8),
+            seconds_remaining: pending.available_at.saturating_sub(now).max(0),
+            expires_at: pending.expires_at,
+        }
+    }
+}
+
+/// Pool activity during one epoch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct EpochStats {
+    pub deposit_count: u64,    // 8 bytes
+    pub withdraw_count: u64,   // 8 bytes
+    pub churn_count: u64,      // 8 bytes
+    pub deposited: u64,        // 8 bytes - lamports
+    pub withdrawn: u64,        // 8 bytes - lamports
+}
+
+impl EpochStats {
+    pub const SPACE: usize = 8 + 8 + 8 + 8 + 8;
+
+    /// Activity of an ended `epoch`, given the current and previous
+    /// (epoch, stats) windows of one account
+    ///
+    /// Epochs between or after the windows had no activity; epochs before
+    /// the previous window are no longer retained.
+    pub fn retained(epoch: u64, current: (u64, EpochStats), previous: (u64, EpochStats)) -> Option<EpochStats> {
+        if epoch == current.0 {
+            Some(current.1)
+        } else if epoch > current.0 {
+            Some(EpochStats::default())
+        } else if epoch == previous.0 {
+            Some(previous.1)
+        } else if epoch > previous.0 {
+            Some(EpochStats::default())
+        } else {
+            None
+        }
+    }
+
+    /// Combined activity of two accounts over the same epoch
+    pub fn add(&self, other: &EpochStats) -> EpochStats {
+        EpochStats {
+            deposit_count: self.deposit_count.saturating_add(other.deposit_count),
+            withdraw_count: self.withdraw_count.saturating_add(other.withdraw_count),
+            churn_count: self.churn_count.saturating_add(other.churn_count),
+            deposited: self.deposited.saturating_add(other.deposited),
+            withdrawn: self.withdrawn.saturating_add(other.withdrawn),
+        }
+    }
+}
+
+/// Historical pool statistics for a single epoch
+/// PRIVACY: Aggregate counts only, same as the pool itself
+#[account]
+pub struct EpochSnapshot {
+    pub epoch: u64,            // 8 bytes
+    pub stats: EpochStats,     // 40 bytes - filled in on finalize
+    pub finalized: bool,       // 1 byte
+    pub finalized_at: i64,     // 8 bytes
+    pub bump: u8,              // 1 byte
+}
+
+impl EpochSnapshot {
+    pub const SPACE: usize = 8 +  // discriminator
+        8 +                        // epoch
+        EpochStats::SPACE +        // stats
+        1 +                        // finalized
+        8 +                        // finalized_at
+        1 +                        // bump
         16;                        // padding
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_shard() -> PoolShard {
+        PoolShard::try_deserialize_unchecked(&mut &[0u8; PoolShard::SPACE][..]).unwrap()
+    }
+
+    fn pool_with_outflow_limit(max_epoch_outflow: u64) -> PrivacyPool {
+        let mut pool = PrivacyPool::try_deserialize_unchecked(&mut &[0u8; PrivacyPool::SPACE][..]).unwrap();
+        pool.max_epoch_outflow = max_epoch_outflow;
+        pool
+    }
+
+    #[test]
+    fn books_withdrawals_per_epoch() {
+        let mut shard = empty_shard();
+        shard.credit_vault(1_000_000_000).unwrap();
+
+        shard.book_withdrawal(ALLOWED_AMOUNTS[1], 5).unwrap();
+        shard.book_withdrawal(ALLOWED_AMOUNTS[1], 5).unwrap();
+        assert_eq!(shard.vault_balance, 800_000_000);
+        assert_eq!((shard.outflow_epoch, shard.epoch_outflow), (5, 200_000_000));
+        assert_eq!((shard.total_withdrawn, shard.withdraw_count), (200_000_000, 2));
+        assert_eq!(shard.denomination_withdrawals[1], 2);
+        assert_eq!((shard.stats_epoch, shard.current_epoch_stats.withdraw_count), (5, 2));
+
+        // A new epoch restarts the outflow counter and keeps the last epoch's stats
+        shard.book_withdrawal(123, 6).unwrap();
+        assert_eq!((shard.outflow_epoch, shard.epoch_outflow), (6, 123));
+        assert_eq!(shard.denomination_withdrawals, [0, 2, 0, 0, 0, 0]);
+        assert_eq!(shard.finished_epoch_stats(5).unwrap().withdrawn, 200_000_000);
+        assert_eq!(shard.finished_epoch_stats(6).unwrap().withdrawn, 123);
+        assert!(shard.finished_epoch_stats(4).is_none());
+
+        assert!(shard.book_withdrawal(800_000_000, 6).is_err());
+    }
+
+    #[test]
+    fn limits_outflow_to_the_shard_share() {
+        let mut shard = empty_shard();
+        shard.credit_vault(1_000_000_000).unwrap();
+        shard.book_withdrawal(ALLOWED_AMOUNTS[1], 5).unwrap();
+
+        let pool = pool_with_outflow_limit(VAULT_SHARD_COUNT as u64 * 150_000_000);
+        assert!(shard.outflow_allows(&pool, 50_000_000, 5));
+        assert!(!shard.outflow_allows(&pool, 50_000_001, 5));
+        assert!(shard.outflow_allows(&pool, 150_000_000, 6));
+        assert!(shard.outflow_allows(&pool_with_outflow_limit(0), u64::MAX, 5));
+    }
+
+    #[test]
+    fn reserves_booked_balance() {
+        let mut shard = empty_shard();
+        shard.credit_vault(ALLOWED_AMOUNTS[2]).unwrap();
+
+        shard.reserve(ALLOWED_AMOUNTS[1]).unwrap();
+        assert_eq!(shard.unreserved_balance(), ALLOWED_AMOUNTS[2] - ALLOWED_AMOUNTS[1]);
+        shard.reserve(ALLOWED_AMOUNTS[2]).unwrap();
+        assert_eq!(shard.unreserved_balance(), 0);
+
+        shard.release_reservation(ALLOWED_AMOUNTS[2]);
+        shard.release_reservation(ALLOWED_AMOUNTS[2]);
+        assert_eq!(shard.total_reserved, 0);
+        assert!(shard.debit_vault(ALLOWED_AMOUNTS[2] + 1).is_err());
+    }
+}
@@ -9,7 +9,8 @@ use anchor_lang::prelude::*;
 /// Proves payment was received without revealing the amount
 ///
 /// Privacy Model:
-/// - commitment = hash(employee || batch || timestamp || amount || secret)
+/// - commitment = hash(tag || employee || batch || timestamp || amount || secret)
+///   (version 0 receipts omit the tag)
 /// - The employee keeps the secret
 /// - To prove payment: reveal (employee, batch, timestamp) + show receipt exists
 /// - To prove specific amount: reveal secret (optional, for full audits)
@@ -27,6 +28,7 @@ pub struct PaymentReceipt {
     pub timestamp: i64,             // 8 bytes - when payment was made
     pub receipt_index: u64,         // 8 bytes - unique index for this receipt
     pub bump: u8,                   // 1 byte
+    pub version: u8,                // 1 byte - commitment format, 0 for receipts made before tagging
 }
 
 impl PaymentReceipt {
@@ -38,5 +40,6 @@ impl PaymentReceipt {
         8 +                          // timestamp
         8 +                          // receipt_index
         1 +                          // bump
-        32;                          // padding
+        1 +                          // version
+        31;                          // padding
 }
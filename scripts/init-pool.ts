@@ -16,13 +16,12 @@ async function main() {
     [Buffer.from("privacy_pool")],
     PROGRAM_ID
   );
-  const [poolVaultPda] = PublicKey.findProgramAddressSync(
-    [Buffer.from("pool_vault")],
-    PROGRAM_ID
+  const [programDataPda] = PublicKey.findProgramAddressSync(
+    [PROGRAM_ID.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
   );
 
   console.log("Pool PDA:", poolPda.toString());
-  console.log("Pool Vault:", poolVaultPda.toString());
 
   // Init Privacy Pool
   try {
@@ -34,14 +33,45 @@ async function main() {
       .initPrivacyPool()
       .accounts({
         authority: provider.wallet.publicKey,
+        program: PROGRAM_ID,
+        programData: programDataPda,
         pool: poolPda,
-        poolVault: poolVaultPda,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
     console.log("✅ Pool initialized! Tx:", tx);
   }
 
+  // Init Vault Shards (deposits and withdrawals go through one of them)
+  for (let i = 0; i < 4; i++) {
+    const [poolShardPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_shard"), Buffer.from([i])],
+      PROGRAM_ID
+    );
+    const [poolVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_vault"), Buffer.from([i])],
+      PROGRAM_ID
+    );
+
+    try {
+      await program.account.poolShard.fetch(poolShardPda);
+      console.log(`✅ Pool Shard ${i} exists`);
+    } catch (e) {
+      console.log(`🔄 Init Pool Shard ${i}...`);
+      const tx = await program.methods
+        .initPoolShard(i)
+        .accounts({
+          payer: provider.wallet.publicKey,
+          pool: poolPda,
+          poolShard: poolShardPda,
+          poolVault: poolVaultPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      console.log(`✅ Shard ${i} done! Tx:`, tx);
+    }
+  }
+
   // Init Churn Vaults
   for (let i = 0; i < 3; i++) {
     const indexBuffer = Buffer.alloc(1);
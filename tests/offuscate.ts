@@ -1,16 +1,114 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
 import { Offuscate } from "../target/types/offuscate";
 
 describe("offuscate", () => {
   // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
 
   const program = anchor.workspace.offuscate as Program<Offuscate>;
 
-  it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
-    console.log("Your transaction signature", tx);
+  const AMOUNT = new anchor.BN(100_000_000); // 0.1 SOL, an allowed amount
+  const SHARD = 0;
+
+  const [pool] = PublicKey.findProgramAddressSync([Buffer.from("privacy_pool")], program.programId);
+  const [poolShard] = PublicKey.findProgramAddressSync(
+    [Buffer.from("pool_shard"), Buffer.from([SHARD])],
+    program.programId
+  );
+  const [poolVault] = PublicKey.findProgramAddressSync(
+    [Buffer.from("pool_vault"), Buffer.from([SHARD])],
+    program.programId
+  );
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
+
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  it("Initializes the pool and a vault shard", async () => {
+    await program.methods
+      .initPrivacyPool()
+      .accountsPartial({
+        authority: provider.wallet.publicKey,
+        program: program.programId,
+        programData,
+        pool,
+      })
+      .rpc();
+
+    await program.methods
+      .initPoolShard(SHARD)
+      .accountsPartial({ payer: provider.wallet.publicKey, pool, poolShard, poolVault })
+      .rpc();
+
+    const shard = await program.account.poolShard.fetch(poolShard);
+    assert.equal(shard.index, SHARD);
+  });
+
+  it("Posts an encrypted memo with a deposit", async () => {
+    const memo = Buffer.from(Keypair.generate().secretKey.subarray(0, 64));
+    const tx = await program.methods
+      .poolDeposit(AMOUNT, memo)
+      .accountsPartial({ depositor: provider.wallet.publicKey, pool, poolShard, poolVault })
+      .rpc({ commitment: "confirmed" });
+
+    const txInfo = await provider.connection.getTransaction(tx, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const events = [...new anchor.EventParser(program.programId, program.coder).parseLogs(txInfo.meta.logMessages)];
+    const depositMemo = events.find((event) => event.name === "depositMemo");
+    assert.isNull(depositMemo.data.commitment);
+    assert.deepEqual(Buffer.from(depositMemo.data.ciphertext), memo);
+  });
+
+  it("Deposits into a shard, then requests and claims from it in two parts", async () => {
+    await program.methods
+      .poolDeposit(AMOUNT, null)
+      .accountsPartial({ depositor: provider.wallet.publicKey, pool, poolShard, poolVault })
+      .rpc();
+
+    const recipient = Keypair.generate();
+    const [pendingWithdraw] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pending"), recipient.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .requestWithdraw(AMOUNT)
+      .accountsPartial({
+        payer: provider.wallet.publicKey,
+        recipient: recipient.publicKey,
+        pool,
+        poolShard,
+        pendingWithdraw,
+      })
+      .signers([recipient])
+      .rpc();
+
+    // Wait out the variable claim delay
+    const pending = await program.account.pendingWithdraw.fetch(pendingWithdraw);
+    while (Math.floor(Date.now() / 1000) <= pending.availableAt.toNumber()) {
+      await sleep(5_000);
+    }
+
+    // Fan the claim out into two fresh addresses
+    const outputs = [Keypair.generate().publicKey, Keypair.generate().publicKey];
+    const parts = [new anchor.BN(60_000_000), new anchor.BN(40_000_000)];
+    await program.methods
+      .claimWithdraw(parts)
+      .accountsPartial({ recipient: recipient.publicKey, pool, poolShard, poolVault, pendingWithdraw })
+      .remainingAccounts(outputs.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .signers([recipient])
+      .rpc();
+
+    for (const [i, output] of outputs.entries()) {
+      assert.equal(await provider.connection.getBalance(output), parts[i].toNumber());
+    }
   });
 });